@@ -44,6 +44,37 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         c.bench_function("tesselate", |b| {
             b.iter(|| ctx.tesselate(paint_commands.clone()))
         });
+
+        // What `tesselate` would cost without reusing the triangles of unchanged commands:
+        let options = egui::paint::TesselationOptions {
+            aa_size: 1.0 / ctx.pixels_per_point(),
+            ..Default::default()
+        };
+        c.bench_function("tesselate_uncached", |b| {
+            b.iter(|| {
+                egui::paint::tessellator::tessellate_paint_commands(
+                    paint_commands.clone(),
+                    options,
+                    ctx.fonts(),
+                )
+            })
+        });
+    }
+
+    {
+        let mut ctx = egui::Context::new();
+        ctx.memory().all_collpasing_are_open = true; // expand the demo window with everything
+        let mut demo_windows = egui::demos::DemoWindows::default();
+
+        // A full frame, where nothing changes from one frame to the next:
+        c.bench_function("demo_windows_full_tesselated", |b| {
+            b.iter(|| {
+                ctx.begin_frame(raw_input.clone());
+                demo_windows.ui(&ctx, &Default::default(), &mut None);
+                let (_, paint_commands) = ctx.end_frame();
+                ctx.tesselate(paint_commands)
+            })
+        });
     }
 
     {
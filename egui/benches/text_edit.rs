@@ -0,0 +1,89 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// About one megabyte of text, in many paragraphs.
+fn large_text() -> String {
+    let mut text = String::new();
+    while text.len() < 1_000_000 {
+        text += egui::demos::LOREM_IPSUM_LONG;
+        text += "\n\n";
+    }
+    text
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let raw_input = egui::RawInput {
+        screen_size: egui::vec2(1280.0, 1024.0),
+        ..Default::default()
+    };
+    let large_text = large_text();
+
+    {
+        // What `TextEdit` used to do (twice) for every frame: lay out all of the text.
+        let mut ctx = egui::Context::new();
+        ctx.begin_frame(raw_input.clone());
+        let font = &ctx.fonts()[egui::TextStyle::Body];
+
+        c.bench_function("layout_multiline_1mb", |b| {
+            b.iter(|| font.layout_multiline(large_text.clone(), 1000.0))
+        });
+        let _ = ctx.end_frame();
+    }
+
+    {
+        // The same keystroke as below, the way `TextEdit` used to handle it:
+        // insert at a char index and lay out all of the text before and after.
+        let mut ctx = egui::Context::new();
+        let mut text = large_text.clone();
+        let mut cursor = text.chars().count();
+
+        c.bench_function("text_edit_1mb_keystroke_full_relayout", |b| {
+            b.iter(|| {
+                ctx.begin_frame(raw_input.clone());
+                egui::CentralPanel::default().show(&ctx, |ui| {
+                    egui::ScrollArea::auto_sized().show(ui, |ui| {
+                        let text_style = egui::TextStyle::Body;
+                        let width = ui.available().width();
+                        let galley = ui.fonts()[text_style].layout_multiline(text.clone(), width);
+                        let rect = ui.allocate_space(galley.size);
+
+                        let mut new_text: String = text.chars().take(cursor).collect();
+                        new_text.push('x');
+                        new_text.extend(text.chars().skip(cursor));
+                        text = new_text;
+                        cursor += 1;
+
+                        let galley = ui.fonts()[text_style].layout_multiline(text.clone(), width);
+                        let color = ui.style().visuals.text_color();
+                        ui.painter().galley(rect.min, galley, text_style, color);
+                    });
+                });
+                ctx.end_frame()
+            })
+        });
+    }
+
+    {
+        // Typing one character per frame into a `TextEdit` in a `ScrollArea`:
+        let mut ctx = egui::Context::new();
+        let mut text = large_text.clone();
+        let id = egui::Id::new("large_text");
+        ctx.memory().request_kb_focus(id);
+
+        c.bench_function("text_edit_1mb_keystroke", |b| {
+            b.iter(|| {
+                let mut raw_input = raw_input.clone();
+                raw_input.events.push(egui::Event::Text("x".to_owned()));
+                ctx.begin_frame(raw_input);
+                egui::CentralPanel::default().show(&ctx, |ui| {
+                    egui::ScrollArea::auto_sized().show(ui, |ui| {
+                        ui.add(egui::TextEdit::new(&mut text).id(id));
+                    });
+                });
+                ctx.end_frame()
+            })
+        });
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
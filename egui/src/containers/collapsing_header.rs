@@ -233,8 +233,8 @@ impl CollapsingHeader {
             bg_index,
             PaintCmd::Rect {
                 rect: header_response.rect,
-                corner_radius: ui.style().interact(&header_response).corner_radius,
-                fill: ui.style().interact(&header_response).bg_fill,
+                corner_radius: ui.style().interact(&header_response).corner_radius.into(),
+                fill: ui.style().interact(&header_response).bg_fill.into(),
                 stroke: Default::default(),
             },
         );
@@ -1,4 +1,13 @@
-use crate::{paint::PaintCmd, style::WidgetVisuals, *};
+use crate::{paint::PaintCmd, style::WidgetVisuals, widgets::text_edit::find_ignoring_case, *};
+
+/// State of a `searchable_combo_box` while its popup is open.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct State {
+    filter: String,
+
+    /// Index into the matching items, moved with the arrow keys.
+    highlighted: Option<usize>,
+}
 
 pub fn combo_box_with_label(
     ui: &mut Ui,
@@ -22,6 +31,17 @@ pub fn combo_box(
     button_id: Id,
     selected: impl Into<Label>,
     menu_contents: impl FnOnce(&mut Ui),
+) -> Response {
+    combo_box_impl(ui, button_id, selected, true, menu_contents)
+}
+
+/// Like `combo_box`, but clicking inside the popup only closes it if `close_on_click_inside`.
+pub(crate) fn combo_box_impl(
+    ui: &mut Ui,
+    button_id: Id,
+    selected: impl Into<Label>,
+    close_on_click_inside: bool,
+    menu_contents: impl FnOnce(&mut Ui),
 ) -> Response {
     let popup_id = button_id.with("popup");
     let selected = selected.into();
@@ -50,24 +70,31 @@ pub fn combo_box(
     }
 
     if ui.memory().is_popup_open(popup_id) {
-        let parent_clip_rect = ui.clip_rect();
+        // The popup is in screen coordinates, also inside a `Scene`:
+        let parent_clip_rect = ui.to_screen_rect(ui.clip_rect());
+        let button_rect = ui.to_screen_rect(button_response.rect);
 
-        Area::new(popup_id)
+        let popup_response = Area::new(popup_id)
             .order(Order::Foreground)
-            .fixed_pos(button_response.rect.left_bottom())
+            .fixed_pos(button_rect.left_bottom())
             .show(ui.ctx(), |ui| {
                 ui.set_clip_rect(parent_clip_rect); // for when the combo-box is in a scroll area.
                 let frame = Frame::popup(ui.style());
                 let frame_margin = frame.margin;
                 frame.show(ui, |ui| {
                     ui.with_layout(Layout::justified(Direction::Vertical), |ui| {
-                        ui.set_min_width(button_response.rect.width() - 2.0 * frame_margin.x);
+                        ui.set_min_width(button_rect.width() - 2.0 * frame_margin.x);
                         menu_contents(ui);
                     });
                 })
             });
 
-        if ui.input().key_pressed(Key::Escape) || ui.input().mouse.click && !button_response.clicked
+        let clicked_inside = ui.input().mouse.click
+            && matches!(ui.ctx().input().mouse.pos, Some(pos) if popup_response.rect.contains(pos));
+        if ui.input().key_pressed(Key::Escape)
+            || ui.input().mouse.click
+                && !button_response.clicked
+                && (close_on_click_inside || !clicked_inside)
         {
             ui.memory().close_popup();
         }
@@ -76,6 +103,180 @@ pub fn combo_box(
     button_response
 }
 
+pub fn searchable_combo_box_with_label(
+    ui: &mut Ui,
+    label: impl Into<Label>,
+    selected: &mut usize,
+    items: &[impl AsRef<str>],
+) -> Response {
+    let label = label.into();
+    let button_id = ui.make_persistent_id(label.text());
+
+    ui.horizontal(|ui| {
+        let mut response = searchable_combo_box(ui, button_id, selected, items);
+        response |= ui.add(label);
+        response
+    })
+    .0
+}
+
+/// Beyond this height, the items of a `searchable_combo_box` scroll.
+const SEARCHABLE_POPUP_MAX_HEIGHT: f32 = 200.0;
+
+/// A combo box for choosing one of many items, e.g. font names or asset ids.
+///
+/// The popup starts with a search field, which gets keyboard focus,
+/// followed by the items containing the search text (ignoring case).
+/// Pick an item with the mouse, or with the arrow keys and enter.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// let fonts = ["Comfortaa", "Inconsolata", "ProggyClean", "Ubuntu"];
+/// let mut selected = 0;
+/// egui::searchable_combo_box_with_label(&mut ui, "Font", &mut selected, &fonts);
+/// ```
+pub fn searchable_combo_box(
+    ui: &mut Ui,
+    button_id: Id,
+    selected: &mut usize,
+    items: &[impl AsRef<str>],
+) -> Response {
+    let popup_id = button_id.with("popup");
+    let selected_text = items.get(*selected).map_or("", |item| item.as_ref());
+
+    let response = combo_box_impl(ui, button_id, selected_text, false, |ui| {
+        let old_state = ui.memory().combo_box.remove(&button_id);
+        let just_opened = old_state.is_none();
+        let mut state = old_state.unwrap_or_default();
+
+        // The arrow keys and enter pick an item, instead of moving around in the search field:
+        let navigation_keys: Vec<Key> = ui
+            .input()
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Key { key, pressed: true } if is_navigation_key(*key) => Some(*key),
+                _ => None,
+            })
+            .collect();
+        ui.consume_events(
+            |event| matches!(event, Event::Key { key, .. } if is_navigation_key(*key)),
+        );
+
+        let filter_id = button_id.with("filter");
+        let old_filter = state.filter.clone();
+        ui.add(
+            TextEdit::new(&mut state.filter)
+                .id(filter_id)
+                .multiline(false)
+                .hint_text("Search"),
+        );
+        if just_opened {
+            // After adding it, so the click that opened us does not take the focus away again:
+            ui.memory().request_kb_focus(filter_id);
+        }
+
+        let matching: Vec<usize> = (0..items.len())
+            .filter(|&i| find_ignoring_case(items[i].as_ref(), &state.filter).is_some())
+            .collect();
+
+        if just_opened {
+            state.highlighted = matching.iter().position(|&i| i == *selected);
+        } else if state.filter != old_filter {
+            state.highlighted = None;
+        }
+
+        let mut moved_highlight = false;
+        for key in navigation_keys {
+            match key {
+                Key::Down if !matching.is_empty() => {
+                    let last = matching.len() - 1;
+                    state.highlighted = Some(state.highlighted.map_or(0, |h| (h + 1).min(last)));
+                    moved_highlight = true;
+                }
+                Key::Up => {
+                    state.highlighted = state.highlighted.map(|h| h.saturating_sub(1));
+                    moved_highlight = true;
+                }
+                Key::Enter => {
+                    if let Some(&i) = state.highlighted.and_then(|h| matching.get(h)) {
+                        *selected = i;
+                        ui.memory().close_popup();
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Scroll to keep the highlighted item in view:
+        let scroll_id = ui.make_persistent_id("scroll_area");
+        let mut scroll_delta = 0.0;
+
+        ScrollArea::from_max_height(SEARCHABLE_POPUP_MAX_HEIGHT).show(ui, |ui| {
+            for (row, &i) in matching.iter().enumerate() {
+                let where_to_put_background = ui.painter().add(PaintCmd::Noop);
+                let rect = ui.add(Label::new(items[i].as_ref())).rect.expand(1.0);
+                let response = ui.interact(rect, button_id.with(i), Sense::click());
+
+                let is_highlighted = state.highlighted == Some(row);
+                let fill = if is_highlighted || response.hovered {
+                    Some(ui.style().visuals.widgets.hovered.bg_fill)
+                } else if i == *selected {
+                    Some(ui.style().visuals.widgets.inactive.bg_fill)
+                } else {
+                    None
+                };
+                if let Some(fill) = fill {
+                    ui.painter().set(
+                        where_to_put_background,
+                        PaintCmd::Rect {
+                            rect,
+                            corner_radius: ui.style().visuals.widgets.hovered.corner_radius.into(),
+                            fill: fill.into(),
+                            stroke: Default::default(),
+                        },
+                    );
+                }
+
+                if is_highlighted && moved_highlight {
+                    let clip_rect = ui.clip_rect();
+                    if rect.top() < clip_rect.top() {
+                        scroll_delta = rect.top() - clip_rect.top();
+                    } else if rect.bottom() > clip_rect.bottom() {
+                        scroll_delta = rect.bottom() - clip_rect.bottom();
+                    }
+                }
+
+                if response.clicked {
+                    *selected = i;
+                    ui.memory().close_popup();
+                }
+            }
+        });
+
+        if scroll_delta != 0.0 {
+            if let Some(scroll_state) = ui.memory().scroll_areas.get_mut(&scroll_id) {
+                scroll_state.offset.y += scroll_delta;
+            }
+            ui.ctx().request_repaint();
+        }
+
+        ui.memory().combo_box.insert(button_id, state);
+    });
+
+    if !ui.memory().is_popup_open(popup_id) {
+        ui.memory().combo_box.remove(&button_id);
+    }
+
+    response
+}
+
+/// The keys of the search field of a `searchable_combo_box` that pick an item instead.
+fn is_navigation_key(key: Key) -> bool {
+    matches!(key, Key::Up | Key::Down | Key::Enter)
+}
+
 fn button_frame(
     ui: &mut Ui,
     id: Id,
@@ -100,8 +301,8 @@ fn button_frame(
         where_to_put_background,
         PaintCmd::Rect {
             rect: outer_rect,
-            corner_radius: visuals.corner_radius,
-            fill: visuals.bg_fill,
+            corner_radius: visuals.corner_radius.into(),
+            fill: visuals.bg_fill.into(),
             stroke: visuals.bg_stroke,
         },
     );
@@ -121,3 +322,87 @@ fn paint_icon(painter: &Painter, rect: Rect, visuals: &WidgetVisuals) {
         visuals.fg_stroke,
     ));
 }
+
+#[test]
+fn test_popup_in_transformed_ui() {
+    let mut ctx = Context::new();
+    let mut raw_input = RawInput {
+        screen_size: vec2(800.0, 600.0),
+        ..Default::default()
+    };
+    let button_id = Id::new("combo");
+    let mut button_rect = Rect::nothing();
+    // Lay out, hover, press, release:
+    for &(mouse_over_button, mouse_down) in
+        &[(false, false), (true, false), (true, true), (true, false)]
+    {
+        raw_input.mouse_pos = Some(button_rect.center()).filter(|_| mouse_over_button);
+        raw_input.mouse_down = mouse_down;
+        ctx.begin_frame(raw_input.clone());
+        Area::new("transformed")
+            .fixed_pos(pos2(0.0, 0.0))
+            .show(&ctx, |ui| {
+                let mut transformed_ui = ui.child_ui(ui.max_rect(), Layout::default());
+                transformed_ui.add_transform(Transform::new(2.0, vec2(100.0, 50.0)));
+                let response = combo_box(&mut transformed_ui, button_id, "Pick one", |ui| {
+                    ui.label("The one");
+                });
+                button_rect = transformed_ui.to_screen_rect(response.rect);
+                ui.allocate_space(vec2(600.0, 400.0)); // So the area covers the button on screen
+            });
+        let _ = ctx.end_frame();
+    }
+    let popup_id = button_id.with("popup");
+    assert!(ctx.memory().is_popup_open(popup_id));
+    let popup_pos = ctx.memory().areas.get(Id::new(popup_id)).unwrap().pos;
+    assert_eq!(popup_pos, button_rect.left_bottom());
+}
+
+#[test]
+fn test_searchable_combo_box_keys() {
+    let key = |key| Event::Key { key, pressed: true };
+    let mut ctx = Context::new();
+    let items = ["Comfortaa", "Inconsolata", "ProggyClean", "Ubuntu"];
+    let mut selected = 0;
+    let button_id = Id::new("fonts");
+    let popup_id = button_id.with("popup");
+    let filter_id = button_id.with("filter");
+    ctx.memory().open_popup(popup_id);
+    let mut run_frame = |events: Vec<Event>| {
+        ctx.begin_frame(RawInput {
+            screen_size: vec2(800.0, 600.0),
+            events,
+            ..Default::default()
+        });
+        Area::new("combo").show(&ctx, |ui| {
+            searchable_combo_box(ui, button_id, &mut selected, &items);
+        });
+        let _ = ctx.end_frame();
+        let mut memory = ctx.memory();
+        let highlighted = memory.combo_box.get(&button_id).and_then(|s| s.highlighted);
+        let filter_cursor = memory.text_edit.get(&filter_id).and_then(|s| s.cursor);
+        let is_searching = memory.is_popup_open(popup_id) && memory.has_kb_focus(filter_id);
+        (items[selected], is_searching, highlighted, filter_cursor)
+    };
+
+    let (_, is_searching, highlighted, _) = run_frame(vec![]);
+    assert!(is_searching);
+    assert_eq!(highlighted, Some(0));
+
+    // Not in "Comfortaa", so nothing is highlighted:
+    let (_, _, highlighted, _) = run_frame(vec![Event::Text("n".to_owned())]);
+    assert_eq!(highlighted, None);
+    // Enter without a highlighted item keeps searching:
+    let (_, is_searching, _, _) = run_frame(vec![key(Key::Enter)]);
+    assert!(is_searching);
+
+    // The arrow keys move the highlight, not the cursor in the search field:
+    let events = vec![key(Key::Down), key(Key::Down), key(Key::Down), key(Key::Up)];
+    let (_, _, highlighted, filter_cursor) = run_frame(events);
+    assert_eq!(highlighted, Some(1));
+    assert_eq!(filter_cursor, Some(1));
+
+    let (selected, is_searching, _, _) = run_frame(vec![key(Key::Enter)]);
+    assert_eq!(selected, "ProggyClean");
+    assert!(!is_searching);
+}
@@ -7,27 +7,31 @@ use crate::{layers::PaintCmdIdx, paint::*, *};
 pub struct Frame {
     // On each side
     pub margin: Vec2,
-    pub corner_radius: f32,
-    pub fill: Srgba,
+    pub corner_radius: CornerRadius,
+    pub fill: Fill,
     pub stroke: Stroke,
+    /// Painted beneath the frame.
+    pub shadow: Shadow,
 }
 
 impl Frame {
     pub fn none() -> Self {
         Self {
             margin: Vec2::zero(),
-            corner_radius: 0.0,
+            corner_radius: 0.0.into(),
             fill: Default::default(),
             stroke: Stroke::none(),
+            shadow: Default::default(),
         }
     }
 
     pub fn window(style: &Style) -> Self {
         Self {
             margin: style.spacing.window_padding,
-            corner_radius: style.visuals.window_corner_radius,
-            fill: style.visuals.widgets.noninteractive.bg_fill,
+            corner_radius: style.visuals.window_corner_radius.into(),
+            fill: style.visuals.widgets.noninteractive.bg_fill.into(),
             stroke: style.visuals.widgets.inactive.bg_stroke, // because we can resize windows
+            shadow: style.visuals.window_shadow,
         }
     }
 
@@ -35,9 +39,10 @@ impl Frame {
     pub fn dark_canvas(style: &Style) -> Self {
         Self {
             margin: Vec2::new(10.0, 10.0),
-            corner_radius: 5.0,
-            fill: Srgba::black_alpha(250),
+            corner_radius: 5.0.into(),
+            fill: Srgba::black_alpha(250).into(),
             stroke: style.visuals.widgets.noninteractive.bg_stroke,
+            shadow: Default::default(),
         }
     }
 
@@ -45,41 +50,52 @@ impl Frame {
     pub fn background(style: &Style) -> Self {
         Self {
             margin: Vec2::new(8.0, 8.0),
-            corner_radius: 0.0,
-            fill: style.visuals.widgets.noninteractive.bg_fill,
+            corner_radius: 0.0.into(),
+            fill: style.visuals.widgets.noninteractive.bg_fill.into(),
             stroke: Default::default(),
+            shadow: Default::default(),
         }
     }
 
     pub(crate) fn panel(style: &Style) -> Self {
         Self {
             margin: Vec2::new(8.0, 2.0),
-            corner_radius: 0.0,
-            fill: style.visuals.widgets.noninteractive.bg_fill,
+            corner_radius: 0.0.into(),
+            fill: style.visuals.widgets.noninteractive.bg_fill.into(),
             stroke: style.visuals.widgets.noninteractive.bg_stroke,
+            shadow: Default::default(),
         }
     }
 
     pub fn menu(style: &Style) -> Self {
         Self {
             margin: Vec2::splat(1.0),
-            corner_radius: 2.0,
-            fill: style.visuals.widgets.noninteractive.bg_fill,
+            corner_radius: 2.0.into(),
+            fill: style.visuals.widgets.noninteractive.bg_fill.into(),
             stroke: style.visuals.widgets.noninteractive.bg_stroke,
+            shadow: style.visuals.popup_shadow,
         }
     }
 
     pub fn popup(style: &Style) -> Self {
         Self {
             margin: style.spacing.window_padding,
-            corner_radius: 5.0,
-            fill: style.visuals.widgets.noninteractive.bg_fill,
+            corner_radius: 5.0.into(),
+            fill: style.visuals.widgets.noninteractive.bg_fill.into(),
             stroke: style.visuals.widgets.noninteractive.bg_stroke,
+            shadow: style.visuals.popup_shadow,
         }
     }
 
-    pub fn fill(mut self, fill: Srgba) -> Self {
-        self.fill = fill;
+    /// The background, which can be a gradient.
+    pub fn fill(mut self, fill: impl Into<Fill>) -> Self {
+        self.fill = fill.into();
+        self
+    }
+
+    /// Each corner can have a different radius, e.g. for a tab.
+    pub fn corner_radius(mut self, corner_radius: impl Into<CornerRadius>) -> Self {
+        self.corner_radius = corner_radius.into();
         self
     }
 
@@ -87,11 +103,17 @@ impl Frame {
         self.stroke = stroke;
         self
     }
+
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = shadow;
+        self
+    }
 }
 
 pub struct Prepared {
     pub frame: Frame,
     outer_rect_bounds: Rect,
+    where_to_put_shadow: Option<PaintCmdIdx>,
     where_to_put_background: PaintCmdIdx,
     pub content_ui: Ui,
 }
@@ -100,11 +122,17 @@ impl Frame {
     pub fn begin(self, ui: &mut Ui) -> Prepared {
         let outer_rect_bounds = ui.available();
         let inner_rect = outer_rect_bounds.shrink2(self.margin);
+        let where_to_put_shadow = if self.shadow.is_visible() {
+            Some(ui.painter().add(PaintCmd::Noop))
+        } else {
+            None
+        };
         let where_to_put_background = ui.painter().add(PaintCmd::Noop);
         let content_ui = ui.child_ui(inner_rect, *ui.layout());
         Prepared {
             frame: self,
             outer_rect_bounds,
+            where_to_put_shadow,
             where_to_put_background,
             content_ui,
         }
@@ -131,10 +159,22 @@ impl Prepared {
 
         let Prepared {
             frame,
+            where_to_put_shadow,
             where_to_put_background,
             ..
         } = self;
 
+        if let Some(where_to_put_shadow) = where_to_put_shadow {
+            // The shadow may reach outside of where the frame itself is clipped,
+            // e.g. above and to the left of a window:
+            let mut painter = ui.painter().clone();
+            painter.set_clip_rect(painter.clip_rect().expand(frame.shadow.reach()));
+            painter.set(
+                where_to_put_shadow,
+                PaintCmd::triangles(frame.shadow.tessellate(outer_rect, frame.corner_radius)),
+            );
+        }
+
         ui.painter().set(
             where_to_put_background,
             PaintCmd::Rect {
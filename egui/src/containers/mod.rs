@@ -4,11 +4,13 @@
 
 pub(crate) mod area;
 pub(crate) mod collapsing_header;
-mod combo_box;
+pub(crate) mod combo_box;
 pub(crate) mod frame;
+pub(crate) mod node_graph;
 pub(crate) mod panel;
 pub(crate) mod popup;
 pub(crate) mod resize;
+pub(crate) mod scene;
 pub(crate) mod scroll_area;
 pub(crate) mod window;
 
@@ -17,9 +19,11 @@ pub use {
     collapsing_header::*,
     combo_box::*,
     frame::Frame,
+    node_graph::{Node, NodeGraph, NodeGraphEvent, NodeGraphUi, NodeLink, Pin},
     panel::{CentralPanel, SidePanel, TopPanel},
     popup::*,
     resize::Resize,
+    scene::Scene,
     scroll_area::ScrollArea,
     window::Window,
 };
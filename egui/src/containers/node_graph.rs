@@ -0,0 +1,799 @@
+//! A pannable, zoomable canvas of nodes connected by links, e.g. for shader graphs.
+//!
+//! The graph model (which nodes and links exist) is owned by the caller.
+//! `NodeGraph` only remembers where the nodes are and how the view is panned and zoomed,
+//! and reports what the user wants to change as `NodeGraphEvent`s.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::{paint::*, *};
+
+/// Radius of the circles of the pins, in canvas points.
+const PIN_RADIUS: f32 = 5.0;
+
+/// How close (in screen points) the mouse must be to a link to select it.
+const LINK_SELECT_DISTANCE: f32 = 4.0;
+
+/// An input pin is `(node, false, index)`, an output pin is `(node, true, index)`.
+type PinKey = (Id, bool, usize);
+
+/// State that is persisted between frames
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub(crate) struct State {
+    /// Screen-space offset of the canvas origin from the top left of the graph.
+    pan: Vec2,
+    zoom: f32,
+    /// Canvas-space position of the top left corner of each node.
+    node_pos: HashMap<Id, Pos2>,
+
+    /// Inner width (in canvas points) of each node last frame. Used to right-align the output pins.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    node_widths: HashMap<Id, f32>,
+    /// Canvas-space offset of each pin from the top left corner of its node, from last frame.
+    /// Used to paint the links below the nodes.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pin_offsets: HashMap<PinKey, Vec2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    selected_nodes: HashSet<Id>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    selected_link: Option<(PinKey, PinKey)>,
+    /// The pin we are dragging a new link from.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    new_link_from: Option<PinKey>,
+    /// Canvas-space start of an ongoing box selection.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    box_select_start: Option<Pos2>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            pan: Vec2::zero(),
+            zoom: 1.0,
+            node_pos: Default::default(),
+            node_widths: Default::default(),
+            pin_offsets: Default::default(),
+            selected_nodes: Default::default(),
+            selected_link: None,
+            new_link_from: None,
+            box_select_start: None,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A pin of a node in a `NodeGraph`: which node, and which of its inputs or outputs.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Pin<K> {
+    pub node: K,
+    pub index: usize,
+}
+
+/// A link from an output pin to an input pin.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct NodeLink<K> {
+    pub output: Pin<K>,
+    pub input: Pin<K>,
+}
+
+/// Something the user did to a `NodeGraph` that you should apply to your graph model.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NodeGraphEvent<K> {
+    /// The user dragged a new link between two pins.
+    Connect(NodeLink<K>),
+    /// The user selected this link and pressed delete.
+    Disconnect(NodeLink<K>),
+}
+
+/// A node to add to a `NodeGraph` with `NodeGraphUi::node`.
+#[derive(Clone, Debug)]
+pub struct Node<K> {
+    key: K,
+    title: String,
+    default_pos: Pos2,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl<K> Node<K> {
+    /// `key` identifies the node in your graph model, e.g. an index or an id.
+    pub fn new(key: K, title: impl Into<String>) -> Self {
+        Self {
+            key,
+            title: title.into(),
+            default_pos: Pos2::default(),
+            inputs: vec![],
+            outputs: vec![],
+        }
+    }
+
+    /// Where the node is placed on the canvas the first time it is shown.
+    pub fn default_pos(mut self, default_pos: impl Into<Pos2>) -> Self {
+        self.default_pos = default_pos.into();
+        self
+    }
+
+    /// Add an input pin, shown on the left side of the node.
+    pub fn input(mut self, label: impl Into<String>) -> Self {
+        self.inputs.push(label.into());
+        self
+    }
+
+    /// Add an output pin, shown on the right side of the node.
+    pub fn output(mut self, label: impl Into<String>) -> Self {
+        self.outputs.push(label.into());
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A pannable, zoomable canvas for editing a graph of nodes and links.
+///
+/// * Drag a node to move it, click or shift-drag the background to select nodes.
+/// * Drag from one pin to another to connect them.
+/// * Click a link to select it, and press delete to disconnect it.
+/// * Drag the background or use the scroll wheel to pan, and ctrl-scroll to zoom.
+///
+/// The nodes are laid out on the canvas with `Ui::add_transform`, so zooming scales everything:
+/// the nodes, their text and pins, and the links between them.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// use egui::{Node, NodeGraph, NodeGraphEvent, NodeLink, Pin};
+/// let mut links = vec![NodeLink { output: Pin { node: 0, index: 0 }, input: Pin { node: 1, index: 0 } }];
+/// let (_response, events) = NodeGraph::new("graph").show(&mut ui, &links, |graph| {
+///     graph.node(Node::new(0, "Time").output("t"), |_ui| {});
+///     graph.node(Node::new(1, "Sin").default_pos([150.0, 0.0]).input("x").output("sin(x)"), |_ui| {});
+/// });
+/// for event in events {
+///     match event {
+///         NodeGraphEvent::Connect(link) => links.push(link),
+///         NodeGraphEvent::Disconnect(link) => links.retain(|&l| l != link),
+///     }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct NodeGraph {
+    id_source: Id,
+    desired_size: Option<Vec2>,
+}
+
+impl NodeGraph {
+    pub fn new(id_source: impl Hash) -> Self {
+        Self {
+            id_source: Id::new(id_source),
+            desired_size: None,
+        }
+    }
+
+    /// Default: all available space.
+    pub fn desired_size(mut self, desired_size: Vec2) -> Self {
+        self.desired_size = Some(desired_size);
+        self
+    }
+
+    /// `links` are the links in your graph model.
+    /// Add the nodes with `NodeGraphUi::node` in `add_nodes`.
+    pub fn show<K: Copy + Eq + Hash>(
+        self,
+        ui: &mut Ui,
+        links: &[NodeLink<K>],
+        add_nodes: impl FnOnce(&mut NodeGraphUi<'_, K>),
+    ) -> (Response, Vec<NodeGraphEvent<K>>) {
+        let id = ui.make_persistent_id(self.id_source);
+        let desired_size = self
+            .desired_size
+            .unwrap_or_else(|| ui.available_finite().size());
+        let rect = ui.allocate_space(desired_size);
+        let mut state = ui.memory().node_graph.remove(&id).unwrap_or_default();
+
+        let style = ui.style().clone();
+        let visuals = &style.visuals;
+        let painter = ui.painter().sub_region(rect);
+        painter.rect_filled(rect, 0.0, visuals.dark_bg_color);
+        paint_grid(&painter, visuals, rect, rect.min + state.pan, state.zoom);
+
+        // The nodes and links are in canvas coordinates:
+        let mut canvas_ui = ui.child_ui(rect, Layout::vertical(Align::Min));
+        canvas_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+        let origin = rect.min + state.pan;
+        canvas_ui.add_transform(Transform::new(state.zoom, origin.to_vec2()));
+
+        let mut graph_ui = NodeGraphUi {
+            ui: &mut canvas_ui,
+            graph_id: id,
+            state: &mut state,
+            keys: HashMap::new(),
+            node_rects: vec![],
+            pins: vec![],
+            node_drag_delta: Vec2::zero(),
+        };
+
+        // Paint the links below the nodes, from where the pins were last frame:
+        let mut link_points: Vec<(NodeLink<K>, Vec<Pos2>)> = vec![];
+        for &link in links {
+            let (output, input) = (
+                graph_ui.pin_key(link.output, true),
+                graph_ui.pin_key(link.input, false),
+            );
+            if let (Some(from), Some(to)) = (graph_ui.pin_pos(output), graph_ui.pin_pos(input)) {
+                let bezier = link_bezier(from, to);
+                let selected = graph_ui.state.selected_link == Some((output, input));
+                let stroke = link_stroke(graph_ui.ui, selected);
+                graph_ui.ui.painter().cubic_bezier(bezier, stroke);
+                link_points.push((link, flatten_link(bezier)));
+            } else {
+                graph_ui.ui.ctx().request_repaint(); // we will know where the pins are next frame
+            }
+        }
+
+        add_nodes(&mut graph_ui);
+
+        let NodeGraphUi {
+            keys,
+            node_rects,
+            pins,
+            node_drag_delta,
+            ..
+        } = graph_ui;
+        let mut events = vec![];
+        // In canvas coordinates:
+        let input = canvas_ui.input();
+        let mouse_pos = input.mouse.pos;
+        let mut interacted = false;
+
+        // Moving the selected nodes:
+        if node_drag_delta != Vec2::zero() {
+            for node in &state.selected_nodes {
+                if let Some(pos) = state.node_pos.get_mut(node) {
+                    *pos += node_drag_delta;
+                }
+            }
+        }
+
+        // Dragging a new link:
+        if let Some(from) = state.new_link_from {
+            let from_pos = pins
+                .iter()
+                .find(|(pin, _)| *pin == from)
+                .map(|(_, pos)| *pos);
+            if let (Some(from_pos), Some(mouse_pos)) = (from_pos, mouse_pos) {
+                let (is_output, node) = (from.1, from.0);
+                let target = pins
+                    .iter()
+                    .filter(|(pin, pos)| {
+                        pin.0 != node
+                            && pin.1 != is_output
+                            && pos.distance(mouse_pos) < 2.0 * PIN_RADIUS
+                    })
+                    .map(|(pin, pos)| (*pin, *pos))
+                    .next();
+                let to_pos = target.map_or(mouse_pos, |(_, pos)| pos);
+                let bezier = if is_output {
+                    link_bezier(from_pos, to_pos)
+                } else {
+                    link_bezier(to_pos, from_pos)
+                };
+                canvas_ui
+                    .painter()
+                    .cubic_bezier(bezier, link_stroke(&canvas_ui, true));
+
+                if input.mouse.released {
+                    if let Some(to) = target {
+                        let (output, input) = if is_output {
+                            (from, to.0)
+                        } else {
+                            (to.0, from)
+                        };
+                        if let (Some(&output_node), Some(&input_node)) =
+                            (keys.get(&output.0), keys.get(&input.0))
+                        {
+                            events.push(NodeGraphEvent::Connect(NodeLink {
+                                output: Pin {
+                                    node: output_node,
+                                    index: output.2,
+                                },
+                                input: Pin {
+                                    node: input_node,
+                                    index: input.2,
+                                },
+                            }));
+                        }
+                    }
+                }
+            }
+            if !input.mouse.down || input.mouse.released {
+                state.new_link_from = None;
+            }
+            interacted = true;
+        }
+
+        // The background, after the nodes so they get the clicks first:
+        let response = ui.interact(rect, id, Sense::click_and_drag());
+        if response.active {
+            interacted = true;
+            let box_selecting = state.box_select_start.is_some();
+            if input.mouse.pressed && input.modifiers.shift {
+                state.box_select_start = mouse_pos;
+            } else if !box_selecting {
+                state.pan += ui.input().mouse.delta;
+            }
+        }
+        if let Some(start) = state.box_select_start {
+            if let Some(mouse_pos) = mouse_pos {
+                let box_rect = Rect::from_min_max(
+                    pos2(start.x.min(mouse_pos.x), start.y.min(mouse_pos.y)),
+                    pos2(start.x.max(mouse_pos.x), start.y.max(mouse_pos.y)),
+                );
+                let selection_color = Rgba::from(visuals.widgets.active.fg_fill).multiply(0.25);
+                canvas_ui.painter().rect(
+                    box_rect,
+                    0.0,
+                    selection_color,
+                    visuals.widgets.active.fg_stroke.dashed(4.0, 3.0),
+                );
+                if input.mouse.released {
+                    state.selected_nodes = node_rects
+                        .iter()
+                        .filter(|(_, rect)| rect.intersects(box_rect))
+                        .map(|(node, _)| *node)
+                        .collect();
+                    state.selected_link = None;
+                }
+            }
+            if !input.mouse.down || input.mouse.released {
+                state.box_select_start = None;
+            }
+        }
+        if response.clicked {
+            state.selected_nodes.clear();
+            state.selected_link = mouse_pos.and_then(|mouse_pos| {
+                link_points
+                    .iter()
+                    .find(|(_, points)| {
+                        distance_to_line(mouse_pos, points) < LINK_SELECT_DISTANCE / state.zoom
+                    })
+                    .map(|(link, _)| {
+                        (
+                            pin_key(id, link.output, true),
+                            pin_key(id, link.input, false),
+                        )
+                    })
+            });
+        }
+
+        // Panning and zooming with the scroll wheel, in screen coordinates:
+        let input = ui.input();
+        if let Some(mouse_pos) = input.mouse.pos {
+            if rect.contains(mouse_pos) && input.scroll_delta != Vec2::zero() {
+                if input.modifiers.ctrl {
+                    let canvas_pos = (mouse_pos - rect.min - state.pan) / state.zoom;
+                    state.zoom = clamp(
+                        state.zoom * (1.0 + input.scroll_delta.y / 200.0),
+                        0.1..=10.0,
+                    );
+                    state.pan = mouse_pos - rect.min - canvas_pos * state.zoom;
+                } else {
+                    state.pan += input.scroll_delta;
+                }
+            }
+        }
+
+        // Deleting the selected link:
+        let mut memory = ui.memory();
+        if interacted {
+            memory.request_kb_focus(id);
+        } else if input.mouse.click {
+            memory.surrender_kb_focus(id);
+        }
+        if memory.has_kb_focus(id) && input.key_pressed(Key::Delete) {
+            if let Some((output, input)) = state.selected_link.take() {
+                let link = links.iter().find(|link| {
+                    pin_key(id, link.output, true) == output
+                        && pin_key(id, link.input, false) == input
+                });
+                if let Some(&link) = link {
+                    events.push(NodeGraphEvent::Disconnect(link));
+                }
+            }
+        }
+        memory.node_graph.insert(id, state);
+
+        (response, events)
+    }
+}
+
+/// Add nodes to a `NodeGraph` with this.
+pub struct NodeGraphUi<'a, K> {
+    ui: &'a mut Ui,
+    graph_id: Id,
+    state: &'a mut State,
+    keys: HashMap<Id, K>,
+    node_rects: Vec<(Id, Rect)>,
+    /// Canvas-space position of each pin this frame.
+    pins: Vec<(PinKey, Pos2)>,
+    node_drag_delta: Vec2,
+}
+
+impl<'a, K: Copy + Eq + Hash> NodeGraphUi<'a, K> {
+    /// Show a node, with `add_contents` between its inputs and outputs.
+    pub fn node(&mut self, node: Node<K>, add_contents: impl FnOnce(&mut Ui)) -> Response {
+        let Node {
+            key,
+            title,
+            default_pos,
+            inputs,
+            outputs,
+        } = node;
+        let node_id = self.node_id(key);
+        self.keys.insert(node_id, key);
+
+        let node_pos = *self.state.node_pos.entry(node_id).or_insert(default_pos);
+        let padding = self.ui.style().spacing.window_padding;
+        let width = self.state.node_widths.get(&node_id).copied();
+        let width = width.unwrap_or(self.ui.style().spacing.interact_size.x);
+        let where_to_put_background = self.ui.painter().add(PaintCmd::Noop);
+
+        let inner_rect = Rect::from_min_size(node_pos + padding, vec2(width, f32::INFINITY));
+        let mut node_ui = self.ui.child_ui(inner_rect, Layout::vertical(Align::Min));
+        let title_rect = node_ui
+            .add(
+                Label::new(title)
+                    .multiline(false)
+                    .text_style(TextStyle::Button),
+            )
+            .rect;
+        let input_ys: Vec<f32> = inputs
+            .into_iter()
+            .map(|label| {
+                node_ui
+                    .add(Label::new(label).multiline(false))
+                    .rect
+                    .center()
+                    .y
+            })
+            .collect();
+        add_contents(&mut node_ui);
+        let output_ys: Vec<f32> = node_ui
+            .with_layout(Layout::vertical(Align::Max), |ui| {
+                outputs
+                    .into_iter()
+                    .map(|label| ui.add(Label::new(label).multiline(false)).rect.center().y)
+                    .collect()
+            })
+            .0;
+        let inner_rect = node_ui.min_rect();
+        if inner_rect.width() != width {
+            // Align the outputs to the new width next frame:
+            self.state.node_widths.insert(node_id, inner_rect.width());
+            self.ui.ctx().request_repaint();
+        }
+        let node_rect = inner_rect.expand2(padding);
+
+        // Pins:
+        let pins = input_ys
+            .into_iter()
+            .enumerate()
+            .map(|(i, y)| ((node_id, false, i), pos2(node_rect.left(), y)))
+            .chain(
+                output_ys
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, y)| ((node_id, true, i), pos2(node_rect.right(), y))),
+            );
+        for (pin, pos) in pins {
+            let pin_rect = Rect::from_center_size(pos, Vec2::splat(3.0 * PIN_RADIUS));
+            let response =
+                self.ui
+                    .interact(pin_rect, self.graph_id.with(pin), Sense::click_and_drag());
+            if response.active && self.state.new_link_from.is_none() {
+                self.state.new_link_from = Some(pin);
+            }
+            let visuals = self.ui.style().interact(&response);
+            self.ui
+                .painter()
+                .circle(pos, PIN_RADIUS, visuals.fg_fill, visuals.fg_stroke);
+            self.state.pin_offsets.insert(pin, pos - node_pos);
+            self.pins.push((pin, pos));
+        }
+
+        // The node itself, for selecting and moving it:
+        let response = self
+            .ui
+            .interact(node_rect, node_id, Sense::click_and_drag());
+        let is_selected = self.state.selected_nodes.contains(&node_id);
+        if response.active {
+            let input = self.ui.input();
+            let toggle = input.modifiers.shift || input.modifiers.ctrl;
+            if input.mouse.pressed && !is_selected && !toggle {
+                self.state.selected_nodes.clear();
+                self.state.selected_link = None;
+            }
+            if input.mouse.pressed && !is_selected {
+                self.state.selected_nodes.insert(node_id);
+            } else if response.clicked && toggle {
+                self.state.selected_nodes.remove(&node_id);
+            }
+            self.node_drag_delta += input.mouse.delta;
+        }
+        let is_selected = self.state.selected_nodes.contains(&node_id);
+
+        let visuals = &self.ui.style().visuals;
+        let stroke = if is_selected {
+            visuals.widgets.active.bg_stroke
+        } else {
+            visuals.widgets.noninteractive.bg_stroke
+        };
+        self.ui.painter().set(
+            where_to_put_background,
+            PaintCmd::Rect {
+                rect: node_rect,
+                corner_radius: visuals.widgets.noninteractive.corner_radius.into(),
+                fill: visuals.widgets.noninteractive.bg_fill.into(),
+                stroke,
+            },
+        );
+        let title_bottom = title_rect.bottom() + 0.5 * padding.y;
+        self.ui.painter().line_segment(
+            [
+                pos2(node_rect.left(), title_bottom),
+                pos2(node_rect.right(), title_bottom),
+            ],
+            visuals.widgets.noninteractive.bg_stroke,
+        );
+
+        self.node_rects.push((node_id, node_rect));
+        response
+    }
+
+    fn node_id(&self, key: K) -> Id {
+        self.graph_id.with(key)
+    }
+
+    fn pin_key(&self, pin: Pin<K>, is_output: bool) -> PinKey {
+        pin_key(self.graph_id, pin, is_output)
+    }
+
+    /// Where the pin is this frame, based on where it was relative to its node last frame.
+    fn pin_pos(&self, pin: PinKey) -> Option<Pos2> {
+        let offset = self.state.pin_offsets.get(&pin)?;
+        let node_pos = self.state.node_pos.get(&pin.0)?;
+        Some(*node_pos + *offset)
+    }
+}
+
+fn pin_key<K: Hash>(graph_id: Id, pin: Pin<K>, is_output: bool) -> PinKey {
+    (graph_id.with(pin.node), is_output, pin.index)
+}
+
+fn link_stroke(ui: &Ui, selected: bool) -> Stroke {
+    let visuals = &ui.style().visuals.widgets;
+    if selected {
+        Stroke::new(3.0, visuals.active.fg_stroke.color)
+    } else {
+        Stroke::new(2.0, visuals.inactive.fg_stroke.color)
+    }
+}
+
+/// A smooth cubic Bezier curve from an output pin to an input pin.
+fn link_bezier(from: Pos2, to: Pos2) -> [Pos2; 4] {
+    let handle = vec2((0.5 * (to.x - from.x).abs()).max(30.0), 0.0);
+    [from, from + handle, to - handle, to]
+}
+
+/// The link as lines, for selecting it.
+fn flatten_link(bezier: [Pos2; 4]) -> Vec<Pos2> {
+    let mut points = vec![];
+    crate::paint::tessellator::path::bezier(&mut points, &bezier, 0.5);
+    points
+}
+
+fn distance_to_line(pos: Pos2, points: &[Pos2]) -> f32 {
+    points
+        .windows(2)
+        .map(|segment| {
+            let (a, b) = (segment[0], segment[1]);
+            let ab = b - a;
+            let t = if ab == Vec2::zero() {
+                0.0
+            } else {
+                let ap = pos - a;
+                clamp(
+                    (ap.x * ab.x + ap.y * ab.y) / (ab.x * ab.x + ab.y * ab.y),
+                    0.0..=1.0,
+                )
+            };
+            pos.distance(a + t * ab)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Lines at regular canvas-space intervals, painted in screen space so they stay thin.
+fn paint_grid(painter: &Painter, visuals: &style::Visuals, rect: Rect, origin: Pos2, zoom: f32) {
+    let mut spacing = 50.0 * zoom;
+    while spacing < 20.0 {
+        spacing *= 2.0;
+    }
+    let stroke = visuals.widgets.noninteractive.bg_stroke;
+
+    let mut x = rect.left() + (origin.x - rect.left()).rem_euclid(spacing);
+    while x < rect.right() {
+        painter.line_segment([pos2(x, rect.top()), pos2(x, rect.bottom())], stroke);
+        x += spacing;
+    }
+    let mut y = rect.top() + (origin.y - rect.top()).rem_euclid(spacing);
+    while y < rect.bottom() {
+        painter.line_segment([pos2(rect.left(), y), pos2(rect.right(), y)], stroke);
+        y += spacing;
+    }
+}
+
+#[test]
+fn test_link_points() {
+    let (from, to) = (pos2(0.0, 0.0), pos2(100.0, 50.0));
+    let points = flatten_link(link_bezier(from, to));
+    assert_eq!(points.first(), Some(&from));
+    assert_eq!(points.last(), Some(&to));
+    assert!(distance_to_line(pos2(50.0, 25.0), &points) < 0.5);
+    assert!((distance_to_line(pos2(-10.0, 0.0), &points) - 10.0).abs() < 0.01);
+}
+
+#[test]
+fn test_zoom_scales_nodes() {
+    let node_on_screen = |zoom: f32| {
+        let mut ctx = Context::new();
+        let mut paint_commands = vec![];
+        // The second frame is for the outputs to be aligned to the width of the node:
+        for _ in 0..2 {
+            ctx.begin_frame(RawInput {
+                screen_size: vec2(800.0, 600.0),
+                ..Default::default()
+            });
+            Area::new("graph").show(&ctx, |ui| {
+                let graph_id = ui.make_persistent_id(Id::new("graph"));
+                ui.memory().node_graph.entry(graph_id).or_default().zoom = zoom;
+                NodeGraph::new("graph")
+                    .desired_size(vec2(400.0, 300.0))
+                    .show(ui, &[], |graph| {
+                        assert_eq!(graph.graph_id, graph_id);
+                        graph.node(Node::new(0, "Node").input("in").output("out"), |_| {});
+                    });
+            });
+            paint_commands = ctx.end_frame().1;
+        }
+        let fill = Fill::from(ctx.style().visuals.widgets.noninteractive.bg_fill);
+        let node_rect = paint_commands
+            .iter()
+            .find_map(|(_, cmd)| match cmd {
+                PaintCmd::Rect { rect, fill: f, .. } if *f == fill => Some(*rect),
+                _ => None,
+            })
+            .unwrap();
+        let pin_radius = paint_commands
+            .iter()
+            .find_map(|(_, cmd)| match cmd {
+                PaintCmd::Circle { radius, .. } => Some(*radius),
+                _ => None,
+            })
+            .unwrap();
+        (node_rect, pin_radius)
+    };
+    let (rect, pin_radius) = node_on_screen(1.0);
+    let (zoomed_rect, zoomed_pin_radius) = node_on_screen(2.0);
+    // Give or take the rounding of the text to pixels:
+    assert!((zoomed_rect.width() - 2.0 * rect.width()).abs() <= 2.0);
+    assert!((zoomed_rect.height() - 2.0 * rect.height()).abs() <= 2.0);
+    assert_eq!(pin_radius, PIN_RADIUS);
+    assert_eq!(zoomed_pin_radius, 2.0 * PIN_RADIUS);
+}
+
+/// Show a graph with two nodes for one frame, at the top left of the screen.
+/// Returns the id and state of the graph, and its events.
+#[cfg(test)]
+fn run_graph_frame(
+    ctx: &mut std::sync::Arc<Context>,
+    links: &[NodeLink<usize>],
+    mouse: (Pos2, bool),
+    events: Vec<Event>,
+) -> (Id, State, Vec<NodeGraphEvent<usize>>) {
+    let (mouse_pos, mouse_down) = mouse;
+    ctx.begin_frame(RawInput {
+        screen_size: vec2(800.0, 600.0),
+        mouse_pos: Some(mouse_pos),
+        mouse_down,
+        events,
+        ..Default::default()
+    });
+    let mut graph_id = Id::new("");
+    let mut graph_events = vec![];
+    Area::new("graph")
+        .fixed_pos(pos2(0.0, 0.0))
+        .show(ctx, |ui| {
+            graph_id = ui.make_persistent_id(Id::new("graph"));
+            graph_events = NodeGraph::new("graph")
+                .desired_size(vec2(400.0, 300.0))
+                .show(ui, links, |graph| {
+                    let time = Node::new(0, "Time").default_pos([20.0, 20.0]);
+                    graph.node(time.output("t"), |_| {});
+                    let sin = Node::new(1, "Sin").default_pos([200.0, 20.0]);
+                    graph.node(sin.input("x").output("sin(x)"), |_| {});
+                })
+                .1;
+        });
+    let _ = ctx.end_frame();
+    let state = ctx.memory().node_graph[&graph_id].clone();
+    (graph_id, state, graph_events)
+}
+
+#[test]
+fn test_connect_and_disconnect() {
+    let mut ctx = Context::new();
+    let link = NodeLink {
+        output: Pin { node: 0, index: 0 },
+        input: Pin { node: 1, index: 0 },
+    };
+    // The graph is not panned or zoomed, and at the top left, so canvas points are screen points:
+    let pin_pos = |graph_id: Id, state: &State, pin: Pin<usize>, is_output: bool| {
+        let pin = pin_key(graph_id, pin, is_output);
+        state.node_pos[&pin.0] + state.pin_offsets[&pin]
+    };
+
+    // Lay out, so that we know where the pins are:
+    let mut links = vec![];
+    run_graph_frame(&mut ctx, &links, (pos2(0.0, 0.0), false), vec![]);
+    let (graph_id, state, _) = run_graph_frame(&mut ctx, &links, (pos2(0.0, 0.0), false), vec![]);
+    let from = pin_pos(graph_id, &state, link.output, true);
+    let to = pin_pos(graph_id, &state, link.input, false);
+
+    // Drag from the output to the input:
+    let mut events = vec![];
+    for &mouse in &[(from, false), (from, true), (to, true), (to, false)] {
+        events.extend(run_graph_frame(&mut ctx, &links, mouse, vec![]).2);
+    }
+    assert_eq!(events, vec![NodeGraphEvent::Connect(link)]);
+    links.push(link);
+
+    // Click the link to select it, and press delete:
+    let on_link = from + 0.5 * (to - from);
+    let mut events = vec![];
+    for &mouse in &[(on_link, false), (on_link, true), (on_link, false)] {
+        events.extend(run_graph_frame(&mut ctx, &links, mouse, vec![]).2);
+    }
+    assert_eq!(events, vec![]);
+    let delete = Event::Key {
+        key: Key::Delete,
+        pressed: true,
+    };
+    let (_, _, events) = run_graph_frame(&mut ctx, &links, (on_link, false), vec![delete]);
+    assert_eq!(events, vec![NodeGraphEvent::Disconnect(link)]);
+}
+
+#[test]
+fn test_box_select() {
+    let mut ctx = Context::new();
+    let shift = |pressed| Event::Key {
+        key: Key::Shift,
+        pressed,
+    };
+
+    // Lay out, then shift-drag a box around the first node only:
+    let (start, end) = (pos2(5.0, 5.0), pos2(150.0, 250.0));
+    run_graph_frame(&mut ctx, &[], (start, false), vec![]);
+    run_graph_frame(&mut ctx, &[], (start, false), vec![shift(true)]);
+    run_graph_frame(&mut ctx, &[], (start, true), vec![]);
+    let (_, state, _) = run_graph_frame(&mut ctx, &[], (end, true), vec![]);
+    assert_eq!(state.box_select_start, Some(start));
+    assert_eq!(state.pan, Vec2::zero());
+    let (graph_id, state, _) = run_graph_frame(&mut ctx, &[], (end, false), vec![shift(false)]);
+
+    let selected: HashSet<Id> = std::iter::once(graph_id.with(0)).collect();
+    assert_eq!(state.selected_nodes, selected);
+    assert_eq!(state.box_select_start, None);
+}
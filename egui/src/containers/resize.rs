@@ -262,7 +262,7 @@ impl Resize {
             let rect = rect.expand(2.0); // breathing room for content
             ui.painter().add(paint::PaintCmd::Rect {
                 rect,
-                corner_radius: 3.0,
+                corner_radius: 3.0.into(),
                 fill: Default::default(),
                 stroke: ui.style().visuals.widgets.noninteractive.bg_stroke,
             });
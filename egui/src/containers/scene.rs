@@ -0,0 +1,117 @@
+//! A region that can be panned and zoomed, with interactive content.
+
+use std::{hash::Hash, ops::RangeInclusive};
+
+use crate::*;
+
+/// State that is persisted between frames
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub(crate) struct State {
+    /// Offset of the scene origin from the top left of the `Scene`, in the parent `Ui`.
+    pan: Vec2,
+    zoom: f32,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            pan: Vec2::zero(),
+            zoom: 1.0,
+        }
+    }
+}
+
+/// A pannable, zoomable region, e.g. for maps and diagrams.
+///
+/// Everything painted inside is transformed, and text is laid out at the zoomed scale so it stays sharp.
+/// The contents are laid out in scene coordinates, starting at the origin.
+/// Widgets inside work as usual, since the input is transformed into scene coordinates too.
+///
+/// Drag the background to pan, and hold down ctrl while scrolling to zoom.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// egui::Scene::new("map").desired_size(egui::vec2(300.0, 200.0)).show(&mut ui, |ui| {
+///     ui.label("Zoom me!");
+///     if ui.button("Click me").clicked {
+///         // …
+///     }
+/// });
+/// ```
+#[derive(Clone, Debug)]
+pub struct Scene {
+    id_source: Id,
+    desired_size: Option<Vec2>,
+    zoom_range: RangeInclusive<f32>,
+}
+
+impl Scene {
+    pub fn new(id_source: impl Hash) -> Self {
+        Self {
+            id_source: Id::new(id_source),
+            desired_size: None,
+            zoom_range: 0.1..=10.0,
+        }
+    }
+
+    /// Default: all available space.
+    pub fn desired_size(mut self, desired_size: Vec2) -> Self {
+        self.desired_size = Some(desired_size);
+        self
+    }
+
+    /// How far the user can zoom out and in. Default: `0.1..=10.0`.
+    pub fn zoom_range(mut self, zoom_range: RangeInclusive<f32>) -> Self {
+        self.zoom_range = zoom_range;
+        self
+    }
+
+    pub fn show<R>(self, ui: &mut Ui, add_contents: impl FnOnce(&mut Ui) -> R) -> (R, Response) {
+        let Self {
+            id_source,
+            desired_size,
+            zoom_range,
+        } = self;
+
+        let id = ui.make_persistent_id(id_source);
+        let desired_size = desired_size.unwrap_or_else(|| ui.available_finite().size());
+        let rect = ui.allocate_space(desired_size);
+        let mut state = ui.memory().scenes.get(&id).copied().unwrap_or_default();
+
+        // The contents go from the scene origin, and are as large as the `Scene` when not zoomed:
+        let origin = rect.min + state.pan;
+        let mut content_ui = ui.child_ui(
+            Rect::from_min_size(origin, state.zoom * rect.size()),
+            Layout::default(),
+        );
+        content_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+        content_ui.add_transform(Transform::new(state.zoom, origin.to_vec2()));
+        let ret = add_contents(&mut content_ui);
+
+        // The background, after the contents so they get the clicks first:
+        let response = ui.interact(rect, id, Sense::click_and_drag());
+        let input = ui.input();
+        let old_state = state;
+        if response.active {
+            state.pan += input.mouse.delta;
+        }
+        if input.modifiers.ctrl && input.scroll_delta.y != 0.0 && ui.contains_mouse(rect) {
+            if let Some(mouse_pos) = input.mouse.pos {
+                let zoom = state.zoom * (input.scroll_delta.y / 200.0).exp();
+                let zoom = clamp(zoom, zoom_range);
+                // Zoom around the mouse:
+                let scene_pos = (mouse_pos - rect.min - state.pan) / state.zoom;
+                state.pan = mouse_pos - rect.min - zoom * scene_pos;
+                state.zoom = zoom;
+            }
+        }
+        if state.pan != old_state.pan || state.zoom != old_state.zoom {
+            ui.ctx().request_repaint(); // Show the contents where they now are
+        }
+        ui.memory().scenes.insert(id, state);
+
+        (ret, response)
+    }
+}
@@ -5,7 +5,7 @@ use crate::*;
 #[cfg_attr(feature = "serde", serde(default))]
 pub(crate) struct State {
     /// Positive offset means scrolling down/right
-    offset: Vec2,
+    pub(crate) offset: Vec2,
 
     show_scroll: bool,
 
@@ -259,8 +259,8 @@ impl Prepared {
 
             ui.painter().add(paint::PaintCmd::Rect {
                 rect: outer_scroll_rect,
-                corner_radius,
-                fill: ui.style().visuals.dark_bg_color,
+                corner_radius: corner_radius.into(),
+                fill: ui.style().visuals.dark_bg_color.into(),
                 stroke: Default::default(),
                 // fill: visuals.bg_fill,
                 // stroke: visuals.bg_stroke,
@@ -268,8 +268,8 @@ impl Prepared {
 
             ui.painter().add(paint::PaintCmd::Rect {
                 rect: handle_rect.expand(-2.0),
-                corner_radius,
-                fill: visuals.fg_fill,
+                corner_radius: corner_radius.into(),
+                fill: visuals.fg_fill.into(),
                 stroke: visuals.fg_stroke,
             });
         }
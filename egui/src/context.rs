@@ -52,6 +52,8 @@ pub struct Context {
 
     paint_stats: Mutex<PaintStats>,
 
+    tessellation_cache: Mutex<TessellationCache>,
+
     /// While positive, keep requesting repaints. Decrement at the end of each frame.
     repaint_requests: AtomicU32,
 }
@@ -69,6 +71,7 @@ impl Clone for Context {
             graphics: self.graphics.clone(),
             output: self.output.clone(),
             paint_stats: self.paint_stats.clone(),
+            tessellation_cache: self.tessellation_cache.clone(),
             repaint_requests: self.repaint_requests.load(SeqCst).into(),
         }
     }
@@ -261,11 +264,15 @@ impl Context {
     }
 
     /// Tesselate the given paint commands into triangle meshes.
+    ///
+    /// The triangles of each run of commands with the same clip rectangle are remembered,
+    /// so runs that are the same as last frame are not tesselated again.
     pub fn tesselate(&self, paint_commands: Vec<(Rect, PaintCmd)>) -> PaintJobs {
         let mut tesselation_options = self.options.lock().tesselation_options;
         tesselation_options.aa_size = 1.0 / self.pixels_per_point();
         let paint_stats = PaintStats::from_paint_commands(&paint_commands); // TODO: internal allocations
-        let paint_jobs = tessellator::tessellate_paint_commands(
+
+        let paint_jobs = self.tessellation_cache.lock().tessellate(
             paint_commands,
             tesselation_options,
             self.fonts(),
@@ -110,8 +110,8 @@ impl FrameHistory {
 
         let mut cmds = vec![PaintCmd::Rect {
             rect,
-            corner_radius: style.corner_radius,
-            fill: ui.style().visuals.dark_bg_color,
+            corner_radius: style.corner_radius.into(),
+            fill: ui.style().visuals.dark_bg_color.into(),
             stroke: ui.style().noninteractive().bg_stroke,
         }];
 
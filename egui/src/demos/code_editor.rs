@@ -0,0 +1,81 @@
+use crate::{
+    demos::{Demo, View},
+    *,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+enum Language {
+    Rust,
+    Toml,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CodeEditorDemo {
+    language: Language,
+    rust_code: String,
+    toml_code: String,
+}
+
+impl Default for CodeEditorDemo {
+    fn default() -> Self {
+        Self {
+            language: Language::Rust,
+            rust_code: r#"// A very simple example
+fn main() {
+    let greeting = "Hello";
+    for i in 0..3 {
+        println!("{} number {}!", greeting, i);
+    }
+}
+"#
+            .to_owned(),
+            toml_code: r#"[package]
+name = "egui" # the name
+version = "0.3.0"
+edition = "2018"
+
+[dependencies]
+ahash = { version = "0.6", features = ["std"], default-features = false }
+"#
+            .to_owned(),
+        }
+    }
+}
+
+impl Demo for CodeEditorDemo {
+    fn name(&self) -> &str {
+        "Code Editor"
+    }
+
+    fn show(&mut self, ctx: &std::sync::Arc<Context>, open: &mut bool) {
+        Window::new(self.name())
+            .open(open)
+            .default_size(vec2(512.0, 384.0))
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for CodeEditorDemo {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.language, Language::Rust, "Rust");
+            ui.radio_value(&mut self.language, Language::Toml, "TOML");
+        });
+        ui.label("Tab and shift+tab indents and outdents. Enter keeps the indentation.");
+
+        match self.language {
+            Language::Rust => ui.add(
+                CodeEditor::new(&mut self.rust_code)
+                    .id_source("rust")
+                    .highlighter(&RustHighlighter),
+            ),
+            Language::Toml => ui.add(
+                CodeEditor::new(&mut self.toml_code)
+                    .id_source("toml")
+                    .highlighter(&TomlHighlighter),
+            ),
+        };
+    }
+}
@@ -204,6 +204,17 @@ impl ColorTest {
                 bg_fill,
                 &g,
             );
+            if self.vertex_gradients {
+                ui.horizontal(|ui| {
+                    let rect = ui.allocate_space(GRADIENT_SIZE);
+                    ui.painter().rect_filled(rect, 0.0, bg_fill);
+                    ui.painter()
+                        .rect_filled(rect, 0.0, Fill::horizontal_gradient(left, right));
+                    ui.interact_hover(rect)
+                        .on_hover_text("A PaintCmd::Rect with a linear gradient fill");
+                    ui.label("Fill::horizontal_gradient (test tessellator gradients)");
+                });
+            }
 
             if self.srgb {
                 let g =
@@ -187,6 +187,8 @@ impl ColorWidgets {
 struct BoxPainting {
     size: Vec2,
     corner_radius: f32,
+    only_top_corners: bool,
+    fill: Fill,
     stroke_width: f32,
     num_boxes: usize,
 }
@@ -196,6 +198,8 @@ impl Default for BoxPainting {
         Self {
             size: vec2(100.0, 50.0),
             corner_radius: 5.0,
+            only_top_corners: false,
+            fill: Srgba::gray(64).into(),
             stroke_width: 2.0,
             num_boxes: 1,
         }
@@ -207,6 +211,23 @@ impl BoxPainting {
         ui.add(Slider::f32(&mut self.size.x, 0.0..=500.0).text("width"));
         ui.add(Slider::f32(&mut self.size.y, 0.0..=500.0).text("height"));
         ui.add(Slider::f32(&mut self.corner_radius, 0.0..=50.0).text("corner_radius"));
+        ui.checkbox(
+            &mut self.only_top_corners,
+            "Only round the top corners, like a tab",
+        );
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.fill, Srgba::gray(64).into(), "Solid");
+            ui.radio_value(
+                &mut self.fill,
+                Fill::vertical_gradient(Srgba::gray(96), Srgba::gray(32)),
+                "Linear gradient",
+            );
+            ui.radio_value(
+                &mut self.fill,
+                Fill::radial_gradient(Srgba::gray(96), Srgba::gray(16)),
+                "Radial gradient",
+            );
+        });
         ui.add(Slider::f32(&mut self.stroke_width, 0.0..=10.0).text("stroke_width"));
         ui.add(Slider::usize(&mut self.num_boxes, 0..=5).text("num_boxes"));
 
@@ -214,11 +235,16 @@ impl BoxPainting {
             .allocate_space(vec2(self.size.x * (self.num_boxes as f32), self.size.y))
             .min;
 
+        let corner_radius = if self.only_top_corners {
+            CornerRadius::top(self.corner_radius)
+        } else {
+            CornerRadius::same(self.corner_radius)
+        };
         let mut cmds = vec![];
         for i in 0..self.num_boxes {
             cmds.push(paint::PaintCmd::Rect {
-                corner_radius: self.corner_radius,
-                fill: Srgba::gray(64),
+                corner_radius,
+                fill: self.fill,
                 rect: Rect::from_min_size(
                     pos2(10.0 + pos.x + (i as f32) * (self.size.x * 1.1), pos.y),
                     self.size,
@@ -37,8 +37,12 @@ impl Default for Demos {
     fn default() -> Self {
         Self {
             demos: vec![
+                (false, Box::new(crate::demos::CodeEditorDemo::default())),
                 (false, Box::new(crate::demos::DancingStrings::default())),
                 (false, Box::new(crate::demos::DragAndDropDemo::default())),
+                (false, Box::new(crate::demos::MarkdownDemo::default())),
+                (false, Box::new(crate::demos::NodeGraphDemo::default())),
+                (false, Box::new(crate::demos::SceneDemo::default())),
                 (false, Box::new(crate::demos::Tests::default())),
             ],
         }
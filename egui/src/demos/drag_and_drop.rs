@@ -66,8 +66,8 @@ pub fn drop_target<R>(
     ui.painter().set(
         where_to_put_background,
         PaintCmd::Rect {
-            corner_radius: style.corner_radius,
-            fill: style.bg_fill,
+            corner_radius: style.corner_radius.into(),
+            fill: style.bg_fill.into(),
             stroke: style.bg_stroke,
             rect: outer_rect,
         },
@@ -56,7 +56,7 @@ impl FractalClock {
         self.fractal_ui(&painter);
 
         Frame::popup(ui.style())
-            .fill(Rgba::luminance_alpha(0.02, 0.5).into())
+            .fill(Rgba::luminance_alpha(0.02, 0.5))
             .stroke(Stroke::none())
             .show(&mut ui.left_column(320.0), |ui| {
                 CollapsingHeader::new("Settings")
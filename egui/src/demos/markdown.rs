@@ -0,0 +1,64 @@
+use crate::{
+    demos::{Demo, View},
+    *,
+};
+
+const DEFAULT_MARKDOWN: &str = r#"# Markdown
+This is a *minimal* markdown viewer, written in **pure Egui**.
+Edit the text above to see the result.
+
+## Features
+- Headings
+- Paragraphs with *emphasis*, **strong** text and `inline code`
+- Lists
+  1. Bullet lists
+  2. Numbered lists
+- [Links](https://github.com/emilk/egui)
+
+```
+fn main() {
+    println!("Code blocks too!");
+}
+```
+
+---
+
+[Egui on GitHub](https://github.com/emilk/egui)
+"#;
+
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MarkdownDemo {
+    markdown: String,
+}
+
+impl Default for MarkdownDemo {
+    fn default() -> Self {
+        Self {
+            markdown: DEFAULT_MARKDOWN.to_owned(),
+        }
+    }
+}
+
+impl Demo for MarkdownDemo {
+    fn name(&self) -> &str {
+        "Markdown Viewer"
+    }
+
+    fn show(&mut self, ctx: &std::sync::Arc<Context>, open: &mut bool) {
+        Window::new(self.name())
+            .open(open)
+            .default_size(vec2(512.0, 512.0))
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for MarkdownDemo {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.collapsing("Source", |ui| {
+            ui.add(TextEdit::new(&mut self.markdown).text_style(TextStyle::Monospace));
+        });
+        ui.separator();
+        ui.add(MarkdownViewer::new(&self.markdown));
+    }
+}
@@ -2,21 +2,26 @@
 //!
 //! The demo-code is also used in benchmarks and tests.
 mod app;
+mod code_editor;
 mod color_test;
 mod dancing_strings;
 pub mod demo_window;
 mod demo_windows;
 mod drag_and_drop;
 mod fractal_clock;
+mod markdown;
+mod node_graph;
+mod scene;
 mod sliders;
 mod tests;
 pub mod toggle_switch;
 mod widgets;
 
 pub use {
-    app::*, color_test::ColorTest, dancing_strings::DancingStrings, demo_window::DemoWindow,
-    demo_windows::*, drag_and_drop::*, fractal_clock::FractalClock, sliders::Sliders, tests::Tests,
-    widgets::Widgets,
+    app::*, code_editor::CodeEditorDemo, color_test::ColorTest, dancing_strings::DancingStrings,
+    demo_window::DemoWindow, demo_windows::*, drag_and_drop::*, fractal_clock::FractalClock,
+    markdown::MarkdownDemo, node_graph::NodeGraphDemo, scene::SceneDemo, sliders::Sliders,
+    tests::Tests, widgets::Widgets,
 };
 
 pub const LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
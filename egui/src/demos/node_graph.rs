@@ -0,0 +1,171 @@
+use crate::{
+    demos::{Demo, View},
+    *,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NodeKind {
+    Number(f32),
+    Add,
+    Multiply,
+    Output,
+}
+
+impl NodeKind {
+    fn title(&self) -> &'static str {
+        match self {
+            NodeKind::Number(_) => "Number",
+            NodeKind::Add => "Add",
+            NodeKind::Multiply => "Multiply",
+            NodeKind::Output => "Output",
+        }
+    }
+
+    fn num_inputs(&self) -> usize {
+        match self {
+            NodeKind::Number(_) => 0,
+            NodeKind::Add | NodeKind::Multiply => 2,
+            NodeKind::Output => 1,
+        }
+    }
+
+    fn has_output(&self) -> bool {
+        !matches!(self, NodeKind::Output)
+    }
+}
+
+/// A small calculator built from nodes, to show off `NodeGraph`.
+pub struct NodeGraphDemo {
+    nodes: Vec<NodeKind>,
+    links: Vec<NodeLink<usize>>,
+}
+
+impl Default for NodeGraphDemo {
+    fn default() -> Self {
+        let link = |output, input, index| NodeLink {
+            output: Pin {
+                node: output,
+                index: 0,
+            },
+            input: Pin { node: input, index },
+        };
+        Self {
+            nodes: vec![
+                NodeKind::Number(2.0),
+                NodeKind::Number(3.0),
+                NodeKind::Add,
+                NodeKind::Multiply,
+                NodeKind::Output,
+            ],
+            links: vec![
+                link(0, 2, 0),
+                link(1, 2, 1),
+                link(2, 3, 0),
+                link(1, 3, 1),
+                link(3, 4, 0),
+            ],
+        }
+    }
+}
+
+impl NodeGraphDemo {
+    /// The value going into the given input of a node.
+    /// `depth` protects us from cycles.
+    fn input_value(&self, node: usize, index: usize, depth: usize) -> Option<f32> {
+        let link = self
+            .links
+            .iter()
+            .find(|link| link.input == Pin { node, index })?;
+        self.output_value(link.output.node, depth)
+    }
+
+    fn output_value(&self, node: usize, depth: usize) -> Option<f32> {
+        if depth > self.nodes.len() {
+            return None;
+        }
+        let input = |index| self.input_value(node, index, depth + 1);
+        match self.nodes[node] {
+            NodeKind::Number(value) => Some(value),
+            NodeKind::Add => Some(input(0)? + input(1)?),
+            NodeKind::Multiply => Some(input(0)? * input(1)?),
+            NodeKind::Output => input(0),
+        }
+    }
+}
+
+impl Demo for NodeGraphDemo {
+    fn name(&self) -> &str {
+        "Node Graph"
+    }
+
+    fn show(&mut self, ctx: &std::sync::Arc<Context>, open: &mut bool) {
+        Window::new(self.name())
+            .open(open)
+            .default_size(vec2(512.0, 320.0))
+            .scroll(false)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for NodeGraphDemo {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.label("Drag between pins to connect them. Click a link and press delete to remove it.");
+        ui.horizontal(|ui| {
+            for kind in &[
+                NodeKind::Number(1.0),
+                NodeKind::Add,
+                NodeKind::Multiply,
+                NodeKind::Output,
+            ] {
+                if ui.button(format!("Add {}", kind.title())).clicked {
+                    self.nodes.push(*kind);
+                }
+            }
+        });
+
+        let output_values: Vec<Option<f32>> = (0..self.nodes.len())
+            .map(|node| self.output_value(node, 0))
+            .collect();
+
+        let Self { nodes, links } = self;
+        let (_, events) = NodeGraph::new("node_graph_demo")
+            .desired_size(vec2(ui.available().width(), 256.0))
+            .show(ui, links, |graph| {
+                for (i, kind) in nodes.iter_mut().enumerate() {
+                    let mut node = Node::new(i, kind.title()).default_pos(pos2(
+                        16.0 + 128.0 * (i % 4) as f32,
+                        16.0 + 96.0 * (i / 4) as f32,
+                    ));
+                    for index in 0..kind.num_inputs() {
+                        node = node.input(["a", "b"][index]);
+                    }
+                    if kind.has_output() {
+                        node = node.output("out");
+                    }
+                    let value = output_values[i];
+                    graph.node(node, |ui| match kind {
+                        NodeKind::Number(value) => {
+                            ui.add(DragValue::f32(value).speed(0.1));
+                        }
+                        NodeKind::Output => {
+                            ui.label(value.map_or("?".to_owned(), |value| value.to_string()));
+                        }
+                        _ => {}
+                    });
+                }
+            });
+
+        for event in events {
+            match event {
+                NodeGraphEvent::Connect(link) => {
+                    // An input can only have one link:
+                    links.retain(|l| l.input != link.input);
+                    links.push(link);
+                }
+                NodeGraphEvent::Disconnect(link) => links.retain(|&l| l != link),
+            }
+        }
+
+        ui.add(__egui_github_link_file!());
+    }
+}
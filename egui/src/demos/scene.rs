@@ -0,0 +1,101 @@
+use crate::{
+    demos::{Demo, View},
+    *,
+};
+
+/// A zoomable map with interactive widgets on it, to show off `Scene`.
+pub struct SceneDemo {
+    clicks: usize,
+    value: f32,
+    name: String,
+}
+
+impl Default for SceneDemo {
+    fn default() -> Self {
+        Self {
+            clicks: 0,
+            value: 0.5,
+            name: "Anna".to_owned(),
+        }
+    }
+}
+
+impl Demo for SceneDemo {
+    fn name(&self) -> &str {
+        "Scene"
+    }
+
+    fn show(&mut self, ctx: &std::sync::Arc<Context>, open: &mut bool) {
+        Window::new(self.name())
+            .open(open)
+            .default_size(vec2(400.0, 400.0))
+            .scroll(false)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for SceneDemo {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.label("Drag the background to pan. Hold down ctrl and scroll to zoom.");
+
+        let desired_size = vec2(ui.available().width(), 300.0);
+        Scene::new("scene_demo")
+            .desired_size(desired_size)
+            .show(ui, |ui| {
+                // Some terrain:
+                let painter = ui.painter();
+                for (i, &color) in [
+                    color::srgba(60, 120, 60, 255),
+                    color::srgba(50, 80, 140, 255),
+                ]
+                .iter()
+                .enumerate()
+                {
+                    let center = pos2(80.0 + 160.0 * i as f32, 220.0);
+                    painter.circle_filled(center, 60.0, color);
+                    painter.text(
+                        center,
+                        (Align::Center, Align::Center),
+                        ["Forest", "Lake"][i],
+                        TextStyle::Body,
+                        color::WHITE,
+                    );
+                }
+                // A fenced field with a pond in it:
+                painter.add(PaintCmd::polygon_with_holes(
+                    vec![
+                        pos2(260.0, 40.0),
+                        pos2(380.0, 40.0),
+                        pos2(380.0, 160.0),
+                        pos2(320.0, 160.0),
+                        pos2(320.0, 100.0),
+                        pos2(260.0, 100.0),
+                    ],
+                    vec![(0..16)
+                        .map(|i| pos2(350.0, 70.0) + 12.0 * Vec2::angled(i as f32 * TAU / 16.0))
+                        .collect()],
+                    color::srgba(140, 120, 60, 255),
+                    Stroke::new(1.0, color::LIGHT_GRAY).dashed(4.0, 2.0),
+                ));
+                painter.line_segment(
+                    [pos2(0.0, 0.0), pos2(desired_size.x, 300.0)],
+                    (1.0, color::GRAY),
+                );
+
+                ui.heading("A small world");
+                ui.horizontal(|ui| {
+                    if ui.button("Click me").clicked {
+                        self.clicks += 1;
+                    }
+                    ui.label(format!("Clicked {} times", self.clicks));
+                });
+                ui.add(Slider::f32(&mut self.value, 0.0..=1.0).text("value"));
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.add(TextEdit::new(&mut self.name).multiline(false));
+                });
+            });
+
+        ui.add(__egui_github_link_file!());
+    }
+}
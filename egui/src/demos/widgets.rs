@@ -20,10 +20,22 @@ pub struct Widgets {
     button_enabled: bool,
     count: usize,
     radio: Enum,
+    asset: usize,
+    date: Date,
+    time: TimeOfDay,
     sliders: Sliders,
     angle: f32,
+    gain: f32,
+    pan: f32,
+    steps: i32,
     color: Srgba,
+    gradient: color_picker::ColorGradient,
+    curve: Curve,
     single_line_text_input: String,
+    #[cfg_attr(feature = "serde", serde(skip))] // Don't store passwords
+    password: String,
+    numeric_id: String,
+    command: String,
     multiline_text_input: String,
     toggle_switch: bool,
 }
@@ -33,11 +45,22 @@ impl Default for Widgets {
         Self {
             button_enabled: true,
             radio: Enum::First,
+            asset: 0,
+            date: Date::new(2020, 11, 23),
+            time: TimeOfDay { hour: 12, minute: 0 },
             count: 0,
             sliders: Default::default(),
             angle: TAU / 8.0,
+            gain: 1.0,
+            pan: 0.0,
+            steps: 3,
             color: (Rgba::new(0.0, 1.0, 0.5, 1.0) * 0.75).into(),
+            gradient: color_picker::ColorGradient::two_colors(BLUE, YELLOW),
+            curve: Curve::ease_in_out(),
             single_line_text_input: "Hello World!".to_owned(),
+            password: Default::default(),
+            numeric_id: Default::default(),
+            command: Default::default(),
             multiline_text_input: "Text can both be so wide that it needs a line break, but you can also add manual line break by pressing enter, creating new paragraphs.\nThis is the start of the next paragraph.\n\nClick me to edit me!".to_owned(),
             toggle_switch: false,
         }
@@ -66,6 +89,21 @@ impl Widgets {
                 let _ = ui.button("A button you can never press");
             });
 
+        ui.add(
+            RichText::new()
+                .span("Rich text can mix ")
+                .span(TextSpan::new("styles").heading())
+                .span(", ")
+                .span(TextSpan::new("colors").text_color(srgba(255, 140, 110, 255)))
+                .span(", ")
+                .span(TextSpan::new("backgrounds").background(srgba(80, 60, 20, 255)))
+                .span(", ")
+                .span(TextSpan::new("underlines").underline())
+                .span(" and ")
+                .span(TextSpan::new("links").url("https://github.com/emilk/egui"))
+                .span(" in one wrapping layout."),
+        );
+
         ui.label("Ευρηκα! τ = 2×π")
             .on_hover_text("The current font supports only a few non-latin characters and Egui does not currently support right-to-left text.");
 
@@ -81,6 +119,14 @@ impl Widgets {
             ui.radio_value(&mut self.radio, Enum::Third, "Third");
         });
 
+        let assets: Vec<String> = (0..500).map(|i| format!("asset_{:03}.png", i)).collect();
+        searchable_combo_box_with_label(ui, "Searchable Combo Box", &mut self.asset, &assets);
+
+        ui.horizontal(|ui| {
+            ui.add(DatePicker::new("date", &mut self.date).time(&mut self.time));
+            ui.label("Date Picker");
+        });
+
         ui.checkbox(&mut self.button_enabled, "Button enabled");
 
         ui.horizontal(|ui| {
@@ -123,12 +169,50 @@ impl Widgets {
             });
         }
         ui.separator();
+        {
+            ui.label("Knobs: drag up/down or scroll to turn, double-click to reset:");
+            ui.horizontal(|ui| {
+                ui.add(
+                    Knob::f32(&mut self.gain, 0.0..=10.0)
+                        .logarithmic(true)
+                        .default_value(1.0)
+                        .text("Gain"),
+                );
+                ui.add(
+                    Knob::f32(&mut self.pan, -1.0..=1.0)
+                        .default_value(0.0)
+                        .text("Pan"),
+                );
+                ui.add(
+                    Knob::i32(&mut self.steps, 1..=8)
+                        .default_value(3.0)
+                        .text("Steps"),
+                );
+            });
+        }
+        ui.separator();
 
         ui.horizontal(|ui| {
             ui.add(Label::new("Click to select a different text color: ").text_color(self.color));
             ui.color_edit_button_srgba(&mut self.color);
         });
 
+        ui.horizontal(|ui| {
+            ui.gradient_edit(&mut self.gradient);
+            ui.label("Gradient");
+        });
+
+        CollapsingHeader::new("Curve editor")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.label("Drag keys and tangents. Double-click to add a key.");
+                ui.add(CurveEdit::new(&mut self.curve).key_editor(true));
+                ui.label(format!(
+                    "curve.evaluate(0.25) = {:.3}",
+                    self.curve.evaluate(0.25)
+                ));
+            });
+
         ui.separator();
 
         ui.horizontal(|ui| {
@@ -144,6 +228,46 @@ impl Widgets {
             }
         });
 
+        ui.horizontal(|ui| {
+            ui.label("Password:");
+            ui.add(
+                TextEdit::new(&mut self.password)
+                    .multiline(false)
+                    .password(true)
+                    .hint_text("Enter a password")
+                    .id_source("password"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Numeric ID:");
+            ui.add(
+                TextEdit::new(&mut self.numeric_id)
+                    .multiline(false)
+                    .hint_text("At most 6 digits")
+                    .char_limit(6)
+                    .filter(|c| c.is_ascii_digit())
+                    .id_source("numeric id"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Command:");
+            let commands = |_: &str| {
+                ["open", "open recent", "save", "save as", "close", "quit"]
+                    .iter()
+                    .map(|command| command.to_string())
+                    .collect()
+            };
+            ui.add(
+                TextEdit::new(&mut self.command)
+                    .multiline(false)
+                    .hint_text("Start typing for suggestions")
+                    .suggestions(&commands)
+                    .id_source("command"),
+            );
+        });
+
         ui.label("Multiline text input:");
         ui.add(TextEdit::new(&mut self.multiline_text_input).id_source("multiline"));
 
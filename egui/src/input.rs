@@ -80,6 +80,12 @@ pub struct InputState {
     /// Should be set to the expected time between frames when painting at vsync speeds.
     pub predicted_dt: f32,
 
+    /// Which modifier keys are held down after this frame's events.
+    pub modifiers: Modifiers,
+
+    /// Which modifier keys were held down before this frame's events.
+    frame_start_modifiers: Modifiers,
+
     /// In-order events received this frame
     pub events: Vec<Event>,
 }
@@ -162,6 +168,13 @@ pub enum Event {
         key: Key,
         pressed: bool,
     },
+    /// An IME (Input Method Editor) composition has started,
+    /// e.g. when the user begins typing a CJK character.
+    CompositionStart,
+    /// The text of the ongoing IME composition (the "preedit" text) has changed.
+    CompositionUpdate(String),
+    /// The IME composition has ended and the given text should be inserted.
+    CompositionEnd(String),
 }
 
 /// Keyboard key name. Only covers keys used by Egui.
@@ -190,11 +203,47 @@ pub enum Key {
     Up,
 }
 
+/// Which modifier keys are held down.
+///
+/// Egui keeps track of this from the `Key::Alt`, `Key::Control`, `Key::Shift` and `Key::Logo` events.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Modifiers {
+    pub alt: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+    /// Windows key or Mac Command key
+    pub logo: bool,
+}
+
+impl Modifiers {
+    /// No modifier keys are held down
+    pub fn is_none(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn on_event(&mut self, event: &Event) {
+        if let Event::Key { key, pressed } = *event {
+            match key {
+                Key::Alt => self.alt = pressed,
+                Key::Control => self.ctrl = pressed,
+                Key::Shift => self.shift = pressed,
+                Key::Logo => self.logo = pressed,
+                _ => {}
+            }
+        }
+    }
+}
+
 impl InputState {
     #[must_use]
     pub fn begin_frame(self, new: RawInput) -> InputState {
         let mouse = self.mouse.begin_frame(&new);
         let unstable_dt = (new.time - self.raw.time) as f32;
+        let frame_start_modifiers = self.modifiers;
+        let mut modifiers = self.modifiers;
+        for event in &new.events {
+            modifiers.on_event(event);
+        }
         InputState {
             mouse,
             scroll_delta: new.scroll_delta,
@@ -202,7 +251,9 @@ impl InputState {
             pixels_per_point: new.pixels_per_point.or(self.pixels_per_point),
             time: new.time,
             unstable_dt,
-            predicted_dt: 1.0 / 60.0,   // TODO: remove this hack
+            predicted_dt: 1.0 / 60.0, // TODO: remove this hack
+            modifiers,
+            frame_start_modifiers,
             events: new.events.clone(), // TODO: remove clone() and use raw.events
             raw: new,
         }
@@ -212,6 +263,21 @@ impl InputState {
         Rect::from_min_size(pos2(0.0, 0.0), self.screen_size)
     }
 
+    /// The input as seen from other coordinates, e.g. inside a `Scene`.
+    /// `transform` goes from screen coordinates to those coordinates.
+    #[must_use]
+    pub fn transformed(&self, transform: Transform) -> InputState {
+        let mut input = self.clone();
+        let mouse = &mut input.mouse;
+        mouse.pos = mouse.pos.map(|pos| transform.transform_pos(pos));
+        mouse.press_origin = mouse.press_origin.map(|pos| transform.transform_pos(pos));
+        mouse.delta = transform.transform_vec(mouse.delta);
+        mouse.velocity = transform.transform_vec(mouse.velocity);
+        input.scroll_delta = transform.transform_vec(input.scroll_delta);
+        input.pixels_per_point = Some(self.pixels_per_point() / transform.scale);
+        input
+    }
+
     pub fn wants_repaint(&self) -> bool {
         self.mouse.pressed
             || self.mouse.released
@@ -233,6 +299,16 @@ impl InputState {
         })
     }
 
+    /// The events of this frame,
+    /// each paired with the modifier keys that were held down when it happened.
+    pub fn events_with_modifiers(&self) -> impl Iterator<Item = (&Event, Modifiers)> {
+        let mut modifiers = self.frame_start_modifiers;
+        self.events.iter().map(move |event| {
+            modifiers.on_event(event);
+            (event, modifiers)
+        })
+    }
+
     /// Was the given key released this frame?
     pub fn key_released(&self, desired_key: Key) -> bool {
         self.events.iter().any(|event| {
@@ -373,6 +449,8 @@ impl InputState {
             time,
             unstable_dt,
             predicted_dt,
+            modifiers,
+            frame_start_modifiers: _,
             events,
         } = self;
 
@@ -397,6 +475,7 @@ impl InputState {
             1e3 * unstable_dt
         ));
         ui.label(format!("expected dt: {:.1} ms", 1e3 * predicted_dt));
+        ui.label(format!("modifiers: {:?}", modifiers));
         ui.label(format!("events: {:?}", events))
             .on_hover_text("key presses etc");
     }
@@ -435,3 +514,27 @@ impl MouseInput {
         ));
     }
 }
+
+#[test]
+fn test_transformed() {
+    let input = InputState {
+        mouse: MouseInput {
+            pos: Some(pos2(30.0, 40.0)),
+            press_origin: Some(pos2(10.0, 20.0)),
+            delta: vec2(4.0, -2.0),
+            ..Default::default()
+        },
+        scroll_delta: vec2(0.0, 6.0),
+        pixels_per_point: Some(2.0),
+        ..Default::default()
+    };
+    // Into a scene that is zoomed in two times, with its origin at (10, 20) on screen:
+    let to_scene = Transform::new(2.0, vec2(10.0, 20.0)).inverse();
+    let input = input.transformed(to_scene);
+    assert_eq!(input.mouse.pos, Some(pos2(10.0, 10.0)));
+    assert_eq!(input.mouse.press_origin, Some(pos2(0.0, 0.0)));
+    assert_eq!(input.mouse.delta, vec2(2.0, -1.0));
+    assert_eq!(input.scroll_delta, vec2(0.0, 3.0));
+    // Each point in the scene covers twice as many pixels:
+    assert_eq!(input.pixels_per_point(), 4.0);
+}
@@ -143,14 +143,14 @@ impl GraphicLayers {
             for layer_id in area_order {
                 if layer_id.order == order {
                     if let Some(commands) = order_map.get_mut(&layer_id.id) {
-                        all_commands.extend(commands.0.drain(..));
+                        all_commands.append(&mut commands.0);
                     }
                 }
             }
 
             // Also draw areas that are missing in `area_order`:
             for commands in order_map.values_mut() {
-                all_commands.extend(commands.0.drain(..));
+                all_commands.append(&mut commands.0);
             }
         }
 
@@ -94,8 +94,8 @@ pub use {
     math::*,
     memory::Memory,
     paint::{
-        color, FontDefinitions, FontFamily, PaintCmd, PaintJobs, Rgba, Srgba, Stroke, TextStyle,
-        Texture, TextureId,
+        color, CornerRadius, Fill, FontDefinitions, FontFamily, PaintCmd, PaintJobs, Rgba, Shadow,
+        Srgba, Stroke, StrokePattern, TextStyle, Texture, TextureId,
     },
     painter::Painter,
     style::Style,
@@ -7,9 +7,10 @@ use std::ops::{Add, Div, Mul, RangeInclusive, Sub};
 mod pos2;
 mod rect;
 pub mod smart_aim;
+mod transform;
 mod vec2;
 
-pub use {pos2::*, rect::*, vec2::*};
+pub use {pos2::*, rect::*, transform::Transform, vec2::*};
 
 // ----------------------------------------------------------------------------
 
@@ -0,0 +1,86 @@
+use std::ops::Mul;
+
+use crate::math::*;
+
+/// A uniform scale followed by a translation.
+///
+/// Maps a position `p` to `translation + scale * p`.
+/// Used by `Scene` to go from scene coordinates to screen coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Transform {
+    pub scale: f32,
+    pub translation: Vec2,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self {
+            scale: 1.0,
+            translation: Vec2::zero(),
+        }
+    }
+
+    pub fn new(scale: f32, translation: Vec2) -> Self {
+        Self { scale, translation }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    /// The transform that undoes this one.
+    pub fn inverse(&self) -> Self {
+        Self {
+            scale: 1.0 / self.scale,
+            translation: -self.translation / self.scale,
+        }
+    }
+
+    pub fn transform_pos(&self, pos: Pos2) -> Pos2 {
+        pos2(
+            self.translation.x + self.scale * pos.x,
+            self.translation.y + self.scale * pos.y,
+        )
+    }
+
+    /// Scales a vector, e.g. a size or a mouse movement. Ignores the translation.
+    pub fn transform_vec(&self, vec: Vec2) -> Vec2 {
+        self.scale * vec
+    }
+
+    pub fn transform_rect(&self, rect: Rect) -> Rect {
+        Rect::from_min_max(self.transform_pos(rect.min), self.transform_pos(rect.max))
+    }
+}
+
+/// `a * b` first applies `b`, then `a`.
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Transform) -> Transform {
+        Transform {
+            scale: self.scale * rhs.scale,
+            translation: self.translation + self.scale * rhs.translation,
+        }
+    }
+}
+
+#[test]
+fn test_transform() {
+    let a = Transform::new(2.0, vec2(10.0, 20.0));
+    let b = Transform::new(0.5, vec2(-3.0, 1.0));
+    let p = pos2(7.0, -5.0);
+    assert_eq!(a.transform_pos(p), pos2(24.0, 10.0));
+    assert_eq!(
+        (a * b).transform_pos(p),
+        a.transform_pos(b.transform_pos(p))
+    );
+    assert_eq!(a.inverse().transform_pos(a.transform_pos(p)), p);
+}
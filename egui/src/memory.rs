@@ -3,10 +3,10 @@ use std::collections::{HashMap, HashSet};
 use crate::{
     area,
     cache::Cache,
-    collapsing_header, menu,
+    collapsing_header, combo_box, menu, node_graph,
     paint::color::{Hsva, Srgba},
-    resize, scroll_area,
-    widgets::text_edit,
+    resize, scene, scroll_area,
+    widgets::{code_editor, color_picker, date_picker, text_edit},
     window, Id, LayerId, Pos2, Rect,
 };
 
@@ -34,7 +34,21 @@ pub struct Memory {
     pub(crate) menu_bar: HashMap<Id, menu::BarState>,
     pub(crate) resize: HashMap<Id, resize::State>,
     pub(crate) scroll_areas: HashMap<Id, scroll_area::State>,
+    pub(crate) scenes: HashMap<Id, scene::State>,
     pub(crate) text_edit: HashMap<Id, text_edit::State>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) code_editor: HashMap<Id, code_editor::State>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) combo_box: HashMap<Id, combo_box::State>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) date_picker: HashMap<Id, date_picker::State>,
+    /// The selected key of each `CurveEdit`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) curve_edit: HashMap<Id, usize>,
+    /// The unrounded position (0-1) of the `Knob` being dragged.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) knob: HashMap<Id, f64>,
+    pub(crate) node_graph: HashMap<Id, node_graph::State>,
 
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) window_interaction: Option<window::WindowInteraction>,
@@ -50,6 +64,9 @@ pub struct Memory {
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) color_cache: Cache<Srgba, Hsva>,
 
+    /// Used by color picker
+    pub(crate) color_picker_mode: color_picker::ColorPickerMode,
+
     /// Which popup-window is open (if any)?
     /// Could be a combo box, color picker, menu etc.
     #[cfg_attr(feature = "serde", serde(skip))]
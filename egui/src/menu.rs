@@ -97,7 +97,7 @@ fn menu_impl<'c>(
     if bar_state.open_menu == Some(menu_id) || ui.memory().all_menues_are_open {
         let area = Area::new(menu_id)
             .order(Order::Foreground)
-            .fixed_pos(button_response.rect.left_bottom());
+            .fixed_pos(ui.to_screen_rect(button_response.rect).left_bottom());
         let frame = Frame::menu(ui.style());
 
         area.show(ui.ctx(), |ui| {
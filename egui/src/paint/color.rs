@@ -314,6 +314,7 @@ fn test_srgba_conversion() {
 /// Hue, saturation, value, alpha. All in the range [0, 1].
 /// No premultiplied alpha.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Hsva {
     /// hue 0-1
     pub h: f32,
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use {
     super::{font::Galley, fonts::TextStyle, Fonts, Srgba, Triangles},
     crate::{
@@ -27,22 +29,56 @@ pub enum PaintCmd {
         /// If true, connect the first and last of the points together.
         /// This is required if `fill != TRANSPARENT`.
         closed: bool,
+        /// The polygon may be concave, but should not cross itself.
+        fill: Srgba,
+        stroke: Stroke,
+    },
+    /// A filled polygon with holes cut out of it, e.g. the letter "O".
+    PolygonWithHoles {
+        /// The outline, in either winding order.
+        points: Vec<Pos2>,
+        /// Each hole should be inside of the outline, and not overlap the other holes.
+        holes: Vec<Vec<Pos2>>,
         fill: Srgba,
+        /// Both the outline and the holes are stroked.
         stroke: Stroke,
     },
     Rect {
         rect: Rect,
-        corner_radius: f32,
+        corner_radius: CornerRadius,
+        fill: Fill,
+        stroke: Stroke,
+    },
+    /// A quadratic Bezier curve, flattened to a `Path` when tessellated.
+    QuadraticBezier {
+        /// Start point, control point and end point.
+        points: [Pos2; 3],
+        /// If true, connect the end back to the start with a straight line.
+        /// This is required if `fill != TRANSPARENT`.
+        closed: bool,
+        fill: Srgba,
+        stroke: Stroke,
+    },
+    /// A cubic Bezier curve, flattened to a `Path` when tessellated.
+    CubicBezier {
+        /// Start point, two control points and end point.
+        points: [Pos2; 4],
+        /// If true, connect the end back to the start with a straight line.
+        /// This is required if `fill != TRANSPARENT`.
+        closed: bool,
         fill: Srgba,
         stroke: Stroke,
     },
     Text {
         /// Top left corner of the first character.
         pos: Pos2,
-        /// The layed out text
-        galley: Galley,
+        /// The layed out text. Shared, so that cached layouts can be painted without copying them.
+        galley: Arc<Galley>,
         text_style: TextStyle, // TODO: Font?
         color: Srgba,
+        /// How many times larger than the `galley` the text is painted, e.g. in a zoomed `Scene`.
+        /// The glyphs come from `Fonts::scaled`, so they stay sharp.
+        scale: f32,
     },
     Triangles(Triangles),
 }
@@ -83,6 +119,20 @@ impl PaintCmd {
         }
     }
 
+    pub fn polygon_with_holes(
+        points: Vec<Pos2>,
+        holes: Vec<Vec<Pos2>>,
+        fill: impl Into<Srgba>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        Self::PolygonWithHoles {
+            points,
+            holes,
+            fill: fill.into(),
+            stroke: stroke.into(),
+        }
+    }
+
     pub fn circle_filled(center: Pos2, radius: f32, fill_color: impl Into<Srgba>) -> Self {
         Self::Circle {
             center,
@@ -101,19 +151,45 @@ impl PaintCmd {
         }
     }
 
-    pub fn rect_filled(rect: Rect, corner_radius: f32, fill_color: impl Into<Srgba>) -> Self {
+    pub fn rect_filled(
+        rect: Rect,
+        corner_radius: impl Into<CornerRadius>,
+        fill: impl Into<Fill>,
+    ) -> Self {
         Self::Rect {
             rect,
-            corner_radius,
-            fill: fill_color.into(),
+            corner_radius: corner_radius.into(),
+            fill: fill.into(),
             stroke: Default::default(),
         }
     }
 
-    pub fn rect_stroke(rect: Rect, corner_radius: f32, stroke: impl Into<Stroke>) -> Self {
+    pub fn rect_stroke(
+        rect: Rect,
+        corner_radius: impl Into<CornerRadius>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
         Self::Rect {
             rect,
-            corner_radius,
+            corner_radius: corner_radius.into(),
+            fill: Default::default(),
+            stroke: stroke.into(),
+        }
+    }
+
+    pub fn quadratic_bezier(points: [Pos2; 3], stroke: impl Into<Stroke>) -> Self {
+        Self::QuadraticBezier {
+            points,
+            closed: false,
+            fill: Default::default(),
+            stroke: stroke.into(),
+        }
+    }
+
+    pub fn cubic_bezier(points: [Pos2; 4], stroke: impl Into<Stroke>) -> Self {
+        Self::CubicBezier {
+            points,
+            closed: false,
             fill: Default::default(),
             stroke: stroke.into(),
         }
@@ -132,9 +208,10 @@ impl PaintCmd {
         let rect = anchor_rect(Rect::from_min_size(pos, galley.size), anchor);
         Self::Text {
             pos: rect.min,
-            galley,
+            galley: Arc::new(galley),
             text_style,
             color,
+            scale: 1.0,
         }
     }
 }
@@ -171,9 +248,24 @@ impl PaintCmd {
                     *p += delta;
                 }
             }
+            PaintCmd::PolygonWithHoles { points, holes, .. } => {
+                for p in points.iter_mut().chain(holes.iter_mut().flatten()) {
+                    *p += delta;
+                }
+            }
             PaintCmd::Rect { rect, .. } => {
                 *rect = rect.translate(delta);
             }
+            PaintCmd::QuadraticBezier { points, .. } => {
+                for p in points {
+                    *p += delta;
+                }
+            }
+            PaintCmd::CubicBezier { points, .. } => {
+                for p in points {
+                    *p += delta;
+                }
+            }
             PaintCmd::Text { pos, .. } => {
                 *pos += delta;
             }
@@ -182,6 +274,78 @@ impl PaintCmd {
             }
         }
     }
+
+    /// Scale and translate location and size, in-place.
+    /// Stroke widths and corner radii are scaled too.
+    pub fn transform(&mut self, transform: Transform) {
+        match self {
+            PaintCmd::Noop => {}
+            PaintCmd::Circle {
+                center,
+                radius,
+                stroke,
+                ..
+            } => {
+                *center = transform.transform_pos(*center);
+                *radius *= transform.scale;
+                stroke.scale(transform.scale);
+            }
+            PaintCmd::LineSegment { points, stroke } => {
+                for p in points {
+                    *p = transform.transform_pos(*p);
+                }
+                stroke.scale(transform.scale);
+            }
+            PaintCmd::Path { points, stroke, .. } => {
+                for p in points {
+                    *p = transform.transform_pos(*p);
+                }
+                stroke.scale(transform.scale);
+            }
+            PaintCmd::PolygonWithHoles {
+                points,
+                holes,
+                stroke,
+                ..
+            } => {
+                for p in points.iter_mut().chain(holes.iter_mut().flatten()) {
+                    *p = transform.transform_pos(*p);
+                }
+                stroke.scale(transform.scale);
+            }
+            PaintCmd::Rect {
+                rect,
+                corner_radius,
+                stroke,
+                ..
+            } => {
+                *rect = transform.transform_rect(*rect);
+                corner_radius.scale(transform.scale);
+                stroke.scale(transform.scale);
+            }
+            PaintCmd::QuadraticBezier { points, stroke, .. } => {
+                for p in points {
+                    *p = transform.transform_pos(*p);
+                }
+                stroke.scale(transform.scale);
+            }
+            PaintCmd::CubicBezier { points, stroke, .. } => {
+                for p in points {
+                    *p = transform.transform_pos(*p);
+                }
+                stroke.scale(transform.scale);
+            }
+            PaintCmd::Text { pos, scale, .. } => {
+                *pos = transform.transform_pos(*pos);
+                *scale *= transform.scale;
+            }
+            PaintCmd::Triangles(triangles) => {
+                for vertex in &mut triangles.vertices {
+                    vertex.pos = transform.transform_pos(vertex.pos);
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -189,6 +353,7 @@ impl PaintCmd {
 pub struct Stroke {
     pub width: f32,
     pub color: Srgba,
+    pub pattern: StrokePattern,
 }
 
 impl Stroke {
@@ -200,10 +365,54 @@ impl Stroke {
         Self {
             width: width.into(),
             color: color.into(),
+            pattern: StrokePattern::Solid,
+        }
+    }
+
+    pub fn pattern(mut self, pattern: StrokePattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Dashes of length `dash` separated by gaps of length `gap`.
+    pub fn dashed(self, dash: f32, gap: f32) -> Self {
+        self.pattern(StrokePattern::Dashed { dash, gap })
+    }
+
+    /// Round dots, as wide as the stroke, `spacing` apart.
+    pub fn dotted(self, spacing: f32) -> Self {
+        self.pattern(StrokePattern::Dotted { spacing })
+    }
+
+    fn scale(&mut self, factor: f32) {
+        self.width *= factor;
+        match &mut self.pattern {
+            StrokePattern::Solid => {}
+            StrokePattern::Dashed { dash, gap } => {
+                *dash *= factor;
+                *gap *= factor;
+            }
+            StrokePattern::Dotted { spacing } => *spacing *= factor,
         }
     }
 }
 
+/// How a `Stroke` is broken up along its path.
+///
+/// The pattern continues around the corners of a path,
+/// and starts over for each new `PaintCmd`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum StrokePattern {
+    /// An unbroken line.
+    #[default]
+    Solid,
+    /// Dashes of length `dash` separated by gaps of length `gap`, in points.
+    Dashed { dash: f32, gap: f32 },
+    /// Round dots, as wide as the stroke, with their centers `spacing` points apart.
+    Dotted { spacing: f32 },
+}
+
 impl<Color> From<(f32, Color)> for Stroke
 where
     Color: Into<Srgba>,
@@ -212,3 +421,184 @@ where
         Stroke::new(width, color)
     }
 }
+
+/// The radius of each corner of a rectangle, in points.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct CornerRadius {
+    /// Top left
+    pub nw: f32,
+    /// Top right
+    pub ne: f32,
+    /// Bottom left
+    pub sw: f32,
+    /// Bottom right
+    pub se: f32,
+}
+
+impl CornerRadius {
+    /// All corners the same.
+    pub fn same(radius: f32) -> Self {
+        Self {
+            nw: radius,
+            ne: radius,
+            sw: radius,
+            se: radius,
+        }
+    }
+
+    /// Round only the top corners, e.g. for a tab.
+    pub fn top(radius: f32) -> Self {
+        Self {
+            nw: radius,
+            ne: radius,
+            ..Default::default()
+        }
+    }
+
+    /// Round only the bottom corners.
+    pub fn bottom(radius: f32) -> Self {
+        Self {
+            sw: radius,
+            se: radius,
+            ..Default::default()
+        }
+    }
+
+    pub fn max(&self) -> f32 {
+        self.nw.max(self.ne).max(self.sw).max(self.se)
+    }
+
+    /// No corner will be rounder than this.
+    pub fn at_most(self, max: f32) -> Self {
+        Self {
+            nw: self.nw.min(max),
+            ne: self.ne.min(max),
+            sw: self.sw.min(max),
+            se: self.se.min(max),
+        }
+    }
+
+    fn scale(&mut self, factor: f32) {
+        self.nw *= factor;
+        self.ne *= factor;
+        self.sw *= factor;
+        self.se *= factor;
+    }
+}
+
+impl From<f32> for CornerRadius {
+    fn from(radius: f32) -> Self {
+        Self::same(radius)
+    }
+}
+
+/// How to fill the inside of a shape.
+///
+/// Gradients go across the bounding rectangle of the shape,
+/// and are interpolated in linear space, just like the GPU interpolates vertex colors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Fill {
+    Color(Srgba),
+    /// From `from` on one side of the shape to `to` on the opposite side.
+    LinearGradient {
+        /// From the `from` side towards the `to` side, e.g. `vec2(0.0, 1.0)` for top-to-bottom.
+        direction: Vec2,
+        from: Srgba,
+        to: Srgba,
+    },
+    /// From `inner` at the center of the shape to `outer` at its corners.
+    RadialGradient {
+        inner: Srgba,
+        outer: Srgba,
+    },
+}
+
+impl Default for Fill {
+    fn default() -> Self {
+        Self::Color(Default::default())
+    }
+}
+
+impl From<Srgba> for Fill {
+    fn from(color: Srgba) -> Self {
+        Self::Color(color)
+    }
+}
+
+impl From<super::Rgba> for Fill {
+    fn from(color: super::Rgba) -> Self {
+        Self::Color(color.into())
+    }
+}
+
+impl Fill {
+    /// From `top` to `bottom`.
+    pub fn vertical_gradient(top: impl Into<Srgba>, bottom: impl Into<Srgba>) -> Self {
+        Self::LinearGradient {
+            direction: vec2(0.0, 1.0),
+            from: top.into(),
+            to: bottom.into(),
+        }
+    }
+
+    /// From `left` to `right`.
+    pub fn horizontal_gradient(left: impl Into<Srgba>, right: impl Into<Srgba>) -> Self {
+        Self::LinearGradient {
+            direction: vec2(1.0, 0.0),
+            from: left.into(),
+            to: right.into(),
+        }
+    }
+
+    pub fn radial_gradient(inner: impl Into<Srgba>, outer: impl Into<Srgba>) -> Self {
+        Self::RadialGradient {
+            inner: inner.into(),
+            outer: outer.into(),
+        }
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        use crate::color::TRANSPARENT;
+        match *self {
+            Fill::Color(color) => color == TRANSPARENT,
+            Fill::LinearGradient { from, to, .. } => from == TRANSPARENT && to == TRANSPARENT,
+            Fill::RadialGradient { inner, outer } => inner == TRANSPARENT && outer == TRANSPARENT,
+        }
+    }
+
+    /// The color at `pos`, for a shape whose bounding rectangle is `rect`.
+    pub fn color_at(&self, rect: Rect, pos: Pos2) -> Srgba {
+        let (from, to, t) = match *self {
+            Fill::Color(color) => return color,
+            Fill::LinearGradient {
+                direction,
+                from,
+                to,
+            } => {
+                // The gradient goes from one corner to the opposite one, like in CSS:
+                let direction = direction.normalized();
+                let half_length = (rect.width() * direction.x).abs() / 2.0
+                    + (rect.height() * direction.y).abs() / 2.0;
+                let offset = pos - rect.center();
+                let t = (offset.x * direction.x + offset.y * direction.y)
+                    / half_length.max(f32::EPSILON);
+                (from, to, 0.5 + 0.5 * t)
+            }
+            Fill::RadialGradient { inner, outer } => {
+                let half_size = 0.5 * rect.size();
+                let offset = pos - rect.center();
+                let t = vec2(
+                    offset.x / half_size.x.max(f32::EPSILON),
+                    offset.y / half_size.y.max(f32::EPSILON),
+                )
+                .length()
+                    / std::f32::consts::SQRT_2;
+                (inner, outer, t)
+            }
+        };
+        let t = clamp(t, 0.0..=1.0);
+        Srgba::from(lerp(super::Rgba::from(from)..=super::Rgba::from(to), t))
+    }
+}
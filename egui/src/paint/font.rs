@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
 use {
     ahash::AHashMap,
@@ -11,7 +11,7 @@ use crate::{
     mutex::Mutex,
 };
 
-use super::texture_atlas::TextureAtlas;
+use super::{color::Srgba, fonts::TextStyle, texture_atlas::TextureAtlas};
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct GalleyCursor {
@@ -35,6 +35,28 @@ pub struct Galley {
 
     // Optimization: calculate once and reuse.
     pub size: Vec2,
+
+    /// Different styles for different parts of the text, sorted by `char_range`.
+    /// If empty, all of the text uses the `TextStyle` and color it is painted with.
+    pub sections: Vec<TextSection>,
+}
+
+/// How to paint a range of characters in a `Galley`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSection {
+    /// Character range (NOT bytes) in `Galley::text`.
+    pub char_range: Range<usize>,
+
+    pub text_style: TextStyle,
+
+    pub color: Srgba,
+
+    /// Paint a line under the text.
+    pub underline: bool,
+
+    /// Paint this color behind the text.
+    /// `TRANSPARENT` means no background.
+    pub background: Srgba,
 }
 
 /// A typeset piece of text on a single line.
@@ -67,6 +89,33 @@ impl Galley {
             char_count += line.char_count();
         }
         assert_eq!(char_count, self.text.chars().count());
+        for section in &self.sections {
+            assert!(section.char_range.end <= char_count);
+        }
+    }
+
+    /// Index into `sections` of the section containing the given character, if any.
+    pub fn section_idx_at(&self, char_idx: usize) -> Option<usize> {
+        self.sections
+            .iter()
+            .position(|section| section.char_range.contains(&char_idx))
+    }
+
+    /// Index of the character covering the given position within the galley, if any.
+    /// Unlike `char_at` this does not snap to the closest cursor position.
+    pub fn char_under(&self, pos: Vec2) -> Option<usize> {
+        let mut char_count = 0;
+        for line in &self.lines {
+            if line.y_min <= pos.y && pos.y < line.y_max {
+                let column = line
+                    .x_offsets
+                    .windows(2)
+                    .position(|x| x[0] <= pos.x && pos.x < x[1])?;
+                return Some(char_count + column);
+            }
+            char_count += line.char_count();
+        }
+        None
     }
 
     /// If given a char index after the first line, the end of the last character is returned instead.
@@ -97,7 +146,11 @@ impl Galley {
 
         let mut char_count = 0;
         for (line_nr, line) in self.lines.iter().enumerate() {
-            let y_dist = (line.y_min - pos.y).abs().min((line.y_max - pos.y).abs());
+            let y_dist = if line.y_min <= pos.y && pos.y < line.y_max {
+                0.0
+            } else {
+                (line.y_min - pos.y).abs().min((line.y_max - pos.y).abs())
+            };
             if y_dist < best_y_dist {
                 best_y_dist = y_dist;
                 let mut column = line.char_at(pos.x);
@@ -154,6 +207,10 @@ impl Line {
 // const REPLACEMENT_CHAR: char = '\u{FFFD}'; // � REPLACEMENT CHARACTER
 const REPLACEMENT_CHAR: char = '?';
 
+/// How far above the bottom of a line the baseline of its glyphs is, in points.
+/// Egui aligns the bottom of all fonts on the same line.
+pub const BASELINE_HEIGHT: f32 = 4.0;
+
 #[derive(Clone, Copy, Debug)]
 pub struct UvRect {
     /// X/Y offset for nice rendering (unit: points).
@@ -300,6 +357,7 @@ impl Font {
             text,
             lines: vec![line],
             size,
+            sections: vec![],
         };
         galley.sanity_check();
         galley
@@ -352,7 +410,12 @@ impl Font {
         }
         let size = vec2(widest_line, lines.last().unwrap().y_max);
 
-        let galley = Galley { text, lines, size };
+        let galley = Galley {
+            text,
+            lines,
+            size,
+            sections: vec![],
+        };
         galley.sanity_check();
         galley
     }
@@ -360,7 +423,7 @@ impl Font {
     /// Typeset the given text onto one line.
     /// Assumes there are no \n in the text.
     /// Return `x_offsets`, one longer than the number of characters in the text.
-    fn layout_single_line_fragment(&self, text: &str) -> Vec<f32> {
+    pub(crate) fn layout_single_line_fragment(&self, text: &str) -> Vec<f32> {
         let scale_in_pixels = Scale::uniform(self.scale_in_pixels);
 
         let mut x_offsets = Vec::with_capacity(text.chars().count() + 1);
@@ -393,68 +456,13 @@ impl Font {
     pub fn layout_paragraph_max_width(&self, text: &str, max_width_in_points: f32) -> Vec<Line> {
         let full_x_offsets = self.layout_single_line_fragment(text);
 
-        let mut line_start_x = full_x_offsets[0];
-
-        {
-            #![allow(clippy::float_cmp)]
-            assert_eq!(line_start_x, 0.0);
-        }
-
         let mut cursor_y = 0.0;
-        let mut line_start_idx = 0;
-
-        // start index of the last space. A candidate for a new line.
-        let mut last_space = None;
-
         let mut out_lines = vec![];
 
-        for (i, (x, chr)) in full_x_offsets.iter().skip(1).zip(text.chars()).enumerate() {
-            let line_width = x - line_start_x;
-
-            if line_width > max_width_in_points {
-                if let Some(last_space_idx) = last_space {
-                    let include_trailing_space = true;
-                    let line = if include_trailing_space {
-                        Line {
-                            x_offsets: full_x_offsets[line_start_idx..=last_space_idx + 1]
-                                .iter()
-                                .map(|x| x - line_start_x)
-                                .collect(),
-                            y_min: cursor_y,
-                            y_max: cursor_y + self.height(),
-                            ends_with_newline: false, // we'll fix this later
-                        }
-                    } else {
-                        Line {
-                            x_offsets: full_x_offsets[line_start_idx..=last_space_idx]
-                                .iter()
-                                .map(|x| x - line_start_x)
-                                .collect(),
-                            y_min: cursor_y,
-                            y_max: cursor_y + self.height(),
-                            ends_with_newline: false, // we'll fix this later
-                        }
-                    };
-                    line.sanity_check();
-                    out_lines.push(line);
-
-                    line_start_idx = last_space_idx + 1;
-                    line_start_x = full_x_offsets[line_start_idx];
-                    last_space = None;
-                    cursor_y += self.line_spacing();
-                    cursor_y = self.round_to_pixel(cursor_y);
-                }
-            }
-
-            const NON_BREAKING_SPACE: char = '\u{A0}';
-            if chr.is_whitespace() && chr != NON_BREAKING_SPACE {
-                last_space = Some(i);
-            }
-        }
-
-        if line_start_idx + 1 < full_x_offsets.len() {
+        for row in wrap_paragraph(text, &full_x_offsets, max_width_in_points) {
+            let line_start_x = full_x_offsets[row.start];
             let line = Line {
-                x_offsets: full_x_offsets[line_start_idx..]
+                x_offsets: full_x_offsets[row.start..=row.end]
                     .iter()
                     .map(|x| x - line_start_x)
                     .collect(),
@@ -464,6 +472,9 @@ impl Font {
             };
             line.sanity_check();
             out_lines.push(line);
+
+            cursor_y += self.line_spacing();
+            cursor_y = self.round_to_pixel(cursor_y);
         }
 
         if text.ends_with('\n') {
@@ -474,6 +485,58 @@ impl Font {
     }
 }
 
+/// Word-wrap a paragraph (text with no line break in it, except maybe at the end).
+///
+/// `full_x_offsets` are the x offsets of the whole paragraph layed out on one line,
+/// i.e. one longer than the number of characters in `text`.
+///
+/// Returns the character range of each resulting line.
+pub(crate) fn wrap_paragraph(
+    text: &str,
+    full_x_offsets: &[f32],
+    max_width_in_points: f32,
+) -> Vec<Range<usize>> {
+    let mut line_start_x = full_x_offsets[0];
+
+    {
+        #![allow(clippy::float_cmp)]
+        assert_eq!(line_start_x, 0.0);
+    }
+
+    let mut line_start_idx = 0;
+
+    // start index of the last space. A candidate for a new line.
+    let mut last_space = None;
+
+    let mut rows = vec![];
+
+    for (i, (x, chr)) in full_x_offsets.iter().skip(1).zip(text.chars()).enumerate() {
+        let line_width = x - line_start_x;
+
+        if line_width > max_width_in_points {
+            if let Some(last_space_idx) = last_space {
+                // Include the trailing space:
+                rows.push(line_start_idx..last_space_idx + 1);
+
+                line_start_idx = last_space_idx + 1;
+                line_start_x = full_x_offsets[line_start_idx];
+                last_space = None;
+            }
+        }
+
+        const NON_BREAKING_SPACE: char = '\u{A0}';
+        if chr.is_whitespace() && chr != NON_BREAKING_SPACE {
+            last_space = Some(i);
+        }
+    }
+
+    if line_start_idx + 1 < full_x_offsets.len() {
+        rows.push(line_start_idx..full_x_offsets.len() - 1);
+    }
+
+    rows
+}
+
 fn allocate_glyph(
     atlas: &mut TextureAtlas,
     c: char,
@@ -506,7 +569,8 @@ fn allocate_glyph(
             }
         });
 
-        let offset_y_in_pixels = scale_in_pixels as f32 + bb.min.y as f32 - 4.0 * pixels_per_point; // TODO: use font.v_metrics
+        let offset_y_in_pixels =
+            scale_in_pixels as f32 + bb.min.y as f32 - BASELINE_HEIGHT * pixels_per_point; // TODO: use font.v_metrics
         Some(UvRect {
             offset: vec2(
                 bb.min.x as f32 / pixels_per_point,
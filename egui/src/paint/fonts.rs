@@ -1,13 +1,17 @@
 use std::{
     collections::BTreeMap,
     hash::{Hash, Hasher},
+    ops::Range,
     sync::Arc,
 };
 
-use crate::mutex::Mutex;
+use crate::{
+    math::{vec2, Vec2},
+    mutex::Mutex,
+};
 
 use super::{
-    font::Font,
+    font::{wrap_paragraph, Font, Galley, Line, TextSection},
     texture_atlas::{Texture, TextureAtlas},
 };
 
@@ -74,6 +78,13 @@ impl FontDefinitions {
     }
 }
 
+/// Zoomed fonts are rasterized at this many steps per doubling of the size.
+const SCALE_STEPS_PER_OCTAVE: f32 = 8.0;
+
+/// Zoomed fonts are rasterized at most this much smaller or larger (in octaves).
+/// Beyond that the glyphs are stretched.
+const MAX_SCALE_OCTAVES: f32 = 2.0;
+
 /// Note: the `default()` fonts are invalid (missing `pixels_per_point`).
 #[derive(Default)]
 pub struct Fonts {
@@ -83,6 +94,8 @@ pub struct Fonts {
     /// Copy of the texture in the texture atlas.
     /// This is so we can return a reference to it (the texture atlas is behind a lock).
     buffered_texture: Mutex<Arc<Texture>>,
+    /// Fonts for zoomed text, see `Fonts::scaled`. Key is the number of scale steps.
+    scaled: Mutex<BTreeMap<i32, Arc<Fonts>>>,
 }
 
 impl Fonts {
@@ -113,22 +126,7 @@ impl Fonts {
         let atlas = Arc::new(Mutex::new(atlas));
 
         self.definitions = definitions.clone();
-        let FontDefinitions {
-            pixels_per_point,
-            fonts,
-            ttf_data,
-        } = definitions;
-        self.fonts = fonts
-            .into_iter()
-            .map(|(text_style, (family, size))| {
-                let typeface_data = ttf_data
-                    .get(&family)
-                    .unwrap_or_else(|| panic!("Missing TTF data for {:?}", family));
-                let font = Font::new(atlas.clone(), typeface_data, size, pixels_per_point);
-
-                (text_style, font)
-            })
-            .collect();
+        self.fonts = create_fonts(&atlas, definitions);
 
         {
             let mut atlas = atlas.lock();
@@ -141,6 +139,36 @@ impl Fonts {
 
         self.buffered_texture = Default::default(); //atlas.lock().texture().clone();
         self.atlas = atlas;
+        self.scaled = Default::default();
+    }
+
+    /// Fonts for text that is painted `scale` times larger than normal, e.g. in a zoomed `Scene`.
+    ///
+    /// Text laid out with these is measured in the same points as normal text,
+    /// but is rounded to and rasterized at the zoomed pixel grid, so it stays sharp.
+    /// The scale is rounded to an eighth of an octave to limit the number of glyphs in the texture.
+    ///
+    /// Returns `None` if the scale rounds to `1.0`. Then use these `Fonts` instead.
+    pub fn scaled(&self, scale: f32) -> Option<Arc<Fonts>> {
+        let max_steps = MAX_SCALE_OCTAVES * SCALE_STEPS_PER_OCTAVE;
+        let steps = (scale.log2() * SCALE_STEPS_PER_OCTAVE).round();
+        if !steps.is_finite() || steps == 0.0 {
+            return None;
+        }
+        let steps = steps.clamp(-max_steps, max_steps);
+
+        let mut scaled = self.scaled.lock();
+        let fonts = scaled.entry(steps as i32).or_insert_with(|| {
+            let mut definitions = self.definitions.clone();
+            definitions.pixels_per_point *= 2_f32.powf(steps / SCALE_STEPS_PER_OCTAVE);
+            Arc::new(Fonts {
+                fonts: create_fonts(&self.atlas, definitions.clone()),
+                definitions,
+                atlas: self.atlas.clone(),
+                ..Default::default()
+            })
+        });
+        Some(fonts.clone())
     }
 
     pub fn texture(&self) -> Arc<Texture> {
@@ -152,6 +180,151 @@ impl Fonts {
 
         buffered_texture.clone()
     }
+
+    /// Lay out text where different parts use different `TextStyle`:s.
+    ///
+    /// The `sections` must be sorted, non-overlapping, and together cover all of `text`.
+    /// Lines are word-wrapped at `max_width_in_points`.
+    /// Each line is as high as its tallest font, and all text on a line shares a baseline.
+    pub fn layout_sections(
+        &self,
+        text: String,
+        sections: Vec<TextSection>,
+        max_width_in_points: f32,
+    ) -> Galley {
+        let mut char_byte_offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+        char_byte_offsets.push(text.len());
+        let char_count = char_byte_offsets.len() - 1;
+
+        let mut next_char = 0;
+        for section in &sections {
+            assert_eq!(section.char_range.start, next_char, "Gap between sections");
+            next_char = section.char_range.end;
+        }
+        assert_eq!(next_char, char_count, "Sections must cover all text");
+
+        // The sections overlapping the given char range:
+        let sections_in = |char_range: Range<usize>| {
+            let first = sections.partition_point(|s| s.char_range.end <= char_range.start);
+            sections[first..]
+                .iter()
+                .take_while(move |s| s.char_range.start < char_range.end)
+        };
+
+        // Height of the tallest font used by any character in the range:
+        let line_height = |char_range: Range<usize>| -> f32 {
+            sections_in(char_range)
+                .map(|s| self[s.text_style].height())
+                .fold(0.0, f32::max)
+        };
+
+        let mut cursor_y = 0.0;
+        let mut lines = Vec::new();
+        let mut paragraph_start = 0; // in chars
+
+        while paragraph_start < char_count {
+            let paragraph_end = (paragraph_start..char_count)
+                .find(|&i| text[char_byte_offsets[i]..].starts_with('\n'))
+                .map(|newline| newline + 1)
+                .unwrap_or(char_count);
+            let paragraph_text =
+                &text[char_byte_offsets[paragraph_start]..char_byte_offsets[paragraph_end]];
+
+            let mut full_x_offsets = vec![0.0];
+            for section in sections_in(paragraph_start..paragraph_end) {
+                let start = section.char_range.start.max(paragraph_start);
+                let end = section.char_range.end.min(paragraph_end);
+                if start < end {
+                    let fragment = &text[char_byte_offsets[start]..char_byte_offsets[end]];
+                    let x_offsets = self[section.text_style].layout_single_line_fragment(fragment);
+                    let start_x = *full_x_offsets.last().unwrap();
+                    full_x_offsets.extend(x_offsets[1..].iter().map(|x| start_x + x));
+                }
+            }
+
+            let mut height = 0.0;
+            for row in wrap_paragraph(paragraph_text, &full_x_offsets, max_width_in_points) {
+                height = line_height(paragraph_start + row.start..paragraph_start + row.end);
+                let line_start_x = full_x_offsets[row.start];
+                let line = Line {
+                    x_offsets: full_x_offsets[row.start..=row.end]
+                        .iter()
+                        .map(|x| x - line_start_x)
+                        .collect(),
+                    y_min: cursor_y,
+                    y_max: cursor_y + height,
+                    ends_with_newline: false,
+                };
+                line.sanity_check();
+                lines.push(line);
+                cursor_y = self.round_to_pixel(cursor_y + height);
+            }
+
+            if paragraph_text.ends_with('\n') {
+                lines.last_mut().unwrap().ends_with_newline = true;
+            }
+            cursor_y = lines.last().unwrap().y_max;
+            cursor_y += height * 0.4; // extra spacing between paragraphs
+
+            paragraph_start = paragraph_end;
+        }
+
+        if text.is_empty() || text.ends_with('\n') {
+            // Add an empty last line for correct visuals etc:
+            let height = sections
+                .last()
+                .map(|s| self[s.text_style].height())
+                .unwrap_or_else(|| self[TextStyle::Body].height());
+            lines.push(Line {
+                x_offsets: vec![0.0],
+                y_min: cursor_y,
+                y_max: cursor_y + height,
+                ends_with_newline: text.ends_with('\n'),
+            });
+        }
+
+        let mut widest_line = 0.0;
+        for line in &lines {
+            widest_line = line.max_x().max(widest_line);
+        }
+        let size: Vec2 = vec2(widest_line, lines.last().unwrap().y_max);
+
+        let galley = Galley {
+            text,
+            lines,
+            size,
+            sections,
+        };
+        galley.sanity_check();
+        galley
+    }
+
+    pub(crate) fn round_to_pixel(&self, point: f32) -> f32 {
+        let pixels_per_point = self.definitions.pixels_per_point;
+        (point * pixels_per_point).round() / pixels_per_point
+    }
+}
+
+fn create_fonts(
+    atlas: &Arc<Mutex<TextureAtlas>>,
+    definitions: FontDefinitions,
+) -> BTreeMap<TextStyle, Font> {
+    let FontDefinitions {
+        pixels_per_point,
+        fonts,
+        ttf_data,
+    } = definitions;
+    fonts
+        .into_iter()
+        .map(|(text_style, (family, size))| {
+            let typeface_data = ttf_data
+                .get(&family)
+                .unwrap_or_else(|| panic!("Missing TTF data for {:?}", family));
+            let font = Font::new(atlas.clone(), typeface_data, size, pixels_per_point);
+
+            (text_style, font)
+        })
+        .collect()
 }
 
 impl std::ops::Index<TextStyle> for Fonts {
@@ -161,3 +334,37 @@ impl std::ops::Index<TextStyle> for Fonts {
         &self.fonts[&text_style]
     }
 }
+
+#[test]
+fn test_layout_sections() {
+    let fonts = Fonts::from_definitions(FontDefinitions::with_pixels_per_point(1.0));
+    let section = |char_range: Range<usize>, text_style| TextSection {
+        char_range,
+        text_style,
+        color: super::color::WHITE,
+        underline: false,
+        background: super::color::TRANSPARENT,
+    };
+    let text = "small words BIG WORDS".to_owned();
+    let sections = vec![
+        section(0..12, TextStyle::Small),
+        section(12..21, TextStyle::Heading),
+    ];
+    let galley = fonts.layout_sections(text, sections.clone(), 100.0);
+    assert_eq!(galley.sections, sections);
+
+    // Wrapped between the words, not at the section boundary:
+    let line_chars: Vec<usize> = galley.lines.iter().map(|line| line.char_count()).collect();
+    assert_eq!(line_chars, vec![12, 4, 5]);
+
+    // A line is as high as its tallest font:
+    let height = |line: &Line| line.y_max - line.y_min;
+    assert_eq!(height(&galley.lines[0]), fonts[TextStyle::Small].height());
+    assert_eq!(
+        height(galley.lines.last().unwrap()),
+        fonts[TextStyle::Heading].height()
+    );
+    for line in &galley.lines {
+        assert!(line.max_x() <= 100.0);
+    }
+}
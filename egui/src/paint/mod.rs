@@ -6,17 +6,23 @@ pub mod color;
 pub mod command;
 pub mod font;
 pub mod fonts;
+mod shadow;
 pub mod stats;
+mod tessellation_cache;
 pub mod tessellator;
 mod texture_atlas;
+mod triangulation;
 
 pub use {
     color::{Rgba, Srgba},
-    command::{PaintCmd, Stroke},
+    command::{CornerRadius, Fill, PaintCmd, Stroke, StrokePattern},
     fonts::{FontDefinitions, FontFamily, Fonts, TextStyle},
+    shadow::Shadow,
     stats::PaintStats,
     tessellator::{
         PaintJob, PaintJobs, TesselationOptions, TextureId, Triangles, Vertex, WHITE_UV,
     },
     texture_atlas::Texture,
 };
+
+pub(crate) use tessellation_cache::TessellationCache;
@@ -0,0 +1,82 @@
+use super::{tessellator, CornerRadius, Srgba, Triangles};
+use crate::math::*;
+
+/// A soft drop shadow beneath a rectangle, e.g. a window.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Shadow {
+    /// Move the shadow this much relative to what casts it,
+    /// e.g. down and to the right for a light from the top left.
+    pub offset: Vec2,
+
+    /// How far out the shadow reaches, fading out as it goes.
+    pub extrusion: f32,
+
+    /// Color of the shadow where it is darkest, i.e. beneath the rectangle.
+    pub color: Srgba,
+}
+
+impl Shadow {
+    /// For small things like popups and menus.
+    pub fn small() -> Self {
+        Self {
+            offset: vec2(2.0, 4.0),
+            extrusion: 8.0,
+            color: Srgba::black_alpha(96),
+        }
+    }
+
+    /// For windows.
+    pub fn big() -> Self {
+        Self {
+            offset: vec2(4.0, 8.0),
+            extrusion: 24.0,
+            color: Srgba::black_alpha(96),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.color != crate::color::TRANSPARENT
+    }
+
+    /// How far out from the rectangle the shadow can be seen.
+    pub fn reach(&self) -> f32 {
+        self.extrusion + self.offset.x.abs().max(self.offset.y.abs())
+    }
+
+    /// The shadow of a (rounded) rectangle.
+    pub fn tessellate(&self, rect: Rect, corner_radius: impl Into<CornerRadius>) -> Triangles {
+        tessellator::feathered_rect(
+            rect.translate(self.offset),
+            corner_radius.into(),
+            self.color,
+            self.extrusion,
+        )
+    }
+}
+
+#[test]
+fn test_shadow() {
+    let shadow = Shadow {
+        offset: vec2(2.0, 4.0),
+        extrusion: 8.0,
+        color: Srgba::black_alpha(100),
+    };
+    let rect = Rect::from_min_size(pos2(10.0, 10.0), vec2(100.0, 50.0));
+    let triangles = shadow.tessellate(rect, 5.0);
+    assert!(!triangles.vertices.is_empty());
+
+    // Darkest beneath the rectangle, fading out to nothing `extrusion` beyond it:
+    let shadow_rect = rect.translate(shadow.offset);
+    for vertex in &triangles.vertices {
+        if vertex.color == shadow.color {
+            assert!(shadow_rect.expand(0.1).contains(vertex.pos));
+        } else {
+            assert_eq!(vertex.color, crate::color::TRANSPARENT);
+            assert!(!shadow_rect.contains(vertex.pos));
+            assert!(shadow_rect
+                .expand(shadow.extrusion + 0.1)
+                .contains(vertex.pos));
+        }
+    }
+}
@@ -59,15 +59,23 @@ impl AllocInfo {
             PaintCmd::Noop
             | PaintCmd::Circle { .. }
             | PaintCmd::LineSegment { .. }
-            | PaintCmd::Rect { .. } => Self::default(),
+            | PaintCmd::Rect { .. }
+            | PaintCmd::QuadraticBezier { .. }
+            | PaintCmd::CubicBezier { .. } => Self::default(),
             PaintCmd::Path { points, .. } => Self::from_slice(points),
+            PaintCmd::PolygonWithHoles { points, holes, .. } => holes.iter().fold(
+                Self::from_slice(points) + Self::from_slice(holes),
+                |sum, hole| sum + Self::from_slice(hole),
+            ),
             PaintCmd::Text { galley, .. } => Self::from_galley(galley),
             PaintCmd::Triangles(triangles) => Self::from_triangles(triangles),
         }
     }
 
     pub fn from_galley(galley: &font::Galley) -> Self {
-        Self::from_slice(galley.text.as_bytes()) + Self::from_slice(&galley.lines)
+        Self::from_slice(galley.text.as_bytes())
+            + Self::from_slice(&galley.lines)
+            + Self::from_slice(&galley.sections)
     }
 
     pub fn from_triangles(triangles: &Triangles) -> Self {
@@ -154,10 +162,15 @@ impl PaintStats {
                 PaintCmd::Noop
                 | PaintCmd::Circle { .. }
                 | PaintCmd::LineSegment { .. }
-                | PaintCmd::Rect { .. } => Default::default(),
+                | PaintCmd::Rect { .. }
+                | PaintCmd::QuadraticBezier { .. }
+                | PaintCmd::CubicBezier { .. } => Default::default(),
                 PaintCmd::Path { points, .. } => {
                     stats.cmd_path += AllocInfo::from_slice(points);
                 }
+                PaintCmd::PolygonWithHoles { .. } => {
+                    stats.cmd_path += AllocInfo::from_paint_cmd(cmd);
+                }
                 PaintCmd::Text { galley, .. } => {
                     stats.cmd_text += AllocInfo::from_galley(galley);
                 }
@@ -0,0 +1,395 @@
+//! Reuse the triangles of paint commands that have not changed since last frame.
+
+use std::hash::{Hash, Hasher};
+
+use ahash::{AHashMap, AHasher};
+
+use super::{
+    font::Galley,
+    tessellator::{tessellate_paint_commands, PaintJobs, TesselationOptions, Triangles},
+    CornerRadius, Fill, Fonts, PaintCmd, Stroke, StrokePattern,
+};
+use crate::math::*;
+
+/// Remembers the tessellation of each run of paint commands with the same clip rectangle,
+/// by a hash of what went into it.
+#[derive(Clone, Default)]
+pub(crate) struct TessellationCache {
+    runs: AHashMap<u64, PaintJobs>,
+}
+
+impl TessellationCache {
+    /// Tessellate the given paint commands.
+    /// Runs of commands with the same clip rectangle that have the same commands and options
+    /// as a run last time are not tessellated again.
+    pub fn tessellate(
+        &mut self,
+        commands: Vec<(Rect, PaintCmd)>,
+        options: TesselationOptions,
+        fonts: &Fonts,
+    ) -> PaintJobs {
+        // New glyphs in the font texture can move the old ones around:
+        let texture_version = fonts.texture().version;
+
+        let old_runs = std::mem::take(&mut self.runs);
+        let tessellate_run = |commands: Vec<(Rect, PaintCmd)>| {
+            let mut hasher = AHasher::default();
+            texture_version.hash(&mut hasher);
+            options.hash_bits(&mut hasher);
+            for (clip_rect, cmd) in &commands {
+                clip_rect.hash_bits(&mut hasher);
+                cmd.hash_bits(&mut hasher);
+            }
+            let hash = hasher.finish();
+
+            let new_jobs = if old_runs.contains_key(&hash) {
+                None
+            } else {
+                Some(tessellate_paint_commands(commands, options, fonts))
+            };
+            (hash, new_jobs)
+        };
+
+        let runs = clip_rect_runs(commands);
+        #[cfg(feature = "rayon")]
+        let runs: Vec<_> = {
+            use rayon::prelude::*;
+            runs.into_par_iter().map(tessellate_run).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let runs: Vec<_> = runs.into_iter().map(tessellate_run).collect();
+
+        // Runs that are gone are forgotten:
+        let mut jobs = PaintJobs::default();
+        for (hash, new_jobs) in runs {
+            let run_jobs = new_jobs.unwrap_or_else(|| old_runs[&hash].clone());
+            jobs.extend(run_jobs.iter().cloned());
+            self.runs.insert(hash, run_jobs);
+        }
+        jobs
+    }
+}
+
+/// Split the commands where the clip rectangle changes.
+fn clip_rect_runs(commands: Vec<(Rect, PaintCmd)>) -> Vec<Vec<(Rect, PaintCmd)>> {
+    let mut runs: Vec<Vec<(Rect, PaintCmd)>> = vec![];
+    for (clip_rect, cmd) in commands {
+        match runs.last_mut() {
+            Some(run) if run[0].0 == clip_rect => run.push((clip_rect, cmd)),
+            _ => runs.push(vec![(clip_rect, cmd)]),
+        }
+    }
+    runs
+}
+
+// ----------------------------------------------------------------------------
+
+/// Like `Hash`, but floats are hashed by their bits.
+trait HashBits {
+    fn hash_bits(&self, state: &mut AHasher);
+}
+
+impl HashBits for f32 {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self.to_bits().hash(state);
+    }
+}
+
+impl HashBits for Vec2 {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self.x.hash_bits(state);
+        self.y.hash_bits(state);
+    }
+}
+
+impl HashBits for Pos2 {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self.x.hash_bits(state);
+        self.y.hash_bits(state);
+    }
+}
+
+impl HashBits for Rect {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self.min.hash_bits(state);
+        self.max.hash_bits(state);
+    }
+}
+
+impl<T: HashBits> HashBits for [T] {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self.len().hash(state);
+        for value in self {
+            value.hash_bits(state);
+        }
+    }
+}
+
+impl<T: HashBits, const N: usize> HashBits for [T; N] {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self[..].hash_bits(state);
+    }
+}
+
+impl<T: HashBits> HashBits for Vec<T> {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self.as_slice().hash_bits(state);
+    }
+}
+
+impl HashBits for Stroke {
+    fn hash_bits(&self, state: &mut AHasher) {
+        let Self {
+            width,
+            color,
+            pattern,
+        } = self;
+        width.hash_bits(state);
+        color.hash(state);
+        std::mem::discriminant(pattern).hash(state);
+        match pattern {
+            StrokePattern::Solid => {}
+            StrokePattern::Dashed { dash, gap } => {
+                dash.hash_bits(state);
+                gap.hash_bits(state);
+            }
+            StrokePattern::Dotted { spacing } => spacing.hash_bits(state),
+        }
+    }
+}
+
+impl HashBits for CornerRadius {
+    fn hash_bits(&self, state: &mut AHasher) {
+        [self.nw, self.ne, self.sw, self.se].hash_bits(state);
+    }
+}
+
+impl HashBits for Fill {
+    fn hash_bits(&self, state: &mut AHasher) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Fill::Color(color) => color.hash(state),
+            Fill::LinearGradient {
+                direction,
+                from,
+                to,
+            } => {
+                direction.hash_bits(state);
+                from.hash(state);
+                to.hash(state);
+            }
+            Fill::RadialGradient { inner, outer } => {
+                inner.hash(state);
+                outer.hash(state);
+            }
+        }
+    }
+}
+
+impl HashBits for Galley {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self.text.hash(state);
+        self.size.hash_bits(state);
+        self.lines.len().hash(state);
+        for line in &self.lines {
+            line.x_offsets.hash_bits(state);
+            line.y_min.hash_bits(state);
+            line.y_max.hash_bits(state);
+            line.ends_with_newline.hash(state);
+        }
+        self.sections.len().hash(state);
+        for section in &self.sections {
+            section.char_range.hash(state);
+            section.text_style.hash(state);
+            section.color.hash(state);
+            section.underline.hash(state);
+            section.background.hash(state);
+        }
+    }
+}
+
+impl HashBits for Triangles {
+    fn hash_bits(&self, state: &mut AHasher) {
+        self.indices.hash(state);
+        self.vertices.len().hash(state);
+        for vertex in &self.vertices {
+            vertex.pos.hash_bits(state);
+            vertex.uv.hash_bits(state);
+            vertex.color.hash(state);
+        }
+        self.texture_id.hash(state);
+    }
+}
+
+impl HashBits for TesselationOptions {
+    fn hash_bits(&self, state: &mut AHasher) {
+        let Self {
+            aa_size,
+            anti_alias,
+            coarse_tessellation_culling,
+            debug_paint_clip_rects,
+            debug_ignore_clip_rects,
+        } = self;
+        aa_size.hash_bits(state);
+        anti_alias.hash(state);
+        coarse_tessellation_culling.hash(state);
+        debug_paint_clip_rects.hash(state);
+        debug_ignore_clip_rects.hash(state);
+    }
+}
+
+impl HashBits for PaintCmd {
+    fn hash_bits(&self, state: &mut AHasher) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            PaintCmd::Noop => {}
+            PaintCmd::Circle {
+                center,
+                radius,
+                fill,
+                stroke,
+            } => {
+                center.hash_bits(state);
+                radius.hash_bits(state);
+                fill.hash(state);
+                stroke.hash_bits(state);
+            }
+            PaintCmd::LineSegment { points, stroke } => {
+                points.hash_bits(state);
+                stroke.hash_bits(state);
+            }
+            PaintCmd::Path {
+                points,
+                closed,
+                fill,
+                stroke,
+            } => {
+                points.hash_bits(state);
+                closed.hash(state);
+                fill.hash(state);
+                stroke.hash_bits(state);
+            }
+            PaintCmd::PolygonWithHoles {
+                points,
+                holes,
+                fill,
+                stroke,
+            } => {
+                points.hash_bits(state);
+                holes.hash_bits(state);
+                fill.hash(state);
+                stroke.hash_bits(state);
+            }
+            PaintCmd::Rect {
+                rect,
+                corner_radius,
+                fill,
+                stroke,
+            } => {
+                rect.hash_bits(state);
+                corner_radius.hash_bits(state);
+                fill.hash_bits(state);
+                stroke.hash_bits(state);
+            }
+            PaintCmd::QuadraticBezier {
+                points,
+                closed,
+                fill,
+                stroke,
+            } => {
+                points.hash_bits(state);
+                closed.hash(state);
+                fill.hash(state);
+                stroke.hash_bits(state);
+            }
+            PaintCmd::CubicBezier {
+                points,
+                closed,
+                fill,
+                stroke,
+            } => {
+                points.hash_bits(state);
+                closed.hash(state);
+                fill.hash(state);
+                stroke.hash_bits(state);
+            }
+            PaintCmd::Text {
+                pos,
+                galley,
+                text_style,
+                color,
+                scale,
+            } => {
+                pos.hash_bits(state);
+                galley.hash_bits(state);
+                text_style.hash(state);
+                color.hash(state);
+                scale.hash_bits(state);
+            }
+            PaintCmd::Triangles(triangles) => triangles.hash_bits(state),
+        }
+    }
+}
+
+#[test]
+fn test_tessellation_cache() {
+    use crate::color;
+
+    let fonts = Fonts::from_definitions(super::FontDefinitions::with_pixels_per_point(1.0));
+    let options = TesselationOptions::default();
+    let left_clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(50.0, 100.0));
+    let right_clip_rect = Rect::from_min_max(pos2(50.0, 0.0), pos2(100.0, 100.0));
+    let rect = |x: f32| PaintCmd::Rect {
+        rect: Rect::from_min_size(pos2(x, 0.0), vec2(10.0, 10.0)),
+        corner_radius: 2.0.into(),
+        fill: color::WHITE.into(),
+        stroke: Default::default(),
+    };
+    let hashes = |cache: &TessellationCache| {
+        let mut hashes: Vec<u64> = cache.runs.keys().copied().collect();
+        hashes.sort_unstable();
+        hashes
+    };
+
+    let mut cache = TessellationCache::default();
+    let first = cache.tessellate(
+        vec![(left_clip_rect, rect(0.0)), (right_clip_rect, rect(50.0))],
+        options,
+        &fonts,
+    );
+    let first_hashes = hashes(&cache);
+    assert_eq!(first_hashes.len(), 2, "one run per clip rectangle");
+
+    // Unchanged, so we get the same triangles back:
+    let second = cache.tessellate(
+        vec![(left_clip_rect, rect(0.0)), (right_clip_rect, rect(50.0))],
+        options,
+        &fonts,
+    );
+    assert_eq!(hashes(&cache), first_hashes);
+    assert_eq!(first.len(), second.len());
+    assert_eq!(first[0].1.vertices.len(), second[0].1.vertices.len());
+
+    // One moved, so only that run is tessellated again:
+    let third = cache.tessellate(
+        vec![(left_clip_rect, rect(0.0)), (right_clip_rect, rect(55.0))],
+        options,
+        &fonts,
+    );
+    let third_hashes = hashes(&cache);
+    assert_eq!(
+        first_hashes
+            .iter()
+            .filter(|h| third_hashes.contains(h))
+            .count(),
+        1
+    );
+    assert_eq!(
+        third[1].1.vertices[0].pos.x,
+        second[1].1.vertices[0].pos.x + 5.0
+    );
+
+    // Gone:
+    cache.tessellate(vec![], options, &fonts);
+    assert!(cache.runs.is_empty());
+}
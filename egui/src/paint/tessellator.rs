@@ -9,7 +9,7 @@ use {
     super::{
         color::{self, srgba, Rgba, Srgba, TRANSPARENT},
         fonts::Fonts,
-        PaintCmd, Stroke,
+        triangulation, CornerRadius, Fill, PaintCmd, Stroke, StrokePattern,
     },
     crate::math::*,
 };
@@ -38,6 +38,9 @@ impl Default for TextureId {
 /// (so it doesn't do bilinear blending with bottom right corner).
 pub const WHITE_UV: Pos2 = pos2(0.0, 0.0);
 
+/// How much text is moved from where its galley places it, in points.
+pub const TEXT_OFFSET: Vec2 = vec2(0.0, 1.0); // Eye-balled for buttons. TODO: why is this needed?
+
 /// The vertex type.
 ///
 /// Should be friendly to send to GPU as is.
@@ -254,7 +257,7 @@ impl Triangles {
 
 // ----------------------------------------------------------------------------
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct PathPoint {
     pos: Pos2,
 
@@ -373,29 +376,36 @@ pub(crate) mod path {
     use super::*;
 
     /// overwrites existing points
-    pub fn rounded_rectangle(path: &mut Vec<Pos2>, rect: Rect, corner_radius: f32) {
+    pub fn rounded_rectangle(path: &mut Vec<Pos2>, rect: Rect, corner_radius: CornerRadius) {
         path.clear();
 
         let min = rect.min;
         let max = rect.max;
 
-        let cr = corner_radius
-            .min(rect.width() * 0.5)
-            .min(rect.height() * 0.5);
+        let cr = corner_radius.at_most(rect.width().min(rect.height()) * 0.5);
 
-        if cr <= 0.0 {
-            let min = rect.min;
-            let max = rect.max;
+        if cr.max() <= 0.0 {
             path.reserve(4);
             path.push(pos2(min.x, min.y));
             path.push(pos2(max.x, min.y));
             path.push(pos2(max.x, max.y));
             path.push(pos2(min.x, max.y));
         } else {
-            add_circle_quadrant(path, pos2(max.x - cr, max.y - cr), cr, 0.0);
-            add_circle_quadrant(path, pos2(min.x + cr, max.y - cr), cr, 1.0);
-            add_circle_quadrant(path, pos2(min.x + cr, min.y + cr), cr, 2.0);
-            add_circle_quadrant(path, pos2(max.x - cr, min.y + cr), cr, 3.0);
+            add_corner(path, pos2(max.x, max.y), cr.se, 0.0);
+            add_corner(path, pos2(min.x, max.y), cr.sw, 1.0);
+            add_corner(path, pos2(min.x, min.y), cr.nw, 2.0);
+            add_corner(path, pos2(max.x, min.y), cr.ne, 3.0);
+        }
+    }
+
+    /// A rounded corner of a rectangle, or just the `corner` itself if `radius` is zero.
+    fn add_corner(path: &mut Vec<Pos2>, corner: Pos2, radius: f32, quadrant: f32) {
+        if radius <= 0.0 {
+            path.push(corner);
+        } else {
+            // Towards the inside of the rectangle:
+            let inwards = -Vec2::angled((quadrant + 0.5) * TAU / 4.0) * std::f32::consts::SQRT_2;
+            add_circle_quadrant(path, corner + radius * inwards, radius, quadrant);
         }
     }
 
@@ -433,6 +443,57 @@ pub(crate) mod path {
             path.push(center + radius * Vec2::angled(angle));
         }
     }
+
+    /// Split up the edges of a closed polygon so that none are longer than `max_length`.
+    pub fn subdivide_loop(path: &mut Vec<Pos2>, max_length: f32) {
+        let n = path.len();
+        let mut subdivided = Vec::with_capacity(n);
+        for i in 0..n {
+            let (a, b) = (path[i], path[(i + 1) % n]);
+            let num_segments = (a.distance(b) / max_length).ceil().max(1.0) as usize;
+            subdivided
+                .extend((0..num_segments).map(|j| a + (j as f32 / num_segments as f32) * (b - a)));
+        }
+        *path = subdivided;
+    }
+
+    /// Overwrites existing points with a flattened Bezier curve of any degree,
+    /// e.g. three control points for a quadratic curve and four for a cubic curve.
+    ///
+    /// No point on the curve is further than `tolerance` from the resulting lines.
+    /// The number of lines is found using Wang's formula,
+    /// so larger and more curved curves get more lines.
+    pub fn bezier(path: &mut Vec<Pos2>, control_points: &[Pos2], tolerance: f32) {
+        const MAX_SEGMENTS: f32 = 1024.0;
+
+        path.clear();
+        let degree = control_points.len() - 1;
+        let max_second_difference = control_points
+            .windows(3)
+            .map(|p| (p[0].to_vec2() - 2.0 * p[1].to_vec2() + p[2].to_vec2()).length())
+            .fold(0.0, f32::max);
+        let factor = (degree * degree.saturating_sub(1)) as f32 / 8.0;
+        let n = (factor * max_second_difference / tolerance).sqrt().ceil();
+        let n = if n.is_finite() {
+            n.clamp(1.0, MAX_SEGMENTS) as usize
+        } else {
+            1
+        };
+
+        path.reserve(n + 1);
+        let mut scratch = control_points.to_vec();
+        for i in 0..=n {
+            let t = i as f32 / n as f32;
+            // de Casteljau's algorithm:
+            scratch.copy_from_slice(control_points);
+            for level in (1..=degree).rev() {
+                for j in 0..level {
+                    scratch[j] = scratch[j] + t * (scratch[j + 1] - scratch[j]);
+                }
+            }
+            path.push(scratch[0]);
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -482,41 +543,141 @@ fn fill_closed_path(
     if color == color::TRANSPARENT {
         return;
     }
+    fill_closed_path_with(path, None, |_| color, options, out);
+}
 
+/// Tesselate the given convex area into a polygon, with a color for each vertex.
+///
+/// The triangles fan out from `center` if given, else from the first point.
+/// Since the GPU interpolates linearly between the vertices,
+/// nonlinear gradients need a `center` and many points.
+fn fill_closed_path_with(
+    path: &[PathPoint],
+    center: Option<Pos2>,
+    color_at: impl Fn(Pos2) -> Srgba,
+    options: TesselationOptions,
+    out: &mut Triangles,
+) {
     let n = path.len() as u32;
     if options.anti_alias {
         out.reserve_triangles(3 * n as usize);
-        out.reserve_vertices(2 * n as usize);
+        out.reserve_vertices(2 * n as usize + 1);
         let color_outer = color::TRANSPARENT;
         let idx_inner = out.vertices.len() as u32;
         let idx_outer = idx_inner + 1;
-        for i in 2..n {
-            out.add_triangle(idx_inner + 2 * (i - 1), idx_inner, idx_inner + 2 * i);
+        if center.is_none() {
+            for i in 2..n {
+                out.add_triangle(idx_inner + 2 * (i - 1), idx_inner, idx_inner + 2 * i);
+            }
         }
         let mut i0 = n - 1;
         for i1 in 0..n {
             let p1 = &path[i1 as usize];
             let dm = p1.normal * options.aa_size * 0.5;
-            out.colored_vertex(p1.pos - dm, color);
+            out.colored_vertex(p1.pos - dm, color_at(p1.pos));
             out.colored_vertex(p1.pos + dm, color_outer);
             out.add_triangle(idx_inner + i1 * 2, idx_inner + i0 * 2, idx_outer + 2 * i0);
             out.add_triangle(idx_outer + i0 * 2, idx_outer + i1 * 2, idx_inner + 2 * i1);
             i0 = i1;
         }
+        if let Some(center) = center {
+            let idx_center = out.vertices.len() as u32;
+            out.colored_vertex(center, color_at(center));
+            let mut i0 = n - 1;
+            for i1 in 0..n {
+                out.add_triangle(idx_center, idx_inner + 2 * i0, idx_inner + 2 * i1);
+                i0 = i1;
+            }
+        }
     } else {
         out.reserve_triangles(n as usize);
         let idx = out.vertices.len() as u32;
         out.vertices.extend(path.iter().map(|p| Vertex {
             pos: p.pos,
             uv: WHITE_UV,
-            color,
+            color: color_at(p.pos),
         }));
-        for i in 2..n {
-            out.add_triangle(idx, idx + i - 1, idx + i);
+        if let Some(center) = center {
+            out.colored_vertex(center, color_at(center));
+            let mut i0 = n - 1;
+            for i1 in 0..n {
+                out.add_triangle(idx + n, idx + i0, idx + i1);
+                i0 = i1;
+            }
+        } else {
+            for i in 2..n {
+                out.add_triangle(idx, idx + i - 1, idx + i);
+            }
         }
     }
 }
 
+/// Tesselate the given area, which may be concave.
+///
+/// The first path is the outline, and any others are holes.
+/// The normals of all of them should point away from the filled area.
+fn fill_polygon(
+    rings: &[&[PathPoint]],
+    color: Srgba,
+    options: TesselationOptions,
+    out: &mut Triangles,
+) {
+    if color == color::TRANSPARENT {
+        return;
+    }
+
+    let mut points = vec![];
+    let mut hole_starts = vec![];
+    for ring in rings {
+        if !points.is_empty() {
+            hole_starts.push(points.len());
+        }
+        points.extend(ring.iter().map(|p| p.pos));
+    }
+    let indices = triangulation::triangulate(&points, &hole_starts);
+
+    let n = points.len() as u32;
+    let idx = out.vertices.len() as u32;
+    if options.anti_alias {
+        // Like `fill_closed_path`, but with the inside triangulated,
+        // and a feathered edge around each ring:
+        out.reserve_triangles(indices.len() / 3 + 2 * n as usize);
+        out.reserve_vertices(2 * n as usize);
+        let color_outer = color::TRANSPARENT;
+        for triangle in indices.chunks(3) {
+            out.add_triangle(
+                idx + 2 * triangle[0],
+                idx + 2 * triangle[1],
+                idx + 2 * triangle[2],
+            );
+        }
+        let mut start = idx;
+        for ring in rings {
+            let n = ring.len() as u32;
+            let mut i0 = n - 1;
+            for i1 in 0..n {
+                let p1 = &ring[i1 as usize];
+                let dm = p1.normal * options.aa_size * 0.5;
+                out.colored_vertex(p1.pos - dm, color);
+                out.colored_vertex(p1.pos + dm, color_outer);
+                let (inner0, inner1) = (start + 2 * i0, start + 2 * i1);
+                out.add_triangle(inner1, inner0, inner0 + 1);
+                out.add_triangle(inner0 + 1, inner1 + 1, inner1);
+                i0 = i1;
+            }
+            start += 2 * n;
+        }
+    } else {
+        out.reserve_triangles(indices.len() / 3);
+        out.vertices.extend(points.iter().map(|&pos| Vertex {
+            pos,
+            uv: WHITE_UV,
+            color,
+        }));
+        out.indices.extend(indices.iter().map(|i| idx + i));
+    }
+}
+
 /// Tesselate the given path as a stroke with thickness.
 fn stroke_path(
     path: &[PathPoint],
@@ -529,6 +690,28 @@ fn stroke_path(
         return;
     }
 
+    match stroke.pattern {
+        StrokePattern::Solid => {}
+        StrokePattern::Dashed { dash, gap } => {
+            if dash <= 0.0 {
+                return;
+            }
+            if gap > 0.0 && !too_many_pieces(path, path_type, dash + gap) {
+                let solid = stroke.pattern(StrokePattern::Solid);
+                for dash in dashes(path, path_type, dash, gap) {
+                    stroke_path(&dash, Open, solid, options, out);
+                }
+                return;
+            }
+        }
+        StrokePattern::Dotted { spacing } => {
+            if spacing > 0.0 && !too_many_pieces(path, path_type, spacing) {
+                stroke_dots(path, path_type, spacing, stroke, options, out);
+                return;
+            }
+        }
+    }
+
     let n = path.len() as u32;
     let idx = out.vertices.len() as u32;
 
@@ -656,6 +839,125 @@ fn stroke_path(
     }
 }
 
+/// The segments of a path, including the closing one of a closed path.
+fn path_segments(
+    path: &[PathPoint],
+    path_type: PathType,
+) -> impl Iterator<Item = (&PathPoint, &PathPoint)> {
+    let n = path.len();
+    let num_segments = match path_type {
+        Open => n.saturating_sub(1),
+        Closed if n >= 2 => n,
+        Closed => 0,
+    };
+    (0..num_segments).map(move |i| (&path[i], &path[(i + 1) % n]))
+}
+
+fn path_length(path: &[PathPoint], path_type: PathType) -> f32 {
+    path_segments(path, path_type)
+        .map(|(a, b)| a.pos.distance(b.pos))
+        .sum()
+}
+
+/// A dashed stroke of a huge rectangle could take forever to tessellate,
+/// so we fall back to a solid stroke for those.
+fn too_many_pieces(path: &[PathPoint], path_type: PathType, period: f32) -> bool {
+    const MAX_PIECES: f32 = 10_000.0;
+    path_length(path, path_type) > MAX_PIECES * period
+}
+
+/// Split a path into dashes, each of which can be tessellated as a solid open path.
+///
+/// The dashes continue around corners, keeping the miter of the corner.
+/// For closed paths the last dash is joined with the first one, so there is no seam.
+fn dashes(path: &[PathPoint], path_type: PathType, dash: f32, gap: f32) -> Vec<Vec<PathPoint>> {
+    let mut dashes = vec![];
+    let mut current: Vec<PathPoint> = vec![];
+    let mut drawing = true;
+    let mut left = dash; // of the current dash or gap
+
+    for (a, b) in path_segments(path, path_type) {
+        let dir = b.pos - a.pos;
+        let length = dir.length();
+        if length <= 0.0 {
+            continue;
+        }
+        let normal = (dir / length).rot90();
+        if current.is_empty() && drawing {
+            current.push(PathPoint { pos: a.pos, normal });
+        }
+
+        let mut t = 0.0; // how far along this segment we are
+        while length - t > left {
+            t += left;
+            current.push(PathPoint {
+                pos: a.pos + dir * (t / length),
+                normal,
+            });
+            if drawing {
+                dashes.push(std::mem::take(&mut current));
+            }
+            drawing = !drawing;
+            left = if drawing { dash } else { gap };
+        }
+        left -= length - t;
+
+        if drawing {
+            // Turn the corner (or end the path) as a solid stroke would:
+            current.push(*b);
+        }
+    }
+
+    if drawing && current.len() >= 2 {
+        if path_type == Closed && !dashes.is_empty() {
+            // Continue into the first dash, skipping its duplicated start:
+            let first_dash = dashes.remove(0);
+            current.extend_from_slice(&first_dash[1..]);
+        }
+        dashes.push(current);
+    }
+    dashes
+}
+
+/// Tesselate round dots along the path, as wide as the stroke.
+///
+/// For closed paths the spacing is adjusted slightly so that the dots are evenly spread all the way around.
+fn stroke_dots(
+    path: &[PathPoint],
+    path_type: PathType,
+    spacing: f32,
+    stroke: Stroke,
+    options: TesselationOptions,
+    out: &mut Triangles,
+) {
+    let (spacing, max_dots) = if path_type == Closed {
+        // The last dot would land on top of the first one:
+        let length = path_length(path, path_type);
+        let num_dots = (length / spacing).round().max(1.0);
+        (length / num_dots, num_dots as usize)
+    } else {
+        (spacing, usize::MAX)
+    };
+    let radius = stroke.width / 2.0;
+    let mut dot = Path::default();
+
+    let mut num_dots = 0;
+    let mut left = 0.0; // until the next dot
+    for (a, b) in path_segments(path, path_type) {
+        let dir = b.pos - a.pos;
+        let length = dir.length();
+        let mut t = left;
+        while t <= length && num_dots < max_dots {
+            dot.clear();
+            dot.add_circle(a.pos + dir * (t / length.max(f32::EPSILON)), radius);
+            fill_closed_path(&dot.0, stroke.color, options, out);
+            num_dots += 1;
+            t += spacing;
+        }
+        left = t - length;
+    }
+}
+
 fn mul_color(color: Srgba, factor: f32) -> Srgba {
     debug_assert!(0.0 <= factor && factor <= 1.0);
     // sRGBA correct fading requires conversion to linear space and back again because of premultiplied alpha
@@ -664,6 +966,10 @@ fn mul_color(color: Srgba, factor: f32) -> Srgba {
 
 // ----------------------------------------------------------------------------
 
+/// Radial gradients are only linear along lines from the center,
+/// so we split the edges of the shape into pieces no longer than this, in points.
+const RADIAL_GRADIENT_SEGMENT_LENGTH: f32 = 8.0;
+
 /// Tesselate a single `PaintCmd` into a `Triangles`.
 ///
 /// * `command`: the command to tesselate
@@ -718,27 +1024,48 @@ fn tessellate_paint_command(
             stroke_path(&path.0, Open, stroke, options, out);
         }
         PaintCmd::Path {
-            points,
+            mut points,
             closed,
             fill,
             stroke,
         } => {
-            if points.len() >= 2 {
-                if closed {
-                    path.add_line_loop(&points);
-                } else {
-                    path.add_open_points(&points);
+            tessellate_path(&mut points, closed, fill, stroke, options, out, path);
+        }
+        PaintCmd::PolygonWithHoles {
+            mut points,
+            mut holes,
+            fill,
+            stroke,
+        } => {
+            if points.len() < 3 {
+                return;
+            }
+            // Make the normals point away from the filled area:
+            if triangulation::polygon_area(&points) < 0.0 {
+                points.reverse();
+            }
+            holes.retain(|hole| hole.len() >= 3);
+            for hole in &mut holes {
+                if triangulation::polygon_area(hole) > 0.0 {
+                    hole.reverse();
                 }
+            }
 
-                if fill != TRANSPARENT {
-                    debug_assert!(
-                        closed,
-                        "You asked to fill a path that is not closed. That makes no sense."
-                    );
-                    fill_closed_path(&path.0, fill, options, out);
-                }
-                let typ = if closed { Closed } else { Open };
-                stroke_path(&path.0, typ, stroke, options, out);
+            path.add_line_loop(&points);
+            let hole_paths: Vec<Path> = holes
+                .iter()
+                .map(|hole| {
+                    let mut hole_path = Path::default();
+                    hole_path.add_line_loop(hole);
+                    hole_path
+                })
+                .collect();
+            let rings: Vec<&[PathPoint]> = std::iter::once(&path.0[..])
+                .chain(hole_paths.iter().map(|hole_path| &hole_path.0[..]))
+                .collect();
+            fill_polygon(&rings, fill, options, out);
+            for ring in rings {
+                stroke_path(ring, Closed, stroke, options, out);
             }
         }
         PaintCmd::Rect {
@@ -763,17 +1090,77 @@ fn tessellate_paint_command(
             rect.max = rect.max.at_most(pos2(1e7, 1e7));
 
             path::rounded_rectangle(scratchpad_points, rect, corner_radius);
-            path.add_line_loop(scratchpad_points);
-            fill_closed_path(&path.0, fill, options, out);
+            match fill {
+                Fill::Color(color) => {
+                    path.add_line_loop(scratchpad_points);
+                    fill_closed_path(&path.0, color, options, out);
+                }
+                Fill::LinearGradient { .. } => {
+                    // Linear all the way, so the GPU gets it right from the corners alone:
+                    path.add_line_loop(scratchpad_points);
+                    if !fill.is_transparent() {
+                        let color_at = |pos| fill.color_at(rect, pos);
+                        fill_closed_path_with(&path.0, None, color_at, options, out);
+                    }
+                }
+                Fill::RadialGradient { .. } => {
+                    // Linear along lines from the center, but not around it:
+                    path::subdivide_loop(scratchpad_points, RADIAL_GRADIENT_SEGMENT_LENGTH);
+                    path.add_line_loop(scratchpad_points);
+                    if !fill.is_transparent() {
+                        let color_at = |pos| fill.color_at(rect, pos);
+                        let center = Some(rect.center());
+                        fill_closed_path_with(&path.0, center, color_at, options, out);
+                    }
+                }
+            }
             stroke_path(&path.0, Closed, stroke, options, out);
         }
+        PaintCmd::QuadraticBezier {
+            points,
+            closed,
+            fill,
+            stroke,
+        } => {
+            tessellate_bezier(
+                clip_rect,
+                &points,
+                closed,
+                fill,
+                stroke,
+                options,
+                out,
+                scratchpad_points,
+                path,
+            );
+        }
+        PaintCmd::CubicBezier {
+            points,
+            closed,
+            fill,
+            stroke,
+        } => {
+            tessellate_bezier(
+                clip_rect,
+                &points,
+                closed,
+                fill,
+                stroke,
+                options,
+                out,
+                scratchpad_points,
+                path,
+            );
+        }
         PaintCmd::Text {
             pos,
             galley,
             text_style,
             color,
+            scale,
         } => {
-            if color == TRANSPARENT {
+            let has_sections = !galley.sections.is_empty();
+            if color == TRANSPARENT && !has_sections {
                 return;
             }
             galley.sanity_check();
@@ -782,24 +1169,83 @@ fn tessellate_paint_command(
             out.reserve_triangles(num_chars * 2);
             out.reserve_vertices(num_chars * 4);
 
-            let tex_w = fonts.texture().width as f32;
-            let tex_h = fonts.texture().height as f32;
+            // Zoomed text uses glyphs rasterized at the zoomed size.
+            // Its galley was laid out with the same fonts.
+            let screen_fonts = fonts;
+            let scaled_fonts = if scale == 1.0 {
+                None
+            } else {
+                fonts.scaled(scale)
+            };
+            let fonts = scaled_fonts.as_deref().unwrap_or(fonts);
 
-            let text_offset = vec2(0.0, 1.0); // Eye-balled for buttons. TODO: why is this needed?
+            // Scaling the fonts the first time can grow the shared texture:
+            let tex_w = screen_fonts.texture().width as f32;
+            let tex_h = screen_fonts.texture().height as f32;
+
+            let text_offset = TEXT_OFFSET;
 
             let clip_rect = clip_rect.expand(2.0); // Some fudge to handle letter slightly larger than expected.
 
-            let font = &fonts[text_style];
+            let decorated_sections: Vec<_> = galley
+                .sections
+                .iter()
+                .filter(|section| section.underline || section.background != TRANSPARENT)
+                .collect();
+            if !decorated_sections.is_empty() {
+                // Backgrounds and underlines go behind the glyphs:
+                let mut line_start_char = 0;
+                for line in &galley.lines {
+                    let line_end_char = line_start_char + line.char_count();
+                    for section in &decorated_sections {
+                        let start = section.char_range.start.max(line_start_char);
+                        let end = section.char_range.end.min(line_end_char);
+                        if start >= end {
+                            continue;
+                        }
+                        let min_x = pos.x + scale * line.x_offsets[start - line_start_char];
+                        let max_x = pos.x + scale * line.x_offsets[end - line_start_char];
+                        if section.background != TRANSPARENT {
+                            let rect = Rect::from_min_max(
+                                pos2(min_x, pos.y + scale * line.y_min),
+                                pos2(max_x, pos.y + scale * line.y_max),
+                            );
+                            out.add_colored_rect(rect, section.background);
+                        }
+                        if section.underline {
+                            let y = screen_fonts.round_to_pixel(pos.y + scale * line.y_max);
+                            let rect = Rect::from_min_max(pos2(min_x, y - 1.0), pos2(max_x, y));
+                            out.add_colored_rect(rect, section.color);
+                        }
+                    }
+                    line_start_char = line_end_char;
+                }
+            }
+
+            let mut font = &fonts[text_style];
+            let mut color = color;
+            let mut section_idx = 0;
+            let mut char_idx = 0;
             let mut chars = galley.text.chars();
             for line in &galley.lines {
-                let line_min_y = pos.y + line.y_min + text_offset.x;
-                let line_max_y = line_min_y + font.height();
+                let line_min_y = pos.y + scale * (line.y_min + text_offset.x);
+                let line_max_y = pos.y + scale * (line.y_max + text_offset.x);
                 let is_line_visible =
                     line_max_y >= clip_rect.min.y && line_min_y <= clip_rect.max.y;
 
                 for x_offset in line.x_offsets.iter().take(line.x_offsets.len() - 1) {
                     let c = chars.next().unwrap();
 
+                    if has_sections {
+                        while galley.sections[section_idx].char_range.end <= char_idx {
+                            section_idx += 1;
+                        }
+                        let section = &galley.sections[section_idx];
+                        font = &fonts[section.text_style];
+                        color = section.color;
+                    }
+                    char_idx += 1;
+
                     if options.coarse_tessellation_culling && !is_line_visible {
                         // culling individual lines of text is important, since a single `PaintCmd::Text`
                         // can span hundreds of lines.
@@ -807,12 +1253,14 @@ fn tessellate_paint_command(
                     }
 
                     if let Some(glyph) = font.uv_rect(c) {
+                        // Align the bottom of all fonts on the same line:
+                        let glyph_top = line.y_max - font.height();
                         let mut left_top =
-                            pos + glyph.offset + vec2(*x_offset, line.y_min) + text_offset;
-                        left_top.x = font.round_to_pixel(left_top.x); // Pixel-perfection.
-                        left_top.y = font.round_to_pixel(left_top.y); // Pixel-perfection.
+                            pos + scale * (glyph.offset + vec2(*x_offset, glyph_top) + text_offset);
+                        left_top.x = screen_fonts.round_to_pixel(left_top.x); // Pixel-perfection.
+                        left_top.y = screen_fonts.round_to_pixel(left_top.y); // Pixel-perfection.
 
-                        let pos = Rect::from_min_max(left_top, left_top + glyph.size);
+                        let pos = Rect::from_min_max(left_top, left_top + scale * glyph.size);
                         let uv = Rect::from_min_max(
                             pos2(glyph.min.0 as f32 / tex_w, glyph.min.1 as f32 / tex_h),
                             pos2(glyph.max.0 as f32 / tex_w, glyph.max.1 as f32 / tex_h),
@@ -826,6 +1274,108 @@ fn tessellate_paint_command(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+fn tessellate_bezier(
+    clip_rect: Rect,
+    control_points: &[Pos2],
+    closed: bool,
+    fill: Srgba,
+    stroke: Stroke,
+    options: TesselationOptions,
+    out: &mut Triangles,
+    scratchpad_points: &mut Vec<Pos2>,
+    path: &mut Path,
+) {
+    if options.coarse_tessellation_culling {
+        // The curve is within the convex hull of its control points:
+        let mut bounding_rect = Rect::nothing();
+        for &p in control_points {
+            bounding_rect.extend_with(p);
+        }
+        if !bounding_rect.expand(stroke.width).intersects(clip_rect) {
+            return;
+        }
+    }
+
+    // Flatten to within a quarter of a pixel:
+    path::bezier(scratchpad_points, control_points, 0.25 * options.aa_size);
+    tessellate_path(scratchpad_points, closed, fill, stroke, options, out, path);
+}
+
+/// Fill and stroke a polyline or polygon, which may be concave.
+fn tessellate_path(
+    points: &mut [Pos2],
+    closed: bool,
+    fill: Srgba,
+    stroke: Stroke,
+    options: TesselationOptions,
+    out: &mut Triangles,
+    path: &mut Path,
+) {
+    if points.len() < 2 {
+        return;
+    }
+
+    if fill != TRANSPARENT {
+        debug_assert!(
+            closed,
+            "You asked to fill a path that is not closed. That makes no sense."
+        );
+        // Make the normals point outwards, so the anti-aliasing feathers the outside:
+        if triangulation::polygon_area(points) < 0.0 {
+            points.reverse();
+        }
+    }
+
+    if closed {
+        path.add_line_loop(points);
+    } else {
+        path.add_open_points(points);
+    }
+
+    if fill != TRANSPARENT {
+        if triangulation::is_convex(points) {
+            fill_closed_path(&path.0, fill, options, out);
+        } else {
+            fill_polygon(&[&path.0], fill, options, out);
+        }
+    }
+    let typ = if closed { Closed } else { Open };
+    stroke_path(&path.0, typ, stroke, options, out);
+}
+
+/// A filled rounded rectangle that fades out over `feather` points beyond its edges,
+/// e.g. for shadows.
+pub(crate) fn feathered_rect(
+    rect: Rect,
+    corner_radius: CornerRadius,
+    color: Srgba,
+    feather: f32,
+) -> Triangles {
+    // The anti-aliasing fades out over `aa_size` centered on the path,
+    // so we put the path half way out:
+    let half = feather / 2.0;
+    let corner_radius = CornerRadius {
+        nw: corner_radius.nw + half,
+        ne: corner_radius.ne + half,
+        sw: corner_radius.sw + half,
+        se: corner_radius.se + half,
+    };
+    let options = TesselationOptions {
+        aa_size: feather,
+        anti_alias: true,
+        ..Default::default()
+    };
+
+    let mut points = vec![];
+    path::rounded_rectangle(&mut points, rect.expand(half), corner_radius);
+    let mut path = Path::default();
+    path.add_line_loop(&points);
+    let mut triangles = Triangles::default();
+    fill_closed_path(&path.0, color, options, &mut triangles);
+    triangles
+}
+
 /// Turns `PaintCmd`:s into sets of triangles.
 ///
 /// The given commands will be painted back-to-front (painters algorithm).
@@ -842,39 +1392,21 @@ pub fn tessellate_paint_commands(
     options: TesselationOptions,
     fonts: &Fonts,
 ) -> Vec<(Rect, Triangles)> {
+    #[cfg(feature = "rayon")]
+    let mut jobs = tessellate_jobs_in_parallel(commands, options, fonts);
+    #[cfg(not(feature = "rayon"))]
+    let mut jobs = tessellate_jobs(commands, options, fonts);
+
     let mut scratchpad_points = Vec::new();
     let mut scratchpad_path = Path::default();
 
-    let mut jobs = PaintJobs::default();
-    for (clip_rect, cmd) in commands {
-        let start_new_job = match jobs.last() {
-            None => true,
-            Some(job) => job.0 != clip_rect || job.1.texture_id != cmd.texture_id(),
-        };
-
-        if start_new_job {
-            jobs.push((clip_rect, Triangles::default()));
-        }
-
-        let out = &mut jobs.last_mut().unwrap().1;
-        tessellate_paint_command(
-            clip_rect,
-            cmd,
-            options,
-            fonts,
-            out,
-            &mut scratchpad_points,
-            &mut scratchpad_path,
-        );
-    }
-
     if options.debug_paint_clip_rects {
         for (clip_rect, triangles) in &mut jobs {
             tessellate_paint_command(
                 Rect::everything(),
                 PaintCmd::Rect {
                     rect: *clip_rect,
-                    corner_radius: 0.0,
+                    corner_radius: Default::default(),
                     fill: Default::default(),
                     stroke: Stroke::new(2.0, srgba(150, 255, 150, 255)),
                 },
@@ -902,3 +1434,258 @@ pub fn tessellate_paint_commands(
 
     jobs
 }
+
+/// Tesselate the commands in order, starting a new job whenever the clip rectangle or texture changes.
+fn tessellate_jobs(
+    commands: Vec<(Rect, PaintCmd)>,
+    options: TesselationOptions,
+    fonts: &Fonts,
+) -> PaintJobs {
+    let mut scratchpad_points = Vec::new();
+    let mut scratchpad_path = Path::default();
+
+    let mut jobs = PaintJobs::default();
+    for (clip_rect, cmd) in commands {
+        let start_new_job = match jobs.last() {
+            None => true,
+            Some(job) => job.0 != clip_rect || job.1.texture_id != cmd.texture_id(),
+        };
+
+        if start_new_job {
+            jobs.push((clip_rect, Triangles::default()));
+        }
+
+        let out = &mut jobs.last_mut().unwrap().1;
+        tessellate_paint_command(
+            clip_rect,
+            cmd,
+            options,
+            fonts,
+            out,
+            &mut scratchpad_points,
+            &mut scratchpad_path,
+        );
+    }
+
+    jobs
+}
+
+/// Like `tessellate_jobs`, but each run of commands with the same clip rectangle is tesselated on its own thread.
+///
+/// A new clip rectangle always starts a new job, so the result is exactly the same.
+#[cfg(feature = "rayon")]
+fn tessellate_jobs_in_parallel(
+    commands: Vec<(Rect, PaintCmd)>,
+    options: TesselationOptions,
+    fonts: &Fonts,
+) -> PaintJobs {
+    use rayon::prelude::*;
+
+    let mut runs: Vec<Vec<(Rect, PaintCmd)>> = vec![];
+    for (clip_rect, cmd) in commands {
+        match runs.last_mut() {
+            Some(run) if run[0].0 == clip_rect => run.push((clip_rect, cmd)),
+            _ => runs.push(vec![(clip_rect, cmd)]),
+        }
+    }
+
+    let jobs: Vec<PaintJobs> = runs
+        .into_par_iter()
+        .map(|run| tessellate_jobs(run, options, fonts))
+        .collect();
+    jobs.into_iter().flatten().collect()
+}
+
+#[test]
+fn test_bezier_flattening() {
+    let control_points = [
+        pos2(0.0, 0.0),
+        pos2(0.0, 100.0),
+        pos2(100.0, 100.0),
+        pos2(100.0, 0.0),
+    ];
+    let tolerance = 0.25;
+    let mut points = vec![];
+    path::bezier(&mut points, &control_points, tolerance);
+    assert_eq!(points.first(), Some(&control_points[0]));
+    assert_eq!(points.last(), Some(&control_points[3]));
+
+    // Every point on the curve must be close to the lines:
+    for i in 0..=1000 {
+        let t = i as f32 / 1000.0;
+        let s = 1.0 - t;
+        let [p0, p1, p2, p3] = control_points;
+        let on_curve = pos2(
+            s * s * s * p0.x + 3.0 * s * s * t * p1.x + 3.0 * s * t * t * p2.x + t * t * t * p3.x,
+            s * s * s * p0.y + 3.0 * s * s * t * p1.y + 3.0 * s * t * t * p2.y + t * t * t * p3.y,
+        );
+        let distance = points
+            .windows(2)
+            .map(|line| {
+                let (a, b) = (line[0], line[1]);
+                let ab = b - a;
+                let t = clamp(
+                    ((on_curve.x - a.x) * ab.x + (on_curve.y - a.y) * ab.y) / ab.length_sq(),
+                    0.0..=1.0,
+                );
+                on_curve.distance(a + t * ab)
+            })
+            .fold(f32::INFINITY, f32::min);
+        assert!(distance <= tolerance + 1e-3, "{} > {}", distance, tolerance);
+    }
+
+    // A straight line needs no more than one segment:
+    path::bezier(
+        &mut points,
+        &[pos2(0.0, 0.0), pos2(50.0, 0.0), pos2(100.0, 0.0)],
+        tolerance,
+    );
+    assert_eq!(points.len(), 2);
+}
+
+#[test]
+fn test_closed_dashes_around_corners() {
+    let mut path = Path::default();
+    path.add_line_loop(&[
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(0.0, 10.0),
+    ]);
+    let length =
+        |dash: &[PathPoint]| -> f32 { dash.windows(2).map(|w| w[0].pos.distance(w[1].pos)).sum() };
+
+    // The perimeter is 40 points: six dashes of 4 points, with a last gap of only 1 point:
+    let closed_dashes = dashes(&path.0, Closed, 4.0, 3.0);
+    assert_eq!(closed_dashes.len(), 6);
+    for dash in &closed_dashes {
+        assert!((length(dash) - 4.0).abs() < 1e-3);
+    }
+
+    // The second dash turns the first corner, keeping its miter:
+    assert_eq!(closed_dashes[1].len(), 3);
+    assert_eq!(closed_dashes[1][1].pos, pos2(10.0, 0.0));
+    assert_eq!(closed_dashes[1][1].normal, path.0[1].normal);
+    assert_eq!(closed_dashes[1][2].pos, pos2(10.0, 1.0));
+
+    // Here the last dash runs into the first one, hiding the seam of the closed path:
+    let closed_dashes = dashes(&path.0, Closed, 4.0, 2.0);
+    assert_eq!(closed_dashes.len(), 6);
+    assert!((length(&closed_dashes[5]) - 8.0).abs() < 1e-3);
+    assert!(closed_dashes[5].iter().any(|p| p.pos == pos2(0.0, 0.0)));
+
+    assert_eq!(dashes(&path.0, Open, 4.0, 2.0).len(), 5);
+}
+
+#[test]
+fn test_gradient_rect() {
+    let fonts = Fonts::from_definitions(super::FontDefinitions::with_pixels_per_point(1.0));
+    let options = TesselationOptions {
+        anti_alias: false,
+        ..Default::default()
+    };
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 50.0));
+    let tessellate = |corner_radius, fill| {
+        let cmd = PaintCmd::Rect {
+            rect,
+            corner_radius,
+            fill,
+            stroke: Default::default(),
+        };
+        let jobs = tessellate_paint_commands(vec![(Rect::everything(), cmd)], options, &fonts);
+        jobs.into_iter().next().unwrap().1.vertices
+    };
+    let color_of =
+        |vertices: &[Vertex], pos| vertices.iter().find(|v| v.pos == pos).map(|v| v.color);
+
+    let vertices = tessellate(
+        CornerRadius::top(10.0),
+        Fill::vertical_gradient(color::BLACK, color::WHITE),
+    );
+    assert_eq!(color_of(&vertices, pos2(0.0, 0.0)), None, "rounded");
+    assert_eq!(color_of(&vertices, pos2(0.0, 50.0)), Some(color::WHITE));
+    assert_eq!(color_of(&vertices, pos2(100.0, 50.0)), Some(color::WHITE));
+    let top: Vec<&Vertex> = vertices.iter().filter(|v| v.pos.y.abs() < 1e-3).collect();
+    assert!(!top.is_empty() && top.iter().all(|v| v.color == color::BLACK));
+
+    let vertices = tessellate(
+        CornerRadius::same(0.0),
+        Fill::radial_gradient(color::WHITE, color::BLACK),
+    );
+    assert_eq!(color_of(&vertices, rect.center()), Some(color::WHITE));
+    assert_eq!(color_of(&vertices, rect.max), Some(color::BLACK));
+    // The edges are split up, so that the gradient gets dark towards the corners:
+    let middle_of_bottom = vertices
+        .iter()
+        .filter(|v| v.pos.y == 50.0 && (v.pos.x - 50.0).abs() < 8.0)
+        .map(|v| v.color.r())
+        .next()
+        .unwrap();
+    assert!(0 < middle_of_bottom && middle_of_bottom < 255);
+}
+
+#[test]
+fn test_zoomed_text_uvs() {
+    use super::TextStyle;
+
+    let fonts = Fonts::from_definitions(super::FontDefinitions::with_pixels_per_point(1.0));
+    let texture_height = fonts.texture().height;
+    let galley = fonts[TextStyle::Body].layout_single_line("Zoomed".to_owned());
+    let cmd = PaintCmd::Text {
+        pos: pos2(0.0, 0.0),
+        galley: std::sync::Arc::new(galley),
+        text_style: TextStyle::Body,
+        color: color::WHITE,
+        scale: 4.0,
+    };
+    let jobs =
+        tessellate_paint_commands(vec![(Rect::everything(), cmd)], Default::default(), &fonts);
+    // The glyphs of the new zoom step did not fit in the texture:
+    assert!(fonts.texture().height > texture_height);
+    let vertices = &jobs[0].1.vertices;
+    assert!(!vertices.is_empty());
+    assert!(vertices.iter().all(|v| v.uv.y <= 1.0));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_tessellation() {
+    let mut ctx = crate::Context::new();
+    ctx.memory().all_collpasing_are_open = true;
+    let mut demo_windows = crate::demos::DemoWindows::default();
+    ctx.begin_frame(crate::RawInput {
+        screen_size: vec2(1280.0, 1024.0),
+        ..Default::default()
+    });
+    demo_windows.ui(&ctx, &Default::default(), &mut None);
+    let (_, commands) = ctx.end_frame();
+    let options = TesselationOptions {
+        anti_alias: true,
+        ..Default::default()
+    };
+
+    let bits = |jobs: PaintJobs| -> Vec<_> {
+        jobs.into_iter()
+            .map(|(clip_rect, triangles)| {
+                let vertices: Vec<_> = triangles
+                    .vertices
+                    .iter()
+                    .map(|v| [v.pos.x, v.pos.y, v.uv.x, v.uv.y].map(f32::to_bits))
+                    .zip(triangles.vertices.iter().map(|v| v.color))
+                    .collect();
+                let clip_rect = [
+                    clip_rect.min.x,
+                    clip_rect.min.y,
+                    clip_rect.max.x,
+                    clip_rect.max.y,
+                ]
+                .map(f32::to_bits);
+                (clip_rect, triangles.indices, vertices, triangles.texture_id)
+            })
+            .collect()
+    };
+    let single_threaded = tessellate_jobs(commands.clone(), options, ctx.fonts());
+    let parallel = tessellate_jobs_in_parallel(commands, options, ctx.fonts());
+    assert!(single_threaded.len() > 1);
+    assert!(bits(single_threaded) == bits(parallel));
+}
@@ -0,0 +1,328 @@
+//! Triangulation of concave polygons, with or without holes, using ear clipping.
+
+use crate::math::*;
+
+/// Triangulate a polygon, given as the outline followed by the holes.
+///
+/// `hole_starts` are the indices into `points` where each hole starts.
+/// The outline and the holes can be in either winding order.
+///
+/// Returns the corners of the triangles, as indices into `points`.
+///
+/// Self-intersecting polygons will not crash the triangulation,
+/// but which parts of them end up filled is not well defined.
+pub fn triangulate(points: &[Pos2], hole_starts: &[usize]) -> Vec<u32> {
+    let mut ring_starts = vec![0];
+    ring_starts.extend_from_slice(hole_starts);
+    ring_starts.push(points.len());
+    let mut rings = ring_starts.windows(2).map(|w| {
+        let mut ring: Vec<u32> = (w[0] as u32..w[1] as u32).collect();
+        ring.dedup_by(|a, b| points[*a as usize] == points[*b as usize]);
+        ring
+    });
+
+    let mut outline = rings.next().unwrap_or_default();
+    if signed_area(points, &outline) < 0.0 {
+        outline.reverse();
+    }
+
+    // The holes go the other way around, and are bridged to the outline, rightmost first:
+    let mut holes: Vec<Vec<u32>> = rings.filter(|ring| ring.len() >= 3).collect();
+    for hole in &mut holes {
+        if signed_area(points, hole) > 0.0 {
+            hole.reverse();
+        }
+    }
+    // `total_cmp`, so that a NaN coordinate gives us garbage rather than a panic:
+    holes.sort_by(|a, b| max_x(points, b).total_cmp(&max_x(points, a)));
+    for hole in holes {
+        bridge_hole(points, &mut outline, hole);
+    }
+
+    let mut triangles = Vec::with_capacity(3 * outline.len().saturating_sub(2));
+    clip_ears(points, outline, &mut triangles);
+    triangles
+}
+
+/// Is this closed polygon convex, so that it can be filled as a simple triangle fan?
+pub fn is_convex(points: &[Pos2]) -> bool {
+    let n = points.len();
+    let mut turn_sign = 0.0;
+    // Going once around a convex polygon we go right, then left (or the other way around):
+    let mut first_dx_sign = 0.0;
+    let mut dx_sign = 0.0;
+    let mut num_dx_sign_changes = 0;
+    for i in 0..n {
+        let a = points[i];
+        let b = points[(i + 1) % n];
+        let c = points[(i + 2) % n];
+        let turn = cross(b - a, c - b);
+        if turn != 0.0 {
+            if turn_sign * turn < 0.0 {
+                return false;
+            }
+            turn_sign = turn.signum();
+        }
+        let dx = b.x - a.x;
+        if dx != 0.0 {
+            if first_dx_sign == 0.0 {
+                first_dx_sign = dx.signum();
+            } else if dx.signum() != dx_sign {
+                num_dx_sign_changes += 1;
+            }
+            dx_sign = dx.signum();
+        }
+    }
+    if dx_sign != first_dx_sign {
+        num_dx_sign_changes += 1;
+    }
+    num_dx_sign_changes <= 2
+}
+
+/// Positive if the polygon goes around in the same direction as `Path::add_circle`,
+/// which is when `Vec2::rot90` of its edges point outwards.
+pub fn polygon_area(points: &[Pos2]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| cross(points[i].to_vec2(), points[(i + 1) % n].to_vec2()))
+        .sum::<f32>()
+        / 2.0
+}
+
+fn cross(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn signed_area(points: &[Pos2], ring: &[u32]) -> f32 {
+    let n = ring.len();
+    (0..n)
+        .map(|i| {
+            let a = points[ring[i] as usize];
+            let b = points[ring[(i + 1) % n] as usize];
+            cross(a.to_vec2(), b.to_vec2())
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+fn max_x(points: &[Pos2], ring: &[u32]) -> f32 {
+    ring.iter()
+        .map(|&i| points[i as usize].x)
+        .fold(f32::NEG_INFINITY, f32::max)
+}
+
+fn point_in_triangle(p: Pos2, [a, b, c]: [Pos2; 3]) -> bool {
+    cross(b - a, p - a) >= 0.0 && cross(c - b, p - b) >= 0.0 && cross(a - c, p - c) >= 0.0
+}
+
+/// Is the corner at `i` convex?
+fn is_convex_corner(points: &[Pos2], ring: &[u32], i: usize) -> bool {
+    let n = ring.len();
+    let a = points[ring[(i + n - 1) % n] as usize];
+    let b = points[ring[i] as usize];
+    let c = points[ring[(i + 1) % n] as usize];
+    cross(b - a, c - b) >= 0.0
+}
+
+/// Is `p` within the corner at `i`, as seen from that corner?
+fn is_locally_inside(points: &[Pos2], ring: &[u32], i: usize, p: Pos2) -> bool {
+    let n = ring.len();
+    let a = points[ring[(i + n - 1) % n] as usize];
+    let b = points[ring[i] as usize];
+    let c = points[ring[(i + 1) % n] as usize];
+    let left_of_incoming = cross(b - a, p - b) >= 0.0;
+    let left_of_outgoing = cross(c - b, p - b) >= 0.0;
+    if is_convex_corner(points, ring, i) {
+        left_of_incoming && left_of_outgoing
+    } else {
+        left_of_incoming || left_of_outgoing
+    }
+}
+
+/// Cut the outline open and splice in the hole, so we get one polygon without holes.
+///
+/// We connect the rightmost point of the hole to a point of the outline that is visible from it.
+/// See "Triangulation by Ear Clipping" by David Eberly.
+fn bridge_hole(points: &[Pos2], outline: &mut Vec<u32>, hole: Vec<u32>) {
+    let (m_index, m) = hole
+        .iter()
+        .enumerate()
+        .map(|(i, &j)| (i, points[j as usize]))
+        .max_by(|(_, a), (_, b)| a.x.total_cmp(&b.x))
+        .unwrap();
+
+    // Cast a ray to the right of `m`, and find the closest edge it hits:
+    let n = outline.len();
+    let mut closest: Option<(f32, usize)> = None;
+    for i in 0..n {
+        let a = points[outline[i] as usize];
+        let b = points[outline[(i + 1) % n] as usize];
+        if (a.y <= m.y) == (b.y <= m.y) {
+            continue;
+        }
+        let x = a.x + (m.y - a.y) * (b.x - a.x) / (b.y - a.y);
+        if x >= m.x && !matches!(closest, Some((closest_x, _)) if closest_x <= x) {
+            // Of the two ends of the edge, the one to the right:
+            let end = if a.x > b.x { i } else { (i + 1) % n };
+            closest = Some((x, end));
+        }
+    }
+    let (hit_x, mut bridge) = match closest {
+        Some(closest) => closest,
+        None => return, // The hole is outside of the outline
+    };
+
+    // A reflex corner of the outline could block the view.
+    // If so, the blocking corner that is closest in angle to the ray is visible instead.
+    let hit = pos2(hit_x, m.y);
+    let end = points[outline[bridge] as usize];
+    if end != hit {
+        let triangle = if end.y < m.y {
+            [m, end, hit]
+        } else {
+            [m, hit, end]
+        };
+        let mut best_cos = (end.x - m.x) / m.distance(end);
+        for i in 0..n {
+            let p = points[outline[i] as usize];
+            if i == bridge
+                || is_convex_corner(points, outline, i)
+                || !point_in_triangle(p, triangle)
+            {
+                continue;
+            }
+            let cos = (p.x - m.x) / m.distance(p);
+            if cos > best_cos {
+                best_cos = cos;
+                bridge = i;
+            }
+        }
+    }
+
+    // The point we bridge to occurs twice if another hole is already bridged to it.
+    // Pick the one we can reach without crossing that other bridge:
+    let bridge_point = points[outline[bridge] as usize];
+    if let Some(i) = (0..n).find(|&i| {
+        points[outline[i] as usize] == bridge_point && is_locally_inside(points, outline, i, m)
+    }) {
+        bridge = i;
+    }
+
+    // Walk from the outline into the hole, all the way around it, and back again:
+    let mut spliced = Vec::with_capacity(hole.len() + 2);
+    spliced.extend_from_slice(&hole[m_index..]);
+    spliced.extend_from_slice(&hole[..=m_index]);
+    spliced.push(outline[bridge]);
+    outline.splice(bridge + 1..bridge + 1, spliced);
+}
+
+fn clip_ears(points: &[Pos2], mut ring: Vec<u32>, triangles: &mut Vec<u32>) {
+    let mut i = 0;
+    let mut num_misses = 0;
+    while ring.len() > 3 {
+        let n = ring.len();
+        i %= n;
+        let corners = [ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]];
+        // If nothing is an ear the polygon must be self-intersecting, so we make do:
+        if num_misses >= n || is_ear(points, &ring, corners) {
+            triangles.extend_from_slice(&corners);
+            ring.remove(i);
+            num_misses = 0;
+        } else {
+            i += 1;
+            num_misses += 1;
+        }
+    }
+    if ring.len() == 3 {
+        triangles.extend_from_slice(&ring);
+    }
+}
+
+fn is_ear(points: &[Pos2], ring: &[u32], corners: [u32; 3]) -> bool {
+    let triangle = [
+        points[corners[0] as usize],
+        points[corners[1] as usize],
+        points[corners[2] as usize],
+    ];
+    let [a, b, c] = triangle;
+    let area = cross(b - a, c - b);
+    if area < 0.0 {
+        return false; // A reflex corner
+    }
+    if area == 0.0 {
+        return true; // Nothing can be in the way of removing a straight or degenerate corner
+    }
+    ring.iter().all(|&j| {
+        let p = points[j as usize];
+        // The points of a bridge to a hole occur twice:
+        p == a || p == b || p == c || !point_in_triangle(p, triangle)
+    })
+}
+
+#[test]
+fn test_triangulate() {
+    let area = |points: &[Pos2], triangles: &[u32]| -> f32 {
+        triangles
+            .chunks(3)
+            .map(|t| {
+                let [a, b, c] = [
+                    points[t[0] as usize],
+                    points[t[1] as usize],
+                    points[t[2] as usize],
+                ];
+                cross(b - a, c - b) / 2.0
+            })
+            .sum()
+    };
+
+    // An L-shape, going the "wrong" way around:
+    let l_shape = [
+        pos2(0.0, 0.0),
+        pos2(0.0, 2.0),
+        pos2(2.0, 2.0),
+        pos2(2.0, 1.0),
+        pos2(1.0, 1.0),
+        pos2(1.0, 0.0),
+    ];
+    let triangles = triangulate(&l_shape, &[]);
+    assert_eq!(triangles.len(), 3 * 4);
+    assert_eq!(area(&l_shape, &triangles), 3.0);
+
+    // A square with a square hole:
+    let framed = [
+        pos2(0.0, 0.0),
+        pos2(4.0, 0.0),
+        pos2(4.0, 4.0),
+        pos2(0.0, 4.0),
+        pos2(1.0, 1.0),
+        pos2(3.0, 1.0),
+        pos2(3.0, 3.0),
+        pos2(1.0, 3.0),
+    ];
+    let triangles = triangulate(&framed, &[4]);
+    assert_eq!(triangles.len(), 3 * 8);
+    assert_eq!(area(&framed, &triangles), 12.0);
+
+    // A bow-tie crosses itself, but we should still get something:
+    let bow_tie = [
+        pos2(0.0, 0.0),
+        pos2(2.0, 2.0),
+        pos2(2.0, 0.0),
+        pos2(0.0, 2.0),
+    ];
+    assert_eq!(triangulate(&bow_tie, &[]).len(), 3 * 2);
+
+    assert!(is_convex(&framed[..4]));
+    assert!(!is_convex(&l_shape));
+    assert!(!is_convex(&bow_tie));
+    let pentagram: Vec<Pos2> = (0..5)
+        .map(|i| pos2(0.0, 0.0) + Vec2::angled(i as f32 * 2.0 * TAU / 5.0))
+        .collect();
+    assert!(!is_convex(&pentagram));
+
+    // Not a number, but we should still get something:
+    let mut framed_nan = framed;
+    framed_nan[5].x = f32::NAN;
+    framed_nan[2].y = f32::NAN;
+    triangulate(&framed_nan, &[4]);
+}
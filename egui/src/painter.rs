@@ -4,8 +4,8 @@ use crate::{
     align::{anchor_rect, Align, LEFT_TOP},
     color,
     layers::PaintCmdIdx,
-    math::{Pos2, Rect, Vec2},
-    paint::{font, Fonts, PaintCmd, Stroke, TextStyle},
+    math::{Pos2, Rect, Transform, Vec2},
+    paint::{font, CornerRadius, Fill, Fonts, PaintCmd, Stroke, TextStyle},
     Context, LayerId, Srgba,
 };
 
@@ -21,6 +21,12 @@ pub struct Painter {
     /// Everything painted in this `Painter` will be clipped against this.
     /// This means nothing outside of this rectangle will be visible on screen.
     clip_rect: Rect,
+
+    /// From the coordinates we paint in to screen coordinates, e.g. in a `Scene`.
+    transform: Transform,
+
+    /// Fonts for text laid out at the scale of `transform`, if it is not `1.0`.
+    scaled_fonts: Option<Arc<Fonts>>,
 }
 
 impl Painter {
@@ -29,16 +35,14 @@ impl Painter {
             ctx,
             layer_id,
             clip_rect,
+            transform: Transform::identity(),
+            scaled_fonts: None,
         }
     }
 
     #[must_use]
     pub fn with_layer_id(self, layer_id: LayerId) -> Self {
-        Self {
-            ctx: self.ctx,
-            layer_id,
-            clip_rect: self.clip_rect,
-        }
+        Self { layer_id, ..self }
     }
 
     /// redirect
@@ -51,11 +55,20 @@ impl Painter {
     /// The clip-rect of the returned `Painter` will be the intersection
     /// of the given rectangle and the `clip_rect()` of this `Painter`.
     pub fn sub_region(&self, rect: Rect) -> Self {
-        Self::new(
-            self.ctx.clone(),
-            self.layer_id,
-            rect.intersect(self.clip_rect),
-        )
+        Self {
+            clip_rect: rect.intersect(self.clip_rect),
+            ..self.clone()
+        }
+    }
+
+    /// Everything painted after this is transformed by `transform` (after any previous transform).
+    /// Text is laid out at the resulting scale, so it stays sharp.
+    ///
+    /// The clip rectangle is transformed too, so it stays in place on screen.
+    pub fn add_transform(&mut self, transform: Transform) {
+        self.clip_rect = transform.inverse().transform_rect(self.clip_rect);
+        self.transform = self.transform * transform;
+        self.scaled_fonts = self.ctx.fonts().scaled(self.transform.scale);
     }
 }
 
@@ -65,9 +78,17 @@ impl Painter {
         &self.ctx
     }
 
-    /// Available fonts
+    /// Available fonts. Inside a `Scene` these are scaled to its zoom.
     pub(crate) fn fonts(&self) -> &Fonts {
-        self.ctx.fonts()
+        self.scaled_fonts
+            .as_deref()
+            .unwrap_or_else(|| self.ctx.fonts())
+    }
+
+    /// From the coordinates we paint in to screen coordinates.
+    /// This is the identity except inside a `Scene`.
+    pub fn current_transform(&self) -> Transform {
+        self.transform
     }
 
     /// Where we paint
@@ -107,27 +128,45 @@ impl Painter {
 impl Painter {
     /// It is up to the caller to make sure there is room for this.
     /// Can be used for free painting.
-    /// NOTE: all coordinates are screen coordinates!
+    /// NOTE: all coordinates are screen coordinates, unless inside a `Scene`!
     pub fn add(&self, paint_cmd: PaintCmd) -> PaintCmdIdx {
+        let (clip_rect, paint_cmd) = self.transformed(paint_cmd);
         self.ctx
             .graphics()
             .list(self.layer_id)
-            .add(self.clip_rect, paint_cmd)
+            .add(clip_rect, paint_cmd)
     }
 
-    pub fn extend(&self, cmds: Vec<PaintCmd>) {
+    pub fn extend(&self, mut cmds: Vec<PaintCmd>) {
+        if !self.transform.is_identity() {
+            for cmd in &mut cmds {
+                cmd.transform(self.transform);
+            }
+        }
+        let clip_rect = self.transform.transform_rect(self.clip_rect);
         self.ctx
             .graphics()
             .list(self.layer_id)
-            .extend(self.clip_rect, cmds);
+            .extend(clip_rect, cmds);
     }
 
     /// Modify an existing command.
     pub fn set(&self, idx: PaintCmdIdx, cmd: PaintCmd) {
+        let (clip_rect, cmd) = self.transformed(cmd);
         self.ctx
             .graphics()
             .list(self.layer_id)
-            .set(idx, self.clip_rect, cmd)
+            .set(idx, clip_rect, cmd)
+    }
+
+    /// To screen coordinates.
+    fn transformed(&self, mut paint_cmd: PaintCmd) -> (Rect, PaintCmd) {
+        if self.transform.is_identity() {
+            (self.clip_rect, paint_cmd)
+        } else {
+            paint_cmd.transform(self.transform);
+            (self.transform.transform_rect(self.clip_rect), paint_cmd)
+        }
     }
 }
 
@@ -147,8 +186,8 @@ impl Painter {
         let rect = anchor_rect(Rect::from_min_size(pos, galley.size), LEFT_TOP);
         self.add(PaintCmd::Rect {
             rect: rect.expand(2.0),
-            corner_radius: 0.0,
-            fill: Srgba::black_alpha(240),
+            corner_radius: 0.0.into(),
+            fill: Srgba::black_alpha(240).into(),
             stroke: Stroke::new(1.0, color::RED),
         });
         self.galley(rect.min, galley, text_style, color::RED);
@@ -221,38 +260,60 @@ impl Painter {
         });
     }
 
+    /// The fill can be a gradient, and each corner can have a different radius.
     pub fn rect(
         &self,
         rect: Rect,
-        corner_radius: f32,
-        fill_color: impl Into<Srgba>,
+        corner_radius: impl Into<CornerRadius>,
+        fill: impl Into<Fill>,
         stroke: impl Into<Stroke>,
     ) {
         self.add(PaintCmd::Rect {
             rect,
-            corner_radius,
-            fill: fill_color.into(),
+            corner_radius: corner_radius.into(),
+            fill: fill.into(),
             stroke: stroke.into(),
         });
     }
 
-    pub fn rect_filled(&self, rect: Rect, corner_radius: f32, fill_color: impl Into<Srgba>) {
+    pub fn rect_filled(
+        &self,
+        rect: Rect,
+        corner_radius: impl Into<CornerRadius>,
+        fill: impl Into<Fill>,
+    ) {
         self.add(PaintCmd::Rect {
             rect,
-            corner_radius,
-            fill: fill_color.into(),
+            corner_radius: corner_radius.into(),
+            fill: fill.into(),
             stroke: Default::default(),
         });
     }
 
-    pub fn rect_stroke(&self, rect: Rect, corner_radius: f32, stroke: impl Into<Stroke>) {
+    pub fn rect_stroke(
+        &self,
+        rect: Rect,
+        corner_radius: impl Into<CornerRadius>,
+        stroke: impl Into<Stroke>,
+    ) {
         self.add(PaintCmd::Rect {
             rect,
-            corner_radius,
+            corner_radius: corner_radius.into(),
             fill: Default::default(),
             stroke: stroke.into(),
         });
     }
+
+    /// A smooth curve from `points[0]` to `points[2]`, pulled towards `points[1]`.
+    pub fn quadratic_bezier(&self, points: [Pos2; 3], stroke: impl Into<Stroke>) {
+        self.add(PaintCmd::quadratic_bezier(points, stroke));
+    }
+
+    /// A smooth curve from `points[0]` to `points[3]`,
+    /// leaving towards `points[1]` and arriving from `points[2]`.
+    pub fn cubic_bezier(&self, points: [Pos2; 4], stroke: impl Into<Stroke>) {
+        self.add(PaintCmd::cubic_bezier(points, stroke));
+    }
 }
 
 /// ## Text
@@ -278,12 +339,19 @@ impl Painter {
     }
 
     /// Paint text that has already been layed out in a `Galley`.
-    pub fn galley(&self, pos: Pos2, galley: font::Galley, text_style: TextStyle, color: Srgba) {
+    pub fn galley(
+        &self,
+        pos: Pos2,
+        galley: impl Into<Arc<font::Galley>>,
+        text_style: TextStyle,
+        color: Srgba,
+    ) {
         self.add(PaintCmd::Text {
             pos,
-            galley,
+            galley: galley.into(),
             text_style,
             color,
+            scale: 1.0,
         });
     }
 }
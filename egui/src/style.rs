@@ -3,7 +3,7 @@
 use crate::{
     color::*,
     math::*,
-    paint::{Stroke, TextStyle},
+    paint::{Shadow, Stroke, TextStyle},
     types::*,
 };
 
@@ -129,6 +129,11 @@ pub struct Visuals {
 
     pub window_corner_radius: f32,
 
+    pub window_shadow: Shadow,
+
+    /// For popups and menus
+    pub popup_shadow: Shadow,
+
     pub resize_corner_size: f32,
 
     /// Blink text cursor by this frequency. If 0, always show the cursor.
@@ -259,6 +264,8 @@ impl Default for Visuals {
             widgets: Default::default(),
             dark_bg_color: Srgba::black_alpha(140),
             window_corner_radius: 10.0,
+            window_shadow: Shadow::big(),
+            popup_shadow: Shadow::small(),
             resize_corner_size: 12.0,
             cursor_blink_hz: 0.0, // 1.0 looks good
             text_cursor_width: 2.0,
@@ -443,6 +450,8 @@ impl Visuals {
             widgets,
             dark_bg_color,
             window_corner_radius,
+            window_shadow,
+            popup_shadow,
             resize_corner_size,
             cursor_blink_hz,
             text_cursor_width,
@@ -454,6 +463,8 @@ impl Visuals {
         ui.collapsing("widgets", |ui| widgets.ui(ui));
         ui_color(ui, dark_bg_color, "dark_bg_color");
         ui.add(Slider::f32(window_corner_radius, 0.0..=20.0).text("window_corner_radius"));
+        window_shadow.ui(ui, "window_shadow");
+        popup_shadow.ui(ui, "popup_shadow");
         ui.add(Slider::f32(resize_corner_size, 0.0..=20.0).text("resize_corner_size"));
         ui.add(Slider::f32(cursor_blink_hz, 0.0..=4.0).text("cursor_blink_hz"));
         ui.add(Slider::f32(text_cursor_width, 0.0..=2.0).text("text_cursor_width"));
@@ -466,7 +477,11 @@ impl Visuals {
 
 impl Stroke {
     pub fn ui(&mut self, ui: &mut crate::Ui, text: &str) {
-        let Self { width, color } = self;
+        let Self {
+            width,
+            color,
+            pattern,
+        } = self;
         ui.horizontal(|ui| {
             ui.add(DragValue::f32(width).speed(0.1).range(0.0..=5.0))
                 .on_hover_text("Width");
@@ -477,7 +492,28 @@ impl Stroke {
             let stroke_rect = ui.allocate_space(ui.style().spacing.interact_size);
             let left = stroke_rect.left_center();
             let right = stroke_rect.right_center();
-            ui.painter().line_segment([left, right], (*width, *color));
+            ui.painter()
+                .line_segment([left, right], Stroke::new(*width, *color).pattern(*pattern));
+        });
+    }
+}
+
+impl Shadow {
+    pub fn ui(&mut self, ui: &mut crate::Ui, text: &str) {
+        let Self {
+            offset,
+            extrusion,
+            color,
+        } = self;
+        ui.horizontal(|ui| {
+            ui.add(DragValue::f32(&mut offset.x).speed(0.2).range(-32.0..=32.0))
+                .on_hover_text("Offset x");
+            ui.add(DragValue::f32(&mut offset.y).speed(0.2).range(-32.0..=32.0))
+                .on_hover_text("Offset y");
+            ui.add(DragValue::f32(extrusion).speed(0.2).range(0.0..=64.0))
+                .on_hover_text("Extrusion");
+            ui.color_edit_button_srgba(color);
+            ui.label(text);
         });
     }
 }
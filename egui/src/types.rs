@@ -18,6 +18,10 @@ pub struct Output {
     /// Response to Event::Copy or Event::Cut. Ignore if empty.
     pub copied_text: String,
 
+    /// Where the text cursor is on screen, if a `TextEdit` has keyboard focus.
+    /// The backend can use this to place the IME candidate window.
+    pub text_cursor_rect: Option<Rect>,
+
     /// If `true`, Egui or a user is indicating that the UI needs immediate repaint (e.g. on the next frame).
     /// This happens for instance when there is an animation, or if a user has called `Context::request_repaint()`.
     /// Don't set this manually, but call `Context::request_repaint()` instead.
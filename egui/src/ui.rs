@@ -54,6 +54,9 @@ pub struct Ui {
     /// This is only used to create a unique interact ID for some widgets
     /// that work as long as no other widgets are added/removed while interacting.
     child_count: usize,
+
+    /// Inside a `Scene`: the input in the coordinates of the scene.
+    input: Option<Arc<InputState>>,
 }
 
 impl Ui {
@@ -81,6 +84,7 @@ impl Ui {
             layout,
             cursor,
             child_count: 0,
+            input: None,
         }
     }
 
@@ -101,6 +105,7 @@ impl Ui {
             layout,
             cursor,
             child_count: 0,
+            input: self.input.clone(),
         }
     }
 
@@ -162,9 +167,18 @@ impl Ui {
     }
 
     /// The `Input` of the `Context` associated with the `Ui`.
-    /// Equivalent to `.ctx().input()`.
+    /// Equivalent to `.ctx().input()`, except inside a `Scene`,
+    /// where the mouse is in the coordinates of the scene.
     pub fn input(&self) -> &InputState {
-        self.ctx().input()
+        self.input.as_deref().unwrap_or_else(|| self.ctx().input())
+    }
+
+    /// Hide the events `consume` returns true for from what is added to this ui from now on,
+    /// e.g. keys that a container handles itself.
+    pub(crate) fn consume_events(&mut self, consume: impl Fn(&Event) -> bool) {
+        let mut input = self.input().clone();
+        input.events.retain(|event| !consume(event));
+        self.input = Some(Arc::new(input));
     }
 
     /// The `Memory` of the `Context` associated with the `Ui`.
@@ -180,9 +194,10 @@ impl Ui {
     }
 
     /// The `Fonts` of the `Context` associated with the `Ui`.
-    /// Equivalent to `.ctx().fonts()`.
+    /// Equivalent to `.ctx().fonts()`, except inside a zoomed `Scene`,
+    /// where text is laid out at the zoomed scale.
     pub fn fonts(&self) -> &Fonts {
-        self.ctx().fonts()
+        self.painter.fonts()
     }
 
     /// Screen-space rectangle for clipping what we paint in this ui.
@@ -196,6 +211,31 @@ impl Ui {
     pub fn set_clip_rect(&mut self, clip_rect: Rect) {
         self.painter.set_clip_rect(clip_rect);
     }
+
+    /// Transform everything painted in this ui and its children, e.g. to pan and zoom.
+    /// The input is transformed the other way, so that widgets still work.
+    ///
+    /// After this, positions and rectangles of this ui (e.g. `max_rect`, `clip_rect`
+    /// and those in `Response`:s) are in the new coordinates.
+    /// Convert them with `to_screen_rect` to place an `Area` next to them.
+    /// Use a `Scene` for a pannable, zoomable region.
+    pub fn add_transform(&mut self, transform: Transform) {
+        self.painter.add_transform(transform);
+        let inverse = transform.inverse();
+        self.min_rect = inverse.transform_rect(self.min_rect);
+        self.max_rect = inverse.transform_rect(self.max_rect);
+        self.cursor = inverse.transform_pos(self.cursor);
+        let to_local = self.painter.current_transform().inverse();
+        self.input = Some(Arc::new(self.ctx().input().transformed(to_local)));
+    }
+
+    /// From the coordinates of this ui to screen coordinates.
+    /// They are the same except inside a `Scene`.
+    ///
+    /// `Area`:s (e.g. popups) are in screen coordinates, so use this to place one next to a widget.
+    pub fn to_screen_rect(&self, rect: Rect) -> Rect {
+        self.painter.current_transform().transform_rect(rect)
+    }
 }
 
 // ------------------------------------------------------------------------
@@ -386,18 +426,23 @@ impl Ui {
 /// # Interaction
 impl Ui {
     pub fn interact(&self, rect: Rect, id: Id, sense: Sense) -> Response {
-        self.ctx()
-            .interact(self.layer_id(), self.clip_rect(), rect, Some(id), sense)
+        self.interact_impl(rect, Some(id), sense)
     }
 
     pub fn interact_hover(&self, rect: Rect) -> Response {
-        self.ctx().interact(
+        self.interact_impl(rect, None, Sense::nothing())
+    }
+
+    fn interact_impl(&self, rect: Rect, id: Option<Id>, sense: Sense) -> Response {
+        let mut response = self.ctx().interact(
             self.layer_id(),
-            self.clip_rect(),
-            rect,
-            None,
-            Sense::nothing(),
-        )
+            self.to_screen_rect(self.clip_rect()),
+            self.to_screen_rect(rect),
+            id,
+            sense,
+        );
+        response.rect = rect;
+        response
     }
 
     pub fn hovered(&self, rect: Rect) -> bool {
@@ -405,8 +450,11 @@ impl Ui {
     }
 
     pub fn contains_mouse(&self, rect: Rect) -> bool {
-        self.ctx()
-            .contains_mouse(self.layer_id(), self.clip_rect(), rect)
+        self.ctx().contains_mouse(
+            self.layer_id(),
+            self.to_screen_rect(self.clip_rect()),
+            self.to_screen_rect(rect),
+        )
     }
 
     // ------------------------------------------------------------------------
@@ -609,10 +657,7 @@ impl Ui {
     /// If the user clicks the button, a full color picker is shown.
     /// The given color is in linear RGBA space with premultiplied alpha
     pub fn color_edit_button_rgba_premultiplied(&mut self, rgba: &mut [f32; 4]) -> Response {
-        let mut hsva = Hsva::from_rgba_premultiplied(*rgba);
-        let response = self.color_edit_button_hsva(&mut hsva);
-        *rgba = hsva.to_rgba_premultiplied();
-        response
+        widgets::color_picker::color_edit_button_rgba_premultiplied(self, rgba)
     }
 
     /// Shows a button with the given color.
@@ -625,6 +670,14 @@ impl Ui {
         *rgba = hsva.to_rgba_unmultiplied();
         response
     }
+
+    /// Edit a color gradient: drag, add and remove color stops and click them to change their color.
+    pub fn gradient_edit(
+        &mut self,
+        gradient: &mut widgets::color_picker::ColorGradient,
+    ) -> Response {
+        widgets::color_picker::gradient_edit(self, gradient)
+    }
 }
 
 /// # Adding Containers / Sub-uis:
@@ -851,3 +904,56 @@ impl Ui {
         self.layout.debug_paint_cursor(self.cursor, &self.painter);
     }
 }
+
+#[test]
+fn test_interact_under_transform() {
+    let mut ctx = Context::new();
+    // Zoomed in two times, with the origin at (100, 50) on screen:
+    let transform = Transform::new(2.0, vec2(100.0, 50.0));
+    let rect = Rect::from_min_size(pos2(0.0, 0.0), vec2(10.0, 10.0));
+    let rect_on_screen = Rect::from_min_max(pos2(100.0, 50.0), pos2(120.0, 70.0));
+    let text_edit_id = Id::new("text_edit");
+    let mut text = "abc".to_owned();
+    // The first frame is for the area to know its size:
+    for &(mouse_pos, hovered) in &[
+        (pos2(115.0, 65.0), false),
+        (pos2(115.0, 65.0), true),
+        (pos2(5.0, 5.0), false),
+    ] {
+        ctx.begin_frame(RawInput {
+            screen_size: vec2(400.0, 300.0),
+            mouse_pos: Some(mouse_pos),
+            ..Default::default()
+        });
+        ctx.memory().request_kb_focus(text_edit_id);
+        let mut response = None;
+        let mut text_edit_rect_on_screen = None;
+        Area::new("transformed")
+            .fixed_pos(pos2(0.0, 0.0))
+            .show(&ctx, |ui| {
+                let mut transformed_ui = ui.child_ui(ui.max_rect(), Layout::default());
+                transformed_ui.add_transform(transform);
+                assert_eq!(transformed_ui.to_screen_rect(rect), rect_on_screen);
+                assert_eq!(
+                    transformed_ui.input().mouse.pos,
+                    Some(transform.inverse().transform_pos(mouse_pos))
+                );
+                response = Some(transformed_ui.interact(rect, Id::new("rect"), Sense::click()));
+                let text_edit = TextEdit::new(&mut text).id(text_edit_id);
+                let text_edit_rect = transformed_ui.add(text_edit).rect;
+                text_edit_rect_on_screen = Some(transformed_ui.to_screen_rect(text_edit_rect));
+                ui.allocate_space(vec2(200.0, 100.0)); // So the area covers the rect on screen
+            });
+        let (output, _) = ctx.end_frame();
+        let response = response.unwrap();
+        assert_eq!(response.hovered, hovered);
+        assert_eq!(response.rect, rect);
+        // The IME candidate window goes next to the cursor on screen:
+        let text_cursor_rect = output.text_cursor_rect.unwrap();
+        assert!(text_edit_rect_on_screen
+            .unwrap()
+            .contains(text_cursor_rect.center()));
+        let line_spacing = ctx.fonts()[ctx.style().body_text_style].line_spacing();
+        assert_eq!(text_cursor_rect.height(), 2.0 * line_spacing);
+    }
+}
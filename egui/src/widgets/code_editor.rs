@@ -0,0 +1,442 @@
+//! A multiline code editor with syntax highlighting and line numbers.
+
+use std::{collections::HashMap, ops::Range};
+
+use super::text_layout::font_key;
+use crate::{align::RIGHT_TOP, color::srgba, paint::*, *};
+
+/// A colored range of a line of code.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColoredSpan {
+    /// Byte range in the line.
+    pub byte_range: Range<usize>,
+    pub color: Srgba,
+}
+
+/// Syntax highlighting for the `CodeEditor`.
+pub trait Highlighter {
+    /// Color parts of a single line of code (without the trailing newline).
+    ///
+    /// The spans must be sorted and non-overlapping.
+    /// Parts of the line not covered by any span get the default text color.
+    fn highlight_line(&self, line: &str) -> Vec<ColoredSpan>;
+}
+
+/// No highlighting.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlainText;
+
+impl Highlighter for PlainText {
+    fn highlight_line(&self, _line: &str) -> Vec<ColoredSpan> {
+        vec![]
+    }
+}
+
+const COMMENT_COLOR: Srgba = srgba(120, 120, 120, 255);
+const KEYWORD_COLOR: Srgba = srgba(255, 130, 100, 255);
+const LITERAL_COLOR: Srgba = srgba(180, 160, 255, 255);
+const STRING_COLOR: Srgba = srgba(150, 220, 120, 255);
+const TYPE_COLOR: Srgba = srgba(110, 200, 220, 255);
+
+/// Highlighting for Rust code.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RustHighlighter;
+
+impl Highlighter for RustHighlighter {
+    fn highlight_line(&self, line: &str) -> Vec<ColoredSpan> {
+        const KEYWORDS: &[&str] = &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+            "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+            "type", "unsafe", "use", "where", "while",
+        ];
+
+        let mut spans = vec![];
+        let mut it = line.char_indices().peekable();
+        while let Some((start, c)) = it.next() {
+            if line[start..].starts_with("//") {
+                spans.push(span(start..line.len(), COMMENT_COLOR));
+                break;
+            } else if c == '"' {
+                let end = string_end(line, start);
+                spans.push(span(start..end, STRING_COLOR));
+                skip_to(&mut it, end);
+            } else if c.is_ascii_digit() {
+                let end = word_end(line, start);
+                spans.push(span(start..end, LITERAL_COLOR));
+                skip_to(&mut it, end);
+            } else if is_word_char(c) {
+                let end = word_end(line, start);
+                let word = &line[start..end];
+                if KEYWORDS.contains(&word) {
+                    spans.push(span(start..end, KEYWORD_COLOR));
+                } else if word == "true" || word == "false" {
+                    spans.push(span(start..end, LITERAL_COLOR));
+                } else if c.is_uppercase() {
+                    spans.push(span(start..end, TYPE_COLOR));
+                }
+                skip_to(&mut it, end);
+            }
+        }
+        spans
+    }
+}
+
+/// Highlighting for TOML files.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TomlHighlighter;
+
+impl Highlighter for TomlHighlighter {
+    fn highlight_line(&self, line: &str) -> Vec<ColoredSpan> {
+        let mut spans = vec![];
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('[') {
+            let start = line.len() - trimmed.len();
+            let end = line.find('#').unwrap_or(line.len());
+            spans.push(span(start..end, TYPE_COLOR));
+            if end < line.len() {
+                spans.push(span(end..line.len(), COMMENT_COLOR));
+            }
+            return spans;
+        }
+
+        let mut is_key = true;
+        let mut it = line.char_indices().peekable();
+        while let Some((start, c)) = it.next() {
+            if c == '#' {
+                spans.push(span(start..line.len(), COMMENT_COLOR));
+                break;
+            } else if c == '"' || c == '\'' {
+                let end = string_end(line, start);
+                let color = if is_key { KEYWORD_COLOR } else { STRING_COLOR };
+                spans.push(span(start..end, color));
+                skip_to(&mut it, end);
+            } else if c == '=' {
+                is_key = false;
+            } else if is_word_char(c) || c == '-' {
+                let end = line[start..]
+                    .find(|c: char| !is_word_char(c) && c != '-' && c != '.')
+                    .map_or(line.len(), |len| start + len);
+                let word = &line[start..end];
+                if is_key {
+                    spans.push(span(start..end, KEYWORD_COLOR));
+                } else if word == "true" || word == "false" || c.is_ascii_digit() || c == '-' {
+                    spans.push(span(start..end, LITERAL_COLOR));
+                }
+                skip_to(&mut it, end);
+            }
+        }
+        spans
+    }
+}
+
+fn span(byte_range: Range<usize>, color: Srgba) -> ColoredSpan {
+    ColoredSpan { byte_range, color }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn word_end(line: &str, start: usize) -> usize {
+    line[start..]
+        .find(|c: char| !is_word_char(c))
+        .map_or(line.len(), |len| start + len)
+}
+
+/// End of the string literal starting with a quote at `start`.
+fn string_end(line: &str, start: usize) -> usize {
+    let quote = line[start..].chars().next().unwrap();
+    let mut escaped = false;
+    for (i, c) in line[start + 1..].char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return start + 1 + i + 1;
+        }
+    }
+    line.len()
+}
+
+fn skip_to(it: &mut std::iter::Peekable<std::str::CharIndices<'_>>, end: usize) {
+    while matches!(it.peek(), Some(&(i, _)) if i < end) {
+        it.next();
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct State {
+    /// Highlighting of each line, keyed by the hash of the line contents.
+    highlighted_lines: HashMap<u64, Vec<ColoredSpan>>,
+
+    /// The last layout, and the hash of what produced it.
+    galley: Option<(u64, font::Galley)>,
+}
+
+impl State {
+    fn layout(
+        &mut self,
+        fonts: &Fonts,
+        text_style: TextStyle,
+        highlighter: &dyn Highlighter,
+        text: &str,
+        wrap_width: f32,
+        default_color: Srgba,
+    ) -> font::Galley {
+        let key = hash((
+            text,
+            wrap_width.to_bits(),
+            default_color,
+            font_key(fonts, text_style),
+        ));
+        if let Some((galley_key, galley)) = &self.galley {
+            if *galley_key == key {
+                return galley.clone();
+            }
+        }
+
+        let section = |char_range: Range<usize>, color: Srgba| font::TextSection {
+            char_range,
+            text_style,
+            color,
+            underline: false,
+            background: color::TRANSPARENT,
+        };
+
+        // Only keep the highlighting of lines that are still around:
+        let mut highlighted_lines = HashMap::new();
+        let mut sections = vec![];
+        let mut char_idx = 0;
+        for (line_nr, line) in text.split('\n').enumerate() {
+            if line_nr > 0 {
+                sections.push(section(char_idx..char_idx + 1, default_color));
+                char_idx += 1;
+            }
+
+            let line_hash = hash(line);
+            let spans = highlighted_lines
+                .get(&line_hash)
+                .cloned()
+                .or_else(|| self.highlighted_lines.remove(&line_hash))
+                .unwrap_or_else(|| highlighter.highlight_line(line));
+
+            let mut byte_idx = 0;
+            for span in &spans {
+                if byte_idx < span.byte_range.start {
+                    let num_chars = line[byte_idx..span.byte_range.start].chars().count();
+                    sections.push(section(char_idx..char_idx + num_chars, default_color));
+                    char_idx += num_chars;
+                }
+                let num_chars = line[span.byte_range.clone()].chars().count();
+                sections.push(section(char_idx..char_idx + num_chars, span.color));
+                char_idx += num_chars;
+                byte_idx = span.byte_range.end;
+            }
+            if byte_idx < line.len() {
+                let num_chars = line[byte_idx..].chars().count();
+                sections.push(section(char_idx..char_idx + num_chars, default_color));
+                char_idx += num_chars;
+            }
+
+            highlighted_lines.insert(line_hash, spans);
+        }
+        self.highlighted_lines = highlighted_lines;
+
+        let galley = fonts.layout_sections(text.to_owned(), sections, wrap_width);
+        self.galley = Some((key, galley.clone()));
+        galley
+    }
+}
+
+fn hash(value: impl std::hash::Hash) -> u64 {
+    use std::hash::Hasher;
+    let mut hasher = ahash::AHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// ----------------------------------------------------------------------------
+
+/// A multiline `TextEdit` for editing code.
+///
+/// Uses a monospace font, shows line numbers, highlights the line with the cursor,
+/// auto-indents on enter and indents/outdents with tab/shift+tab.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// # let mut code = String::new();
+/// ui.add(egui::CodeEditor::new(&mut code).highlighter(&egui::RustHighlighter));
+/// ```
+pub struct CodeEditor<'t> {
+    text: &'t mut String,
+    id_source: Option<Id>,
+    highlighter: &'t dyn Highlighter,
+    line_numbers: bool,
+}
+
+impl<'t> CodeEditor<'t> {
+    pub fn new(text: &'t mut String) -> Self {
+        Self {
+            text,
+            id_source: None,
+            highlighter: &PlainText,
+            line_numbers: true,
+        }
+    }
+
+    pub fn id_source(mut self, id_source: impl std::hash::Hash) -> Self {
+        self.id_source = Some(Id::new(id_source));
+        self
+    }
+
+    /// Highlighting is cached per line,
+    /// so do not switch the highlighter of an editor.
+    pub fn highlighter(mut self, highlighter: &'t dyn Highlighter) -> Self {
+        self.highlighter = highlighter;
+        self
+    }
+
+    /// Default is `true`.
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+}
+
+impl<'t> Widget for CodeEditor<'t> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let CodeEditor {
+            text,
+            id_source,
+            highlighter,
+            line_numbers,
+        } = self;
+
+        let id = ui.make_persistent_id(id_source);
+        let text_style = TextStyle::Monospace;
+        let default_color = ui.style().visuals.text_color();
+
+        let gutter_width = if line_numbers {
+            let num_lines = text.split('\n').count();
+            let widest_number = "0".repeat(num_lines.to_string().len().max(2));
+            ui.fonts()[text_style]
+                .layout_single_line(widest_number)
+                .size
+                .x
+        } else {
+            0.0
+        };
+
+        let where_to_put_background = ui.painter().add(PaintCmd::Noop);
+        let where_to_put_line_highlight = ui.painter().add(PaintCmd::Noop);
+
+        let layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+            ui.memory().code_editor.entry(id).or_default().layout(
+                ui.fonts(),
+                text_style,
+                highlighter,
+                text,
+                wrap_width,
+                default_color,
+            )
+        };
+
+        let (response, outer_response) = ui.horizontal(|ui| {
+            if line_numbers {
+                ui.allocate_space(vec2(gutter_width, 0.0));
+            }
+            ui.add(
+                TextEdit::new(text)
+                    .id(id)
+                    .text_style(text_style)
+                    .desired_width(f32::INFINITY)
+                    .frame(false)
+                    .code_editing(true)
+                    .layouter(&layouter),
+            )
+        });
+
+        // Where each line number goes, and where the cursor line is:
+        let mut line_number_ys = vec![];
+        let mut cursor_row = None;
+        {
+            let memory = ui.memory();
+            let galley = memory.code_editor.get(&id).and_then(|s| s.galley.as_ref());
+            let cursor = memory.text_edit.get(&id).and_then(|state| state.cursor);
+            if let (Some((_, galley)), Some(cursor)) = (galley, cursor) {
+                let cursor = galley.text.get(..cursor).map_or(0, |s| s.chars().count());
+                let mut is_new_line = true;
+                let mut char_idx = 0;
+                for line in &galley.lines {
+                    if is_new_line {
+                        line_number_ys.push(line.y_min);
+                    }
+                    is_new_line = line.ends_with_newline;
+                    let line_end = char_idx + line.char_count();
+                    if char_idx <= cursor && cursor < line_end && cursor_row.is_none() {
+                        cursor_row = Some(line.y_min..line.y_max);
+                    }
+                    char_idx = line_end;
+                }
+                if cursor_row.is_none() {
+                    cursor_row = galley.lines.last().map(|line| line.y_min..line.y_max);
+                }
+            }
+        }
+
+        let painter = ui.painter();
+        let visuals = ui.style().interact(&response);
+        let frame_rect = outer_response.rect.expand(2.0);
+        painter.set(
+            where_to_put_background,
+            PaintCmd::Rect {
+                rect: frame_rect,
+                corner_radius: visuals.corner_radius.into(),
+                fill: ui.style().visuals.dark_bg_color.into(),
+                stroke: visuals.bg_stroke,
+            },
+        );
+
+        if ui.memory().has_kb_focus(id) {
+            if let Some(row) = cursor_row {
+                let rect = Rect::from_min_max(
+                    pos2(frame_rect.left(), response.rect.top() + row.start),
+                    pos2(frame_rect.right(), response.rect.top() + row.end),
+                );
+                painter.set(
+                    where_to_put_line_highlight,
+                    PaintCmd::Rect {
+                        rect,
+                        corner_radius: 0.0.into(),
+                        fill: Srgba::additive_luminance(12).into(),
+                        stroke: Default::default(),
+                    },
+                );
+            }
+        }
+
+        if line_numbers {
+            let right = outer_response.rect.left() + gutter_width;
+            let clip_rect = painter.clip_rect();
+            for (line_nr, y) in line_number_ys.into_iter().enumerate() {
+                let y = response.rect.top() + y;
+                if y < clip_rect.top() - 100.0 || clip_rect.bottom() < y {
+                    continue; // Not visible
+                }
+                painter.text(
+                    pos2(right, y),
+                    RIGHT_TOP,
+                    (line_nr + 1).to_string(),
+                    text_style,
+                    COMMENT_COLOR,
+                );
+            }
+        }
+
+        response
+    }
+}
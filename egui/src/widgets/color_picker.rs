@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 use crate::{
     paint::{color::*, *},
     *,
@@ -47,8 +49,8 @@ fn show_srgba(ui: &mut Ui, srgba: Srgba, desired_size: Vec2) -> Response {
     background_checkers(ui.painter(), rect);
     ui.painter().add(PaintCmd::Rect {
         rect,
-        corner_radius: 2.0,
-        fill: srgba,
+        corner_radius: 2.0.into(),
+        fill: srgba.into(),
         stroke: Stroke::new(3.0, srgba.to_opaque()),
     });
     ui.interact_hover(rect)
@@ -63,8 +65,8 @@ fn color_button(ui: &mut Ui, color: Srgba) -> Response {
     background_checkers(ui.painter(), rect);
     ui.painter().add(PaintCmd::Rect {
         rect,
-        corner_radius: visuals.corner_radius.at_most(2.0),
-        fill: color,
+        corner_radius: visuals.corner_radius.at_most(2.0).into(),
+        fill: color.into(),
         stroke: visuals.fg_stroke,
     });
     response
@@ -186,41 +188,305 @@ fn color_slider_2d(
     response
 }
 
-fn color_picker_hsvag_2d(ui: &mut Ui, hsva: &mut HsvaGamma) {
+/// A hue ring around a saturation-value triangle.
+fn color_wheel(ui: &mut Ui, hsva: &mut HsvaGamma) -> Response {
+    let desired_size = Vec2::splat(ui.style().spacing.slider_width);
+    let rect = ui.allocate_space(desired_size);
+
+    let id = ui.make_position_id();
+    let response = ui.interact(rect, id, Sense::click_and_drag());
+
+    let center = rect.center();
+    let outer_radius = rect.width() / 2.0;
+    let inner_radius = 0.8 * outer_radius;
+    let direction = |turns: f32| Vec2::angled(turns * TAU);
+    // The corners of the triangle: pure hue, white and black.
+    let triangle = |h: f32| {
+        [
+            center + inner_radius * direction(h),
+            center + inner_radius * direction(h + 1.0 / 3.0),
+            center + inner_radius * direction(h + 2.0 / 3.0),
+        ]
+    };
+    // Black + value * (white - black) + value * saturation * (hue - white):
+    let pos_from_sv = |[hue, white, black]: [Pos2; 3], s: f32, v: f32| {
+        black + v * (white - black) + v * s * (hue - white)
+    };
+
+    if response.active {
+        let mouse = ui.input().mouse.pos;
+        let press_origin = ui.input().mouse.press_origin;
+        if let (Some(mouse_pos), Some(press_origin)) = (mouse, press_origin) {
+            let delta = mouse_pos - center;
+            if (press_origin - center).length() > inner_radius {
+                hsva.h = (delta.y.atan2(delta.x) / TAU).rem_euclid(1.0);
+            } else {
+                let [hue, white, black] = triangle(hsva.h);
+                let (e1, e2, d) = (white - black, hue - white, mouse_pos - black);
+                let det = e1.x * e2.y - e1.y * e2.x;
+                let v = clamp((d.x * e2.y - d.y * e2.x) / det, 0.0..=1.0);
+                let vs = clamp((e1.x * d.y - e1.y * d.x) / det, 0.0..=v);
+                hsva.v = v;
+                if v > 0.0 {
+                    hsva.s = vs / v;
+                }
+            }
+        }
+    }
+
+    let visuals = ui.style().interact(&response);
+
+    {
+        // The hue ring:
+        let n = 8 * N;
+        let mut triangles = Triangles::default();
+        for i in 0..=n {
+            let h = i as f32 / (n as f32);
+            let color = HsvaGamma::new(h, 1.0, 1.0, 1.0).into();
+            triangles.colored_vertex(center + inner_radius * direction(h), color);
+            triangles.colored_vertex(center + outer_radius * direction(h), color);
+            if i < n {
+                triangles.add_triangle(2 * i, 2 * i + 1, 2 * i + 2);
+                triangles.add_triangle(2 * i + 1, 2 * i + 2, 2 * i + 3);
+            }
+        }
+        ui.painter().add(PaintCmd::triangles(triangles));
+    }
+
+    {
+        // The triangle, as rows of vertices from the black corner (v = 0) to the far edge (v = 1):
+        let corners = triangle(hsva.h);
+        let vertex_idx = |row: u32, col: u32| row * (row + 1) / 2 + col;
+        let mut triangles = Triangles::default();
+        for row in 0..=N {
+            let v = row as f32 / (N as f32);
+            for col in 0..=row {
+                let s = if row == 0 {
+                    0.0
+                } else {
+                    col as f32 / row as f32
+                };
+                let color = HsvaGamma::new(hsva.h, s, v, 1.0).into();
+                triangles.colored_vertex(pos_from_sv(corners, s, v), color);
+                if row < N {
+                    triangles.add_triangle(
+                        vertex_idx(row, col),
+                        vertex_idx(row + 1, col),
+                        vertex_idx(row + 1, col + 1),
+                    );
+                    if col < row {
+                        triangles.add_triangle(
+                            vertex_idx(row, col),
+                            vertex_idx(row, col + 1),
+                            vertex_idx(row + 1, col + 1),
+                        );
+                    }
+                }
+            }
+        }
+        ui.painter().add(PaintCmd::triangles(triangles));
+    }
+
+    // Show where the picked color is:
+    let hue_color = HsvaGamma::new(hsva.h, 1.0, 1.0, 1.0).into();
+    ui.painter().add(PaintCmd::Circle {
+        center: center + 0.5 * (inner_radius + outer_radius) * direction(hsva.h),
+        radius: 0.5 * (outer_radius - inner_radius),
+        fill: hue_color,
+        stroke: Stroke::new(visuals.fg_stroke.width, contrast_color(hue_color)),
+    });
+    let picked_color = HsvaGamma { a: 1.0, ..*hsva }.into();
+    ui.painter().add(PaintCmd::Circle {
+        center: pos_from_sv(triangle(hsva.h), hsva.s, hsva.v),
+        radius: rect.width() / 24.0,
+        fill: picked_color,
+        stroke: Stroke::new(visuals.fg_stroke.width, contrast_color(picked_color)),
+    });
+
+    response
+}
+
+/// How the color picker popup lets you pick hue, saturation and value.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ColorPickerMode {
+    /// A hue-saturation square and a value-saturation square.
+    #[default]
+    Squares,
+    /// A hue ring around a saturation-value triangle.
+    Wheel,
+}
+
+/// `additive` is `None` if the color being edited can not be additive.
+fn color_picker_hsvag_2d(ui: &mut Ui, hsva: &mut HsvaGamma, additive: Option<&mut bool>) {
     ui.vertical(|ui| {
+        let mut mode = ui.memory().color_picker_mode;
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut mode, ColorPickerMode::Squares, "Squares");
+            ui.radio_value(&mut mode, ColorPickerMode::Wheel, "Wheel");
+        });
+        ui.memory().color_picker_mode = mode;
+
         let current_color_size = vec2(
             ui.style().spacing.slider_width,
             ui.style().spacing.interact_size.y * 2.0,
         );
 
-        show_color(ui, *hsva, current_color_size).on_hover_text("Current color");
+        let is_additive = additive.as_deref() == Some(&true);
+        show_color(
+            ui,
+            srgba_from_hsva(Hsva::from(*hsva), is_additive),
+            current_color_size,
+        )
+        .on_hover_text("Current color");
 
         show_color(ui, HsvaGamma { a: 1.0, ..*hsva }, current_color_size)
             .on_hover_text("Current color (opaque)");
 
+        let is_additive = match additive {
+            Some(additive) => {
+                let response = ui.checkbox(additive, "Additive").on_hover_text(
+                    "Add the color to what is behind it, rather than blending with it",
+                );
+                if response.clicked && !*additive {
+                    hsva.a = 1.0;
+                }
+                *additive
+            }
+            None => false,
+        };
+
         let opaque = HsvaGamma { a: 1.0, ..*hsva };
+        match mode {
+            ColorPickerMode::Squares => {
+                let HsvaGamma { h, s, v, .. } = hsva;
+                color_slider_2d(ui, h, s, |h, s| HsvaGamma::new(h, s, 1.0, 1.0).into())
+                    .on_hover_text("Hue - Saturation");
+                color_slider_2d(ui, v, s, |v, s| HsvaGamma { v, s, ..opaque }.into())
+                    .on_hover_text("Value - Saturation");
+            }
+            ColorPickerMode::Wheel => {
+                color_wheel(ui, hsva).on_hover_text("Hue (outside), Saturation - Value (inside)");
+            }
+        }
         let HsvaGamma { h, s, v, a } = hsva;
-        color_slider_2d(ui, h, s, |h, s| HsvaGamma::new(h, s, 1.0, 1.0).into())
-            .on_hover_text("Hue - Saturation");
-        color_slider_2d(ui, v, s, |v, s| HsvaGamma { v, s, ..opaque }.into())
-            .on_hover_text("Value - Saturation");
         color_slider_1d(ui, h, |h| HsvaGamma { h, ..opaque }.into()).on_hover_text("Hue");
         color_slider_1d(ui, s, |s| HsvaGamma { s, ..opaque }.into()).on_hover_text("Saturation");
         color_slider_1d(ui, v, |v| HsvaGamma { v, ..opaque }.into()).on_hover_text("Value");
-        color_slider_1d(ui, a, |a| HsvaGamma { a, ..opaque }.into()).on_hover_text("Alpha");
+        if !is_additive {
+            color_slider_1d(ui, a, |a| HsvaGamma { a, ..opaque }.into()).on_hover_text("Alpha");
+        }
     });
 }
 
-fn color_picker_hsva_2d(ui: &mut Ui, hsva: &mut Hsva) {
+fn color_picker_hsva_2d(ui: &mut Ui, hsva: &mut Hsva, mut additive: Option<&mut bool>) {
     let mut hsvag = HsvaGamma::from(*hsva);
-    color_picker_hsvag_2d(ui, &mut hsvag);
+    color_picker_hsvag_2d(ui, &mut hsvag, additive.as_deref_mut());
     *hsva = Hsva::from(hsvag);
+    color_text_ui(ui, hsva, additive.as_deref() == Some(&true));
+}
+
+/// Hex, RGB and HSV entry of a color.
+/// Additive colors have no alpha to enter.
+fn color_text_ui(ui: &mut Ui, hsva: &mut Hsva, additive: bool) {
+    let [r, g, b, a] = hsva.to_srgba_unmultiplied();
+
+    ui.horizontal(|ui| {
+        ui.label("Hex:");
+        let hex = if additive || a == 255 {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        };
+        let id = ui.make_position_id().with("hex");
+        let mut text = if ui.memory().has_kb_focus(id) {
+            ui.memory().temp_edit_string.take().unwrap_or(hex)
+        } else {
+            hex
+        };
+        ui.add(
+            TextEdit::new(&mut text)
+                .id(id)
+                .multiline(false)
+                .desired_width(ui.style().spacing.slider_width / 2.0)
+                .text_style(TextStyle::Monospace),
+        );
+        if ui.memory().has_kb_focus(id) {
+            if let Some(srgba) = parse_hex(&text) {
+                *hsva = Hsva::from_srgba_unmultiplied(srgba);
+            }
+            ui.memory().temp_edit_string = Some(text);
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("RGB:");
+        let mut rgb = [r, g, b];
+        for value in &mut rgb {
+            ui.add(DragValue::u8(value).range(0.0..=255.0));
+        }
+        if rgb != [r, g, b] {
+            *hsva = Hsva::from_srgba_unmultiplied([rgb[0], rgb[1], rgb[2], a]);
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("HSV:");
+        for value in &mut [&mut hsva.h, &mut hsva.s, &mut hsva.v] {
+            ui.add(DragValue::f32(value).range(0.0..=1.0).speed(0.005));
+        }
+    });
+}
+
+/// Parse "#RRGGBB" or "#RRGGBBAA" (with or without the "#") into unmultiplied sRGBA.
+fn parse_hex(text: &str) -> Option<[u8; 4]> {
+    let hex = text.trim().trim_start_matches('#');
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok();
+    let a = if hex.len() == 8 { byte(3)? } else { 255 };
+    Some([byte(0)?, byte(1)?, byte(2)?, a])
+}
+
+/// Premultiplied colors with zero alpha are added to what is behind them,
+/// rather than blended with it.
+fn is_additive(rgba_premultiplied: [f32; 4]) -> bool {
+    let [r, g, b, a] = rgba_premultiplied;
+    a == 0.0 && (r > 0.0 || g > 0.0 || b > 0.0)
+}
+
+/// The color with premultiplied alpha, and with zero alpha if it is additive.
+fn srgba_from_hsva(hsva: Hsva, additive: bool) -> Srgba {
+    if additive {
+        let [r, g, b, _] = hsva.to_srgba_unmultiplied();
+        Srgba([r, g, b, 0])
+    } else {
+        hsva.into()
+    }
 }
 
 pub fn color_edit_button_hsva(ui: &mut Ui, hsva: &mut Hsva) -> Response {
+    color_edit_button(ui, hsva, None)
+}
+
+/// `additive` is `None` if the color being edited can not be additive.
+fn color_edit_button(ui: &mut Ui, hsva: &mut Hsva, additive: Option<&mut bool>) -> Response {
     let pupup_id = ui.make_position_id().with("popup");
-    let button_response = color_button(ui, (*hsva).into()).on_hover_text("Click to edit color");
+    let color = srgba_from_hsva(*hsva, additive.as_deref() == Some(&true));
+    let button_response = color_button(ui, color).on_hover_text("Click to edit color");
+    color_picker_popup(ui, pupup_id, &button_response, hsva, additive);
+    button_response
+}
 
+/// Toggle a color picker popup when `button_response` is clicked, and show it if it is open.
+fn color_picker_popup(
+    ui: &mut Ui,
+    pupup_id: Id,
+    button_response: &Response,
+    hsva: &mut Hsva,
+    additive: Option<&mut bool>,
+) {
     if button_response.clicked {
         ui.memory().toggle_popup(pupup_id);
     }
@@ -228,10 +494,10 @@ pub fn color_edit_button_hsva(ui: &mut Ui, hsva: &mut Hsva) -> Response {
     if ui.memory().is_popup_open(pupup_id) {
         let area_response = Area::new(pupup_id)
             .order(Order::Foreground)
-            .default_pos(button_response.rect.max)
+            .default_pos(ui.to_screen_rect(button_response.rect).max)
             .show(ui.ctx(), |ui| {
                 Frame::popup(ui.style()).show(ui, |ui| {
-                    color_picker_hsva_2d(ui, hsva);
+                    color_picker_hsva_2d(ui, hsva, additive);
                 })
             });
 
@@ -242,8 +508,6 @@ pub fn color_edit_button_hsva(ui: &mut Ui, hsva: &mut Hsva) -> Response {
             }
         }
     }
-
-    button_response
 }
 
 /// Shows a button with the given color.
@@ -252,23 +516,223 @@ pub fn color_edit_button_srgba(ui: &mut Ui, srgba: &mut Srgba) -> Response {
     // To ensure we keep hue slider when `srgba` is grey we store the
     // full `Hsva` in a cache:
 
+    let mut additive = is_additive(Rgba::from(*srgba).0);
     let mut hsva = ui
         .ctx()
         .memory()
         .color_cache
         .get(srgba)
         .cloned()
-        .unwrap_or_else(|| Hsva::from(*srgba));
+        .unwrap_or_else(|| {
+            if additive {
+                let [r, g, b, _] = srgba.0;
+                Hsva::from_srgba_unmultiplied([r, g, b, 255])
+            } else {
+                Hsva::from(*srgba)
+            }
+        });
 
-    let response = color_edit_button_hsva(ui, &mut hsva);
+    let response = color_edit_button(ui, &mut hsva, Some(&mut additive));
 
-    *srgba = Srgba::from(hsva);
+    *srgba = srgba_from_hsva(hsva, additive);
 
     ui.ctx().memory().color_cache.set(*srgba, hsva);
 
     response
 }
 
+/// Shows a button with the given color, in linear RGBA space with premultiplied alpha.
+/// If the user clicks the button, a full color picker is shown.
+pub fn color_edit_button_rgba_premultiplied(ui: &mut Ui, rgba: &mut [f32; 4]) -> Response {
+    let mut additive = is_additive(*rgba);
+    let mut hsva = if additive {
+        let [r, g, b, _] = *rgba;
+        Hsva::from_rgba_unmultiplied([r, g, b, 1.0])
+    } else {
+        Hsva::from_rgba_premultiplied(*rgba)
+    };
+    let response = color_edit_button(ui, &mut hsva, Some(&mut additive));
+    *rgba = if additive {
+        let [r, g, b, _] = hsva.to_rgba_unmultiplied();
+        [r, g, b, 0.0]
+    } else {
+        hsva.to_rgba_premultiplied()
+    };
+    response
+}
+
+// ----------------------------------------------------------------------------
+
+/// A color at position `t` (0-1) in a `ColorGradient`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ColorStop {
+    pub t: f32,
+    pub color: Hsva,
+}
+
+/// A one-dimensional color gradient, interpolated in linear space.
+///
+/// Edit it with `gradient_edit`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ColorGradient {
+    /// Need not be sorted by `t`.
+    pub stops: Vec<ColorStop>,
+}
+
+impl ColorGradient {
+    pub fn two_colors(left: impl Into<Hsva>, right: impl Into<Hsva>) -> Self {
+        Self {
+            stops: vec![
+                ColorStop {
+                    t: 0.0,
+                    color: left.into(),
+                },
+                ColorStop {
+                    t: 1.0,
+                    color: right.into(),
+                },
+            ],
+        }
+    }
+
+    /// The color at `t` (0-1), linearly interpolated between the closest stops.
+    /// Before the first stop and after the last stop the color is constant.
+    pub fn sample(&self, t: f32) -> Rgba {
+        let by_t =
+            |a: &&ColorStop, b: &&ColorStop| a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal);
+        let below = self.stops.iter().filter(|stop| stop.t <= t).max_by(by_t);
+        let above = self.stops.iter().filter(|stop| stop.t > t).min_by(by_t);
+
+        match (below, above) {
+            (Some(below), Some(above)) => {
+                let t = remap(t, below.t..=above.t, 0.0..=1.0);
+                lerp(Rgba::from(below.color)..=Rgba::from(above.color), t)
+            }
+            (Some(stop), None) | (None, Some(stop)) => stop.color.into(),
+            (None, None) => Rgba::default(),
+        }
+    }
+}
+
+impl Default for ColorGradient {
+    fn default() -> Self {
+        Self::two_colors(Srgba::black_alpha(0), color::WHITE)
+    }
+}
+
+/// Edit a `ColorGradient`.
+///
+/// Click the gradient to add a color stop, drag a stop to move it,
+/// drag it away from the gradient to remove it,
+/// and click it to edit its color.
+pub fn gradient_edit(ui: &mut Ui, gradient: &mut ColorGradient) -> Response {
+    let id = ui.make_position_id();
+    let strip_size = vec2(
+        ui.style().spacing.slider_width,
+        ui.style().spacing.interact_size.y * 2.0,
+    );
+    let handle_size = 0.75 * ui.style().spacing.interact_size.y;
+    let rect = ui.allocate_space(strip_size + vec2(0.0, handle_size));
+    let strip_rect = Rect::from_min_size(rect.min, strip_size);
+
+    let strip_response = ui.interact(strip_rect, id, Sense::click());
+    if strip_response.clicked {
+        if let Some(mouse_pos) = ui.input().mouse.pos {
+            let t = remap_clamp(mouse_pos.x, strip_rect.x_range(), 0.0..=1.0);
+            let color = Hsva::from(gradient.sample(t));
+            gradient.stops.push(ColorStop { t, color });
+        }
+    }
+
+    // Paint the gradient, with a vertex at every stop:
+    background_checkers(ui.painter(), strip_rect);
+    {
+        let mut ts: Vec<f32> = gradient.stops.iter().map(|stop| stop.t).collect();
+        ts.push(0.0);
+        ts.push(1.0);
+        ts.retain(|t| (0.0..=1.0).contains(t));
+        ts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let mut triangles = Triangles::default();
+        for (i, &t) in ts.iter().enumerate() {
+            let color = Srgba::from(gradient.sample(t));
+            let x = lerp(strip_rect.x_range(), t);
+            triangles.colored_vertex(pos2(x, strip_rect.top()), color);
+            triangles.colored_vertex(pos2(x, strip_rect.bottom()), color);
+            if i + 1 < ts.len() {
+                let i = i as u32;
+                triangles.add_triangle(2 * i, 2 * i + 1, 2 * i + 2);
+                triangles.add_triangle(2 * i + 1, 2 * i + 2, 2 * i + 3);
+            }
+        }
+        ui.painter().add(PaintCmd::triangles(triangles));
+    }
+    let visuals = ui.style().interact(&strip_response);
+    ui.painter().rect_stroke(strip_rect, 0.0, visuals.bg_stroke);
+
+    // The stops, as handles below the gradient.
+    // They are never re-sorted, so a stop keeps its id and popup when dragged past another:
+    let mut removed = None;
+    for (i, stop) in gradient.stops.iter_mut().enumerate() {
+        let x = lerp(strip_rect.x_range(), stop.t);
+        let handle_rect = Rect::from_min_max(
+            pos2(x - 0.5 * handle_size, strip_rect.bottom()),
+            pos2(x + 0.5 * handle_size, rect.bottom()),
+        );
+        let stop_id = id.with(i);
+        let response = ui.interact(handle_rect, stop_id, Sense::click_and_drag());
+
+        let mut dragged_off = false;
+        if response.active && !response.clicked {
+            let delta_x = ui.input().mouse.delta.x;
+            if delta_x != 0.0 {
+                stop.t = clamp(stop.t + delta_x / strip_rect.width(), 0.0..=1.0);
+            }
+            if let Some(mouse_pos) = ui.input().mouse.pos {
+                dragged_off = !rect.expand(strip_size.y).contains(mouse_pos);
+                if dragged_off && ui.input().mouse.released {
+                    removed = Some(i);
+                }
+            }
+        }
+
+        let x = lerp(strip_rect.x_range(), stop.t);
+        let visuals = ui.style().interact(&response);
+        let mut fill = Srgba::from(stop.color).to_opaque();
+        if dragged_off {
+            fill = Rgba::from(fill).multiply(0.25).into(); // about to be removed
+        }
+        ui.painter().add(PaintCmd::polygon(
+            vec![
+                pos2(x - 0.5 * handle_size, rect.bottom()),
+                pos2(x + 0.5 * handle_size, rect.bottom()),
+                pos2(x, strip_rect.bottom()),
+            ],
+            fill,
+            Stroke::new(visuals.fg_stroke.width, contrast_color(fill)),
+        ));
+
+        let popup_id = stop_id.with("popup");
+        let response = response.on_hover_text("Click to edit color, drag away to remove");
+        color_picker_popup(ui, popup_id, &response, &mut stop.color, None);
+    }
+
+    if let Some(i) = removed {
+        if gradient.stops.len() > 1 {
+            // The stops after the removed one move down, and would take over each other's popup:
+            let len = gradient.stops.len();
+            if (i..len).any(|j| ui.memory().is_popup_open(id.with(j).with("popup"))) {
+                ui.memory().close_popup();
+            }
+            gradient.stops.remove(i);
+        }
+    }
+
+    strip_response
+}
+
 // ----------------------------------------------------------------------------
 
 /// Like Hsva but with the `v` (value/brightness) being gamma corrected
@@ -350,3 +814,171 @@ fn srgb_from_linear(l: f32) -> f32 {
         1.055 * l.powf(1.0 / 2.4) - 0.055
     }
 }
+
+#[test]
+fn test_gradient_sample() {
+    let mut gradient = ColorGradient::two_colors(color::BLACK, color::WHITE);
+    gradient.stops.push(ColorStop {
+        t: 0.25,
+        color: Hsva::new(0.0, 0.0, 1.0, 1.0),
+    });
+    assert_eq!(gradient.sample(-1.0), Rgba::new(0.0, 0.0, 0.0, 1.0));
+    assert_eq!(gradient.sample(0.125), Rgba::new(0.5, 0.5, 0.5, 1.0));
+    assert_eq!(gradient.sample(0.5), Rgba::new(1.0, 1.0, 1.0, 1.0));
+    assert_eq!(gradient.sample(2.0), Rgba::new(1.0, 1.0, 1.0, 1.0));
+}
+
+/// Show a `gradient_edit` for one frame, and return its id and the rectangle of its gradient strip.
+#[cfg(test)]
+fn run_gradient_frame(
+    ctx: &mut std::sync::Arc<Context>,
+    gradient: &mut ColorGradient,
+    mouse: (Pos2, bool),
+) -> (Id, Rect) {
+    let (mouse_pos, mouse_down) = mouse;
+    ctx.begin_frame(RawInput {
+        screen_size: vec2(800.0, 600.0),
+        mouse_pos: Some(mouse_pos),
+        mouse_down,
+        ..Default::default()
+    });
+    let mut id_and_rect = (Id::new(""), Rect::nothing());
+    Area::new("gradient")
+        .fixed_pos(pos2(100.0, 100.0))
+        .show(ctx, |ui| {
+            let id = ui.make_position_id();
+            id_and_rect = (id, gradient_edit(ui, gradient).rect);
+        });
+    let _ = ctx.end_frame();
+    id_and_rect
+}
+
+#[test]
+fn test_gradient_edit_add_stop() {
+    let mut ctx = Context::new();
+    let mut gradient = ColorGradient::two_colors(color::BLACK, color::WHITE);
+    let (_, strip_rect) = run_gradient_frame(&mut ctx, &mut gradient, (pos2(0.0, 0.0), false));
+
+    let middle = strip_rect.center();
+    for &mouse_down in &[false, true, false] {
+        run_gradient_frame(&mut ctx, &mut gradient, (middle, mouse_down));
+    }
+    assert_eq!(gradient.stops.len(), 3);
+    assert_eq!((gradient.stops[0].t, gradient.stops[1].t), (0.0, 1.0));
+    assert_eq!(gradient.stops[2].t, 0.5);
+    assert_eq!(
+        Rgba::from(gradient.stops[2].color),
+        Rgba::new(0.5, 0.5, 0.5, 1.0)
+    );
+}
+
+#[test]
+fn test_gradient_edit_drag_stop_past_neighbor() {
+    let mut ctx = Context::new();
+    let mut gradient = ColorGradient::two_colors(color::BLACK, color::WHITE);
+    let red = Hsva::new(0.0, 1.0, 1.0, 1.0);
+    gradient.stops.push(ColorStop { t: 0.5, color: red });
+    let (id, strip_rect) = run_gradient_frame(&mut ctx, &mut gradient, (pos2(0.0, 0.0), false));
+
+    // Drag the black stop past the red one, in two steps:
+    let handle = |t| pos2(lerp(strip_rect.x_range(), t), strip_rect.bottom() + 2.0);
+    for &mouse in &[
+        (handle(0.0), false),
+        (handle(0.0), true),
+        (handle(0.625), true),
+        (handle(0.75), true),
+        (handle(0.75), false),
+    ] {
+        run_gradient_frame(&mut ctx, &mut gradient, mouse);
+        assert_eq!(gradient.stops[0].color, Hsva::from(color::BLACK));
+        assert_eq!(gradient.stops[2].color, red);
+    }
+    assert!((gradient.stops[0].t - 0.75).abs() < 1e-3);
+    assert_eq!(gradient.stops[2].t, 0.5);
+
+    // Clicking the stop opens its own popup:
+    for &mouse_down in &[false, true, false] {
+        run_gradient_frame(&mut ctx, &mut gradient, (handle(0.75), mouse_down));
+    }
+    assert!(ctx.memory().is_popup_open(id.with(0).with("popup")));
+}
+
+#[test]
+fn test_gradient_edit_remove_stop() {
+    let mut ctx = Context::new();
+    let mut gradient = ColorGradient::two_colors(color::BLACK, color::WHITE);
+    let red = Hsva::new(0.0, 1.0, 1.0, 1.0);
+    gradient.stops.push(ColorStop { t: 0.5, color: red });
+    let (_, strip_rect) = run_gradient_frame(&mut ctx, &mut gradient, (pos2(0.0, 0.0), false));
+
+    // Drag the black stop away from the gradient:
+    let handle = pos2(strip_rect.left(), strip_rect.bottom() + 2.0);
+    let away = handle + vec2(0.0, 4.0 * strip_rect.height());
+    for &mouse in &[(handle, false), (handle, true), (away, true)] {
+        run_gradient_frame(&mut ctx, &mut gradient, mouse);
+        assert_eq!(gradient.stops.len(), 3);
+    }
+    run_gradient_frame(&mut ctx, &mut gradient, (away, false));
+    let colors: Vec<Hsva> = gradient.stops.iter().map(|stop| stop.color).collect();
+    assert_eq!(colors, vec![Hsva::from(color::WHITE), red]);
+}
+
+#[test]
+fn test_parse_hex() {
+    assert_eq!(parse_hex("#ff8000"), Some([255, 128, 0, 255]));
+    assert_eq!(parse_hex("FF800040"), Some([255, 128, 0, 64]));
+    assert_eq!(parse_hex("#ff80"), None);
+    assert_eq!(parse_hex("#gg8000"), None);
+}
+
+#[test]
+fn test_additive_color_roundtrip() {
+    let additive = [200, 100, 50, 0];
+    let additive_rgba = Rgba::from(Srgba(additive)).0;
+    let mut srgba_premultiplied = additive;
+    let mut srgba_unmultiplied = additive;
+    let mut rgba_premultiplied = additive_rgba;
+    let mut rgba_unmultiplied = additive_rgba;
+
+    let mut ctx = Context::new();
+    let mut raw_input = RawInput {
+        screen_size: vec2(800.0, 600.0),
+        ..Default::default()
+    };
+    let mut buttons: Vec<(Id, Rect)> = vec![];
+    // Lay out, then click each button in turn to open its color picker:
+    for button in 0..=4 {
+        for &mouse_down in &[false, true, false] {
+            raw_input.mouse_pos = buttons.get(button).map(|(_, rect)| rect.center());
+            raw_input.mouse_down = mouse_down;
+            ctx.begin_frame(raw_input.clone());
+            Area::new("colors")
+                .fixed_pos(pos2(10.0, 10.0))
+                .show(&ctx, |ui| {
+                    let popup_id = |ui: &Ui| ui.make_position_id().with("popup");
+                    buttons.clear();
+                    let id = popup_id(ui);
+                    let response =
+                        ui.color_edit_button_srgba_premultiplied(&mut srgba_premultiplied);
+                    buttons.push((id, response.rect));
+                    let id = popup_id(ui);
+                    let response = ui.color_edit_button_srgba_unmultiplied(&mut srgba_unmultiplied);
+                    buttons.push((id, response.rect));
+                    let id = popup_id(ui);
+                    let response = ui.color_edit_button_rgba_premultiplied(&mut rgba_premultiplied);
+                    buttons.push((id, response.rect));
+                    let id = popup_id(ui);
+                    let response = ui.color_edit_button_rgba_unmultiplied(&mut rgba_unmultiplied);
+                    buttons.push((id, response.rect));
+                });
+            let _ = ctx.end_frame();
+            assert_eq!(srgba_premultiplied, additive);
+            assert_eq!(srgba_unmultiplied, additive);
+            assert_eq!(Srgba::from(Rgba(rgba_premultiplied)).0, additive);
+            assert_eq!(Srgba::from(Rgba(rgba_unmultiplied)).0, additive);
+        }
+        if let Some(&(popup_id, _)) = buttons.get(button) {
+            assert!(ctx.memory().is_popup_open(popup_id));
+        }
+    }
+}
@@ -0,0 +1,549 @@
+use std::ops::RangeInclusive;
+
+use crate::{math::NumExt, paint::*, *};
+
+/// How to get from one key of a `Curve` to the next.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Interpolation {
+    /// A straight line to the next key.
+    Linear,
+    /// A smooth cubic Hermite spline, using the `out_tangent` of this key
+    /// and the `in_tangent` of the next key.
+    CubicHermite,
+}
+
+/// A key (control point) of a `Curve`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct CurveKey {
+    pub t: f32,
+    pub value: f32,
+    /// Slope (value per t) of the curve coming into this key.
+    pub in_tangent: f32,
+    /// Slope (value per t) of the curve leaving this key.
+    pub out_tangent: f32,
+    /// How to interpolate from this key to the next one.
+    pub interpolation: Interpolation,
+}
+
+impl CurveKey {
+    pub fn linear(t: f32, value: f32) -> Self {
+        Self {
+            t,
+            value,
+            in_tangent: 0.0,
+            out_tangent: 0.0,
+            interpolation: Interpolation::Linear,
+        }
+    }
+
+    pub fn smooth(t: f32, value: f32, tangent: f32) -> Self {
+        Self {
+            t,
+            value,
+            in_tangent: tangent,
+            out_tangent: tangent,
+            interpolation: Interpolation::CubicHermite,
+        }
+    }
+}
+
+/// A piecewise curve, e.g. for easing an animation.
+///
+/// Edit it with `CurveEdit`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Curve {
+    /// Should be sorted by `t`.
+    pub keys: Vec<CurveKey>,
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Self::ease_in_out()
+    }
+}
+
+impl Curve {
+    /// A straight line from (0, 0) to (1, 1).
+    pub fn linear() -> Self {
+        Self {
+            keys: vec![CurveKey::linear(0.0, 0.0), CurveKey::linear(1.0, 1.0)],
+        }
+    }
+
+    /// A smooth s-curve from (0, 0) to (1, 1).
+    pub fn ease_in_out() -> Self {
+        Self {
+            keys: vec![
+                CurveKey::smooth(0.0, 0.0, 0.0),
+                CurveKey::smooth(1.0, 1.0, 0.0),
+            ],
+        }
+    }
+
+    /// The value of the curve at `t`.
+    /// Before the first key and after the last key the value is constant.
+    pub fn evaluate(&self, t: f32) -> f32 {
+        let next = self.keys.partition_point(|key| key.t <= t);
+        if self.keys.is_empty() {
+            0.0
+        } else if next == 0 {
+            self.keys[0].value
+        } else if next == self.keys.len() {
+            self.keys[next - 1].value
+        } else {
+            evaluate_segment(&self.keys[next - 1], &self.keys[next], t)
+        }
+    }
+}
+
+fn evaluate_segment(k0: &CurveKey, k1: &CurveKey, t: f32) -> f32 {
+    let dt = k1.t - k0.t;
+    if dt <= 0.0 {
+        return k1.value;
+    }
+    let s = (t - k0.t) / dt;
+    match k0.interpolation {
+        Interpolation::Linear => lerp(k0.value..=k1.value, s),
+        Interpolation::CubicHermite => {
+            let s2 = s * s;
+            let s3 = s2 * s;
+            let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+            let h10 = s3 - 2.0 * s2 + s;
+            let h01 = -2.0 * s3 + 3.0 * s2;
+            let h11 = s3 - s2;
+            h00 * k0.value + h10 * dt * k0.out_tangent + h01 * k1.value + h11 * dt * k1.in_tangent
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Radius of the key handles, in points.
+const KEY_RADIUS: f32 = 4.0;
+
+/// Distance from a key to its tangent handles, in points.
+const TANGENT_LENGTH: f32 = 30.0;
+
+/// Keys snap to "nice" values within this many points of the mouse.
+const SNAP_RADIUS: f32 = 3.0;
+
+/// Edit a `Curve` in a framed rectangle.
+///
+/// Drag the keys and their tangent handles to change the curve.
+/// Double-click to add a key, and press delete to remove the selected key.
+/// Use `key_editor` to also edit the selected key numerically.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// let mut curve = egui::Curve::ease_in_out();
+/// ui.add(egui::CurveEdit::new(&mut curve));
+/// let eased = curve.evaluate(0.25);
+/// ```
+#[derive(Debug)]
+pub struct CurveEdit<'a> {
+    curve: &'a mut Curve,
+    id_source: Option<Id>,
+    t_range: RangeInclusive<f32>,
+    value_range: RangeInclusive<f32>,
+    desired_size: Option<Vec2>,
+    key_editor: bool,
+}
+
+impl<'a> CurveEdit<'a> {
+    pub fn new(curve: &'a mut Curve) -> Self {
+        Self {
+            curve,
+            id_source: None,
+            t_range: 0.0..=1.0,
+            value_range: 0.0..=1.0,
+            desired_size: None,
+            key_editor: false,
+        }
+    }
+
+    pub fn id_source(mut self, id_source: impl std::hash::Hash) -> Self {
+        self.id_source = Some(Id::new(id_source));
+        self
+    }
+
+    /// The range of `t` shown. Default: `0.0..=1.0`.
+    pub fn t_range(mut self, t_range: RangeInclusive<f32>) -> Self {
+        self.t_range = t_range;
+        self
+    }
+
+    /// The range of values shown. Default: `0.0..=1.0`.
+    pub fn value_range(mut self, value_range: RangeInclusive<f32>) -> Self {
+        self.value_range = value_range;
+        self
+    }
+
+    /// Default: two times `spacing.slider_width` wide, and `spacing.slider_width` high.
+    pub fn desired_size(mut self, desired_size: Vec2) -> Self {
+        self.desired_size = Some(desired_size);
+        self
+    }
+
+    /// Show `DragValue`s and radio buttons for the selected key below the curve,
+    /// as part of this widget. Default: `false`.
+    pub fn key_editor(mut self, key_editor: bool) -> Self {
+        self.key_editor = key_editor;
+        self
+    }
+}
+
+impl<'a> Widget for CurveEdit<'a> {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        if self.key_editor {
+            ui.vertical(|ui| {
+                let (response, selected) = self.curve_ui(ui);
+                match selected {
+                    Some(i) => response | self.key_ui(ui, i),
+                    None => response,
+                }
+            })
+            .0
+        } else {
+            self.curve_ui(ui).0
+        }
+    }
+}
+
+impl<'a> CurveEdit<'a> {
+    /// Just the curve. Also returns the selected key.
+    fn curve_ui(&mut self, ui: &mut Ui) -> (Response, Option<usize>) {
+        let curve = &mut *self.curve;
+        let id_source = self.id_source;
+        let t_range = self.t_range.clone();
+        let value_range = self.value_range.clone();
+        let desired_size = self.desired_size;
+
+        let id = match id_source {
+            Some(id_source) => ui.make_persistent_id(id_source),
+            None => ui.make_position_id(),
+        };
+        let slider_width = ui.style().spacing.slider_width;
+        let desired_size = desired_size.unwrap_or_else(|| vec2(2.0 * slider_width, slider_width));
+        let rect = ui.allocate_space(desired_size);
+
+        let to_screen = |t: f32, value: f32| {
+            pos2(
+                remap(t, t_range.clone(), rect.x_range()),
+                remap(value, value_range.clone(), rect.bottom()..=rect.top()),
+            )
+        };
+        let t_from_x = |x: f32| remap(x, rect.x_range(), t_range.clone());
+        let value_from_y = |y: f32| remap(y, rect.bottom()..=rect.top(), value_range.clone());
+        let snap = |from: f32, to: f32| best_in_range_f32(from.min(to), from.max(to));
+
+        // Screen-space direction of the given slope:
+        let points_per_t = rect.width() / (t_range.end() - t_range.start());
+        let points_per_value = rect.height() / (value_range.end() - value_range.start());
+        let tangent_dir = |slope: f32| {
+            vec2(points_per_t, -slope * points_per_value).normalized() * TANGENT_LENGTH
+        };
+        let slope_from_dir = |dir: Vec2| (-dir.y / points_per_value) / (dir.x / points_per_t);
+
+        let mut selected = ui.memory().curve_edit.get(&id).copied();
+        let mouse_pos = ui.input().mouse.pos;
+        let mut interacted = false;
+
+        // Interact with the handles before the background, so they get the clicks:
+        let num_keys = curve.keys.len();
+        for i in 0..num_keys {
+            let key = curve.keys[i];
+            let key_pos = to_screen(key.t, key.value);
+            let handle_rect =
+                |pos: Pos2| Rect::from_center_size(pos, Vec2::splat(3.0 * KEY_RADIUS));
+
+            if selected == Some(i) {
+                let has_in_tangent =
+                    i > 0 && curve.keys[i - 1].interpolation == Interpolation::CubicHermite;
+                let has_out_tangent =
+                    i + 1 < num_keys && key.interpolation == Interpolation::CubicHermite;
+
+                if has_in_tangent {
+                    let handle_pos = key_pos - tangent_dir(key.in_tangent);
+                    let response =
+                        ui.interact(handle_rect(handle_pos), id.with(("in", i)), Sense::drag());
+                    if let (true, Some(mouse_pos)) = (response.active, mouse_pos) {
+                        let dir = key_pos - mouse_pos;
+                        curve.keys[i].in_tangent = slope_from_dir(vec2(dir.x.at_least(1.0), dir.y));
+                        interacted = true;
+                    }
+                }
+                if has_out_tangent {
+                    let handle_pos = key_pos + tangent_dir(key.out_tangent);
+                    let response =
+                        ui.interact(handle_rect(handle_pos), id.with(("out", i)), Sense::drag());
+                    if let (true, Some(mouse_pos)) = (response.active, mouse_pos) {
+                        let dir = mouse_pos - key_pos;
+                        curve.keys[i].out_tangent =
+                            slope_from_dir(vec2(dir.x.at_least(1.0), dir.y));
+                        interacted = true;
+                    }
+                }
+            }
+
+            let response = ui.interact(
+                handle_rect(key_pos),
+                id.with(("key", i)),
+                Sense::click_and_drag(),
+            );
+            if response.active {
+                selected = Some(i);
+                interacted = true;
+                if let Some(mouse_pos) = mouse_pos {
+                    if mouse_pos != ui.input().mouse.press_origin.unwrap_or(mouse_pos) {
+                        let t = snap(
+                            t_from_x(mouse_pos.x - SNAP_RADIUS),
+                            t_from_x(mouse_pos.x + SNAP_RADIUS),
+                        );
+                        let value = snap(
+                            value_from_y(mouse_pos.y - SNAP_RADIUS),
+                            value_from_y(mouse_pos.y + SNAP_RADIUS),
+                        );
+                        curve.keys[i].t = clamp(t, t_bounds(&curve.keys, i, t_range.clone()));
+                        curve.keys[i].value = clamp(value, ordered(value_range.clone()));
+                    }
+                }
+            }
+        }
+
+        let response = ui.interact(rect, id, Sense::click());
+        if response.clicked {
+            selected = None;
+            interacted = true;
+        }
+        if response.double_clicked {
+            if let Some(mouse_pos) = mouse_pos {
+                let t = t_from_x(mouse_pos.x);
+                let next = curve.keys.partition_point(|key| key.t <= t);
+                let value = value_from_y(mouse_pos.y);
+                let interpolation = if next > 0 {
+                    curve.keys[next - 1].interpolation
+                } else {
+                    Interpolation::CubicHermite
+                };
+                let slope = (curve.evaluate(t + 1e-3) - curve.evaluate(t - 1e-3)) / 2e-3;
+                curve.keys.insert(
+                    next,
+                    CurveKey {
+                        interpolation,
+                        ..CurveKey::smooth(t, value, slope)
+                    },
+                );
+                selected = Some(next);
+            }
+        }
+
+        if interacted {
+            ui.memory().request_kb_focus(id);
+        } else if ui.input().mouse.click {
+            ui.memory().surrender_kb_focus(id);
+        }
+        if let Some(i) = selected {
+            let delete = ui.memory().has_kb_focus(id) && ui.input().key_pressed(Key::Delete);
+            if delete && i < curve.keys.len() {
+                curve.keys.remove(i);
+                selected = None;
+            }
+        }
+        let selected = selected.filter(|&i| i < curve.keys.len());
+
+        // Paint it:
+        let visuals = ui.style().interact(&response);
+        let painter = ui.painter().sub_region(rect);
+        painter.rect(
+            rect,
+            visuals.corner_radius,
+            ui.style().visuals.dark_bg_color,
+            visuals.bg_stroke,
+        );
+
+        let grid_stroke = ui.style().visuals.widgets.noninteractive.bg_stroke;
+        for t in grid_lines(t_range.clone(), rect.width()) {
+            let x = to_screen(t, 0.0).x;
+            painter.line_segment([pos2(x, rect.top()), pos2(x, rect.bottom())], grid_stroke);
+        }
+        for value in grid_lines(value_range.clone(), rect.height()) {
+            let y = to_screen(0.0, value).y;
+            painter.line_segment([pos2(rect.left(), y), pos2(rect.right(), y)], grid_stroke);
+        }
+
+        let points: Vec<Pos2> = (0..=rect.width().ceil() as usize / 2)
+            .map(|i| {
+                let x = (rect.left() + 2.0 * i as f32).at_most(rect.right());
+                let t = t_from_x(x);
+                to_screen(t, curve.evaluate(t))
+            })
+            .collect();
+        painter.add(PaintCmd::line(
+            points,
+            ui.style().visuals.widgets.hovered.fg_stroke,
+        ));
+
+        let key_fill = ui.style().visuals.widgets.inactive.fg_fill;
+        let selected_fill = ui.style().visuals.widgets.active.fg_fill;
+        let key_stroke = ui.style().visuals.widgets.inactive.fg_stroke;
+        for (i, key) in curve.keys.iter().enumerate() {
+            let key_pos = to_screen(key.t, key.value);
+            if selected == Some(i) {
+                let mut tangents = vec![];
+                if i > 0 && curve.keys[i - 1].interpolation == Interpolation::CubicHermite {
+                    tangents.push(key_pos - tangent_dir(key.in_tangent));
+                }
+                if i + 1 < curve.keys.len() && key.interpolation == Interpolation::CubicHermite {
+                    tangents.push(key_pos + tangent_dir(key.out_tangent));
+                }
+                for handle_pos in tangents {
+                    painter.line_segment([key_pos, handle_pos], key_stroke);
+                    painter.circle(handle_pos, 0.75 * KEY_RADIUS, key_fill, key_stroke);
+                }
+                painter.circle(key_pos, KEY_RADIUS, selected_fill, key_stroke);
+            } else {
+                painter.circle(key_pos, KEY_RADIUS, key_fill, key_stroke);
+            }
+        }
+
+        match selected {
+            Some(i) => ui.memory().curve_edit.insert(id, i),
+            None => ui.memory().curve_edit.remove(&id),
+        };
+
+        (response, selected)
+    }
+
+    /// `DragValue`s and radio buttons for the selected key.
+    fn key_ui(&mut self, ui: &mut Ui, i: usize) -> Response {
+        let t_bounds = t_bounds(&self.curve.keys, i, self.t_range.clone());
+        let key = &mut self.curve.keys[i];
+        let (response, row_response) = ui.horizontal(|ui| {
+            ui.label("t:");
+            let response = ui.add(DragValue::f32(&mut key.t).speed(0.01));
+            ui.label("value:");
+            response
+                | ui.add(DragValue::f32(&mut key.value).speed(0.01))
+                | ui.radio_value(&mut key.interpolation, Interpolation::Linear, "Linear")
+                | ui.radio_value(&mut key.interpolation, Interpolation::CubicHermite, "Cubic")
+        });
+        key.t = clamp(key.t, t_bounds);
+        row_response | response
+    }
+}
+
+/// Where key `i` can be moved without passing its neighbors.
+fn t_bounds(keys: &[CurveKey], i: usize, t_range: RangeInclusive<f32>) -> RangeInclusive<f32> {
+    let min_t = if i > 0 {
+        keys[i - 1].t
+    } else {
+        *t_range.start()
+    };
+    let max_t = if i + 1 < keys.len() {
+        keys[i + 1].t
+    } else {
+        *t_range.end()
+    };
+    ordered(min_t..=max_t)
+}
+
+/// `clamp` wants the smaller bound first, but ranges can go from high to low.
+fn ordered(range: RangeInclusive<f32>) -> RangeInclusive<f32> {
+    range.start().min(*range.end())..=range.start().max(*range.end())
+}
+
+fn best_in_range_f32(min: f32, max: f32) -> f32 {
+    crate::math::smart_aim::best_in_range_f64(min as f64, max as f64) as f32
+}
+
+/// Values in the range at evenly spaced round numbers, at least `MIN_SPACING` points apart.
+fn grid_lines(range: RangeInclusive<f32>, points: f32) -> Vec<f32> {
+    const MIN_SPACING: f32 = 20.0;
+    let (min, max) = (
+        range.start().min(*range.end()),
+        range.start().max(*range.end()),
+    );
+    let min_step = (max - min) * MIN_SPACING / points;
+    if !(min_step > 0.0 && min_step.is_finite()) {
+        return vec![];
+    }
+    let step = 10.0_f32.powf(min_step.log10().ceil());
+    let step = if step / 5.0 >= min_step {
+        step / 5.0
+    } else if step / 2.0 >= min_step {
+        step / 2.0
+    } else {
+        step
+    };
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f32 * step).collect()
+}
+
+#[test]
+fn test_curve_evaluate() {
+    let curve = Curve::ease_in_out();
+    assert_eq!(curve.evaluate(-1.0), 0.0);
+    assert_eq!(curve.evaluate(0.5), 0.5);
+    assert!(curve.evaluate(0.25) < 0.25);
+    assert_eq!(curve.evaluate(2.0), 1.0);
+
+    let curve = Curve {
+        keys: vec![
+            CurveKey::linear(0.0, 0.0),
+            CurveKey::linear(0.5, 1.0),
+            CurveKey::linear(1.0, 0.0),
+        ],
+    };
+    assert_eq!(curve.evaluate(0.25), 0.5);
+    assert_eq!(curve.evaluate(0.5), 1.0);
+    assert_eq!(curve.evaluate(0.75), 0.5);
+
+    assert_eq!(grid_lines(0.0..=1.0, 100.0).len(), 6); // 0.0, 0.2, …, 1.0
+    assert_eq!(grid_lines(-10.0..=10.0, 100.0).len(), 5); // -10, -5, 0, 5, 10
+}
+
+#[test]
+fn test_drag_key_in_flipped_range() {
+    let mut ctx = Context::new();
+    let mut raw_input = RawInput {
+        screen_size: vec2(400.0, 300.0),
+        ..Default::default()
+    };
+    let mut curve = Curve::linear();
+    let mut curve_rect = Rect::nothing();
+    let mut response_rect = Rect::nothing();
+    // Lay out, hover the first key, press, drag it to the center:
+    for &(at_center, mouse_down) in &[(false, false), (false, false), (false, true), (true, true)] {
+        // With the value range flipped, the first key is in the top left corner:
+        raw_input.mouse_pos = Some(if at_center {
+            curve_rect.center()
+        } else {
+            curve_rect.left_top()
+        });
+        raw_input.mouse_down = mouse_down;
+        ctx.begin_frame(raw_input.clone());
+        Area::new("curve")
+            .fixed_pos(pos2(10.0, 10.0))
+            .show(&ctx, |ui| {
+                let desired_size = vec2(100.0, 50.0);
+                response_rect = ui
+                    .add(
+                        CurveEdit::new(&mut curve)
+                            .value_range(1.0..=0.0)
+                            .desired_size(desired_size)
+                            .key_editor(true),
+                    )
+                    .rect;
+                curve_rect = Rect::from_min_size(response_rect.min, desired_size);
+            });
+        let _ = ctx.end_frame();
+    }
+    assert!((curve.keys[0].t - 0.5).abs() < 0.05);
+    assert!((curve.keys[0].value - 0.5).abs() < 0.05);
+    // The key editor is part of the widget:
+    assert!(response_rect.height() > curve_rect.height());
+}
@@ -0,0 +1,370 @@
+use crate::{containers::combo_box::combo_box_impl, paint::*, *};
+
+/// A date in the (proleptic) Gregorian calendar.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Date {
+    pub year: i32,
+    /// 1-12
+    pub month: u32,
+    /// 1-31
+    pub day: u32,
+}
+
+impl Date {
+    /// The month and day are clamped to valid values.
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        let month = month.clamp(1, 12);
+        let day = day.clamp(1, days_in_month(year, month));
+        Self { year, month, day }
+    }
+
+    /// Days since 1970-01-01, negative for earlier dates.
+    pub fn days_since_epoch(&self) -> i64 {
+        // See http://howardhinnant.github.io/date_algorithms.html
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The inverse of `days_since_epoch`.
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (year_of_era + era * 400) as i32 + (month <= 2) as i32;
+        Self { year, month, day }
+    }
+
+    /// 0 = Monday, 6 = Sunday.
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday:
+        (self.days_since_epoch() + 3).rem_euclid(7) as u32
+    }
+
+    /// The ISO 8601 week number, 1-53.
+    /// Weeks start on Monday, and the first week of the year is the one with its first Thursday.
+    pub fn iso_week(&self) -> u32 {
+        let thursday = self.days_since_epoch() - self.weekday() as i64 + 3;
+        let year = Self::from_days_since_epoch(thursday).year;
+        let first_day_of_year = Self::new(year, 1, 1).days_since_epoch();
+        ((thursday - first_day_of_year) / 7 + 1) as u32
+    }
+
+    /// Move this many months forward (or backward, if negative).
+    /// The day is clamped to the length of the new month.
+    pub fn add_months(&self, months: i32) -> Self {
+        let month_idx = self.year * 12 + self.month as i32 - 1 + months;
+        Self::new(
+            month_idx.div_euclid(12),
+            month_idx.rem_euclid(12) as u32 + 1,
+            self.day,
+        )
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days_since_epoch(self.days_since_epoch() + days)
+    }
+}
+
+impl Default for Date {
+    fn default() -> Self {
+        Self::new(1970, 1, 1)
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// `month` is 1-12.
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// A time of day, in hours (0-23) and minutes (0-59).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TimeOfDay {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl std::fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// State of a `DatePicker` while its popup is open.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct State {
+    /// The month we are showing, which can be different from the one of the date.
+    /// The day is ignored.
+    shown_month: Date,
+}
+
+/// A button showing a date (and maybe a time of day),
+/// which opens a calendar to pick another one.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// let mut date = egui::Date::new(2020, 11, 23);
+/// let mut time = egui::TimeOfDay { hour: 14, minute: 30 };
+/// ui.add(egui::DatePicker::new("meeting", &mut date).time(&mut time));
+/// ```
+#[derive(Debug)]
+pub struct DatePicker<'a> {
+    id_source: Id,
+    date: &'a mut Date,
+    time: Option<&'a mut TimeOfDay>,
+    today: Option<Date>,
+}
+
+impl<'a> DatePicker<'a> {
+    pub fn new(id_source: impl std::hash::Hash, date: &'a mut Date) -> Self {
+        Self {
+            id_source: Id::new(id_source),
+            date,
+            time: None,
+            today: None,
+        }
+    }
+
+    /// Also edit the time of day, with a `DragValue` for the hours and minutes.
+    pub fn time(mut self, time: &'a mut TimeOfDay) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// Highlight this date in the calendar.
+    /// Egui does not know the local date, so you need to get it from your integration.
+    pub fn today(mut self, today: Date) -> Self {
+        self.today = Some(today);
+        self
+    }
+}
+
+impl<'a> Widget for DatePicker<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let DatePicker {
+            id_source,
+            date,
+            time,
+            today,
+        } = self;
+
+        let button_id = ui.make_persistent_id(id_source);
+        let popup_id = button_id.with("popup");
+
+        let button_text = match &time {
+            Some(time) => format!("{} {}", date, time),
+            None => date.to_string(),
+        };
+
+        let response = combo_box_impl(ui, button_id, button_text, false, |ui| {
+            let mut state = ui
+                .memory()
+                .date_picker
+                .get(&button_id)
+                .copied()
+                .unwrap_or(State { shown_month: *date });
+
+            ui.horizontal(|ui| {
+                if ui.button("<<").on_hover_text("Previous year").clicked {
+                    state.shown_month = state.shown_month.add_months(-12);
+                }
+                if ui.button("<").on_hover_text("Previous month").clicked {
+                    state.shown_month = state.shown_month.add_months(-1);
+                }
+                let month_name = MONTH_NAMES[state.shown_month.month as usize - 1];
+                ui.label(format!("{} {}", month_name, state.shown_month.year));
+                if ui.button(">").on_hover_text("Next month").clicked {
+                    state.shown_month = state.shown_month.add_months(1);
+                }
+                if ui.button(">>").on_hover_text("Next year").clicked {
+                    state.shown_month = state.shown_month.add_months(12);
+                }
+            });
+
+            if let Some(picked) = calendar_ui(ui, button_id, state.shown_month, *date, today) {
+                *date = picked;
+                if time.is_none() {
+                    ui.memory().close_popup();
+                }
+            }
+
+            if let Some(time) = time {
+                ui.horizontal(|ui| {
+                    ui.label("Time:");
+                    ui.add(DragValue::u8(&mut time.hour).range(0.0..=23.0).speed(0.1));
+                    ui.label(":");
+                    ui.add(DragValue::u8(&mut time.minute).range(0.0..=59.0).speed(0.2));
+                });
+            }
+
+            ui.memory().date_picker.insert(button_id, state);
+        });
+
+        if !ui.memory().is_popup_open(popup_id) {
+            ui.memory().date_picker.remove(&button_id);
+        }
+
+        response
+    }
+}
+
+/// Show the weeks of the given month, with week numbers.
+/// Returns the date that was clicked, if any.
+fn calendar_ui(
+    ui: &mut Ui,
+    id: Id,
+    month: Date,
+    selected: Date,
+    today: Option<Date>,
+) -> Option<Date> {
+    let text_style = TextStyle::Body;
+    let cell_size = vec2(2.0, 1.25) * ui.fonts()[text_style].line_spacing();
+    let first_of_month = Date::new(month.year, month.month, 1);
+    let first_shown = first_of_month.add_days(-(first_of_month.weekday() as i64));
+    let num_days_shown = first_of_month.weekday() + days_in_month(month.year, month.month);
+    let num_weeks = num_days_shown.div_ceil(7);
+
+    let rect = ui.allocate_space(vec2(
+        8.0 * cell_size.x,
+        (1 + num_weeks) as f32 * cell_size.y,
+    ));
+    let cell_rect = |column: u32, row: u32| {
+        Rect::from_min_size(
+            rect.min + vec2(column as f32 * cell_size.x, row as f32 * cell_size.y),
+            cell_size,
+        )
+    };
+
+    let visuals = &ui.style().visuals;
+    let dim_color = visuals.widgets.disabled.text_color();
+    let painter = ui.painter();
+
+    painter.text(
+        cell_rect(0, 0).center(),
+        align::CENTER_CENTER,
+        "Wk",
+        text_style,
+        dim_color,
+    );
+    for (weekday, name) in WEEKDAY_NAMES.iter().enumerate() {
+        let center = cell_rect(weekday as u32 + 1, 0).center();
+        painter.text(center, align::CENTER_CENTER, *name, text_style, dim_color);
+    }
+
+    let mut clicked = None;
+    for week in 0..num_weeks {
+        let monday = first_shown.add_days(7 * week as i64);
+        let week_number = monday.iso_week().to_string();
+        let center = cell_rect(0, week + 1).center();
+        painter.text(
+            center,
+            align::CENTER_CENTER,
+            week_number,
+            text_style,
+            dim_color,
+        );
+
+        for weekday in 0..7 {
+            let day = monday.add_days(weekday as i64);
+            let rect = cell_rect(weekday + 1, week + 1);
+            let response = ui.interact(rect, id.with(day), Sense::click());
+            let widget_visuals = ui.style().interact(&response);
+            let corner_radius = widget_visuals.corner_radius;
+
+            if day == selected {
+                painter.rect_filled(
+                    rect.shrink(1.0),
+                    corner_radius,
+                    visuals.widgets.active.bg_fill,
+                );
+            } else if response.hovered {
+                painter.rect_filled(rect.shrink(1.0), corner_radius, widget_visuals.bg_fill);
+            }
+            if Some(day) == today {
+                painter.rect_stroke(rect.shrink(1.0), corner_radius, widget_visuals.fg_stroke);
+            }
+
+            let text_color = if day.month == month.month {
+                widget_visuals.text_color()
+            } else {
+                dim_color
+            };
+            let text = day.day.to_string();
+            painter.text(
+                rect.center(),
+                align::CENTER_CENTER,
+                text,
+                text_style,
+                text_color,
+            );
+
+            if response.clicked {
+                clicked = Some(day);
+            }
+        }
+    }
+    clicked
+}
+
+#[test]
+fn test_gregorian_math() {
+    for &days in &[-800_000, -1, 0, 1, 11_016, 18_589, 2_932_896] {
+        assert_eq!(Date::from_days_since_epoch(days).days_since_epoch(), days);
+    }
+    assert_eq!(Date::new(2000, 3, 1).days_since_epoch(), 11_017);
+    assert_eq!(Date::new(2020, 11, 23).weekday(), 0); // Monday
+    assert_eq!(Date::new(2021, 1, 3).iso_week(), 53); // Still in the last week of 2020
+    assert_eq!(Date::new(2021, 1, 4).iso_week(), 1);
+    assert_eq!(Date::new(2020, 1, 31).add_months(1), Date::new(2020, 2, 29));
+    assert_eq!(
+        Date::new(2020, 1, 15).add_months(-13),
+        Date::new(2018, 12, 15)
+    );
+}
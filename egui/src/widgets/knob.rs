@@ -0,0 +1,338 @@
+#![allow(clippy::float_cmp)]
+
+use std::ops::RangeInclusive;
+
+use crate::{
+    math::NumExt,
+    paint::*,
+    widgets::{slider::SliderValue, Label},
+    *,
+};
+
+/// Dragging the mouse this many points up or down moves the knob over its full range.
+const DRAG_DISTANCE: f32 = 200.0;
+
+/// The knob goes from the lower left, over the top, to the lower right.
+const START_ANGLE: f32 = 0.75 * TAU / 2.0;
+const SWEEP_ANGLE: f32 = 0.75 * TAU;
+
+/// Control a number by a rotary knob, like the ones on audio equipment.
+///
+/// Drag up or down, or use the mouse wheel, to turn it.
+/// Double-click to reset it to its `default_value`.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// let mut gain = 1.0;
+/// ui.add(egui::Knob::f32(&mut gain, 0.0..=10.0).logarithmic(true).default_value(1.0).text("Gain"));
+/// ```
+pub struct Knob<'a> {
+    value: SliderValue<'a>,
+    default_value: Option<f64>,
+    diameter: Option<f32>,
+    text: Option<String>,
+    text_color: Option<Srgba>,
+}
+
+impl<'a> Knob<'a> {
+    fn new(value: SliderValue<'a>) -> Self {
+        Self {
+            value,
+            default_value: None,
+            diameter: None,
+            text: None,
+            text_color: None,
+        }
+    }
+
+    pub fn from_get_set(
+        range: RangeInclusive<f64>,
+        get_set_value: impl 'a + FnMut(Option<f64>) -> f64,
+    ) -> Self {
+        Self::new(SliderValue::from_get_set(range, get_set_value))
+    }
+
+    pub fn f32(value: &'a mut f32, range: RangeInclusive<f32>) -> Self {
+        Self::new(SliderValue::f32(value, range))
+    }
+
+    pub fn f64(value: &'a mut f64, range: RangeInclusive<f64>) -> Self {
+        Self::new(SliderValue::f64(value, range))
+    }
+
+    pub fn u8(value: &'a mut u8, range: RangeInclusive<u8>) -> Self {
+        Self::new(SliderValue::u8(value, range))
+    }
+
+    pub fn i32(value: &'a mut i32, range: RangeInclusive<i32>) -> Self {
+        Self::new(SliderValue::i32(value, range))
+    }
+
+    pub fn u32(value: &'a mut u32, range: RangeInclusive<u32>) -> Self {
+        Self::new(SliderValue::u32(value, range))
+    }
+
+    pub fn usize(value: &'a mut usize, range: RangeInclusive<usize>) -> Self {
+        Self::new(SliderValue::usize(value, range))
+    }
+
+    /// Show the value and this text to the right of the knob.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    pub fn text_color(mut self, text_color: Srgba) -> Self {
+        self.text_color = Some(text_color);
+        self
+    }
+
+    /// The value to reset to when the knob is double-clicked.
+    /// By default, double-clicking does nothing.
+    pub fn default_value(mut self, default_value: f64) -> Self {
+        self.default_value = Some(default_value);
+        self
+    }
+
+    /// Default: two times `spacing.interact_size.y`.
+    pub fn diameter(mut self, diameter: f32) -> Self {
+        self.diameter = Some(diameter);
+        self
+    }
+
+    /// Make this a logarithmic knob, just like `Slider::logarithmic`.
+    /// The default is OFF.
+    pub fn logarithmic(mut self, logarithmic: bool) -> Self {
+        self.value = self.value.logarithmic(logarithmic);
+        self
+    }
+
+    /// For logarithmic knobs that includes zero:
+    /// what is the smallest positive value you want to be able to select?
+    /// The default is `1` for integer knobs and `1e-6` for real knobs.
+    pub fn smallest_positive(mut self, smallest_positive: f64) -> Self {
+        self.value = self.value.smallest_positive(smallest_positive);
+        self
+    }
+
+    /// Turn smart aim on/off. Default is ON.
+    pub fn smart_aim(mut self, smart_aim: bool) -> Self {
+        self.value = self.value.smart_aim(smart_aim);
+        self
+    }
+
+    /// Precision (number of decimals) used when displaying the value.
+    /// Values will also be rounded to this precision.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.value = self.value.precision(precision);
+        self
+    }
+
+    /// Helper: equivalent to `self.precision(0).smallest_positive(1.0)`.
+    pub fn integer(self) -> Self {
+        self.precision(0).smallest_positive(1.0)
+    }
+
+    /// Just the knob, no text
+    fn knob_ui(&mut self, ui: &mut Ui) -> Response {
+        let diameter = self
+            .diameter
+            .unwrap_or(2.0 * ui.style().spacing.interact_size.y);
+        let rect = ui.allocate_space(Vec2::splat(diameter));
+        let id = ui.make_position_id();
+        let response = ui.interact(rect, id, Sense::click_and_drag());
+        let aim_radius = (ui.input().aim_radius() / DRAG_DISTANCE) as f64;
+
+        if response.double_clicked {
+            if let Some(default_value) = self.default_value {
+                self.value.set(default_value);
+            }
+        } else if response.active {
+            // We remember the unrounded position while dragging,
+            // so that smart aim does not keep snapping back to the same value.
+            let normalized = ui
+                .memory()
+                .knob
+                .get(&id)
+                .copied()
+                .unwrap_or_else(|| self.value.normalized());
+            let normalized = turn(normalized, -ui.input().mouse.delta.y);
+            ui.memory().knob.insert(id, normalized);
+            let new_value = self.value.aimed_value(normalized, aim_radius);
+            if new_value != self.value.get() {
+                self.value.set(new_value);
+            }
+        } else {
+            ui.memory().knob.remove(&id);
+
+            let scroll = ui.input().scroll_delta.y;
+            if response.hovered && scroll != 0.0 {
+                let normalized = turn(self.value.normalized(), scroll);
+                let new_value = self.value.aimed_value(normalized, aim_radius);
+                self.value.set(new_value);
+            }
+        }
+
+        // Paint it:
+        {
+            let normalized = self.value.normalized() as f32;
+            let visuals = ui.style().interact(&response);
+            let center = rect.center();
+            let radius = 0.5 * diameter;
+            let track_width = (radius / 5.0).at_least(2.0);
+            let track_radius = radius - 0.5 * track_width;
+
+            // A bipolar range (e.g. -1 to 1) is filled from zero:
+            let range = self.value.range();
+            let zero = if range.contains(&0.0) || range.end() < &0.0 {
+                self.value.normalized_from_value(0.0) as f32
+            } else {
+                0.0
+            };
+
+            let painter = ui.painter();
+            painter.add(PaintCmd::line(
+                arc_points(center, track_radius, 0.0, 1.0),
+                Stroke::new(track_width, ui.style().visuals.dark_bg_color),
+            ));
+            if normalized != zero {
+                painter.add(PaintCmd::line(
+                    arc_points(center, track_radius, zero, normalized),
+                    Stroke::new(track_width, visuals.fg_fill),
+                ));
+            }
+
+            let body_radius = track_radius - track_width;
+            painter.add(PaintCmd::Circle {
+                center,
+                radius: body_radius,
+                fill: ui.style().visuals.widgets.inactive.bg_fill,
+                stroke: visuals.bg_stroke,
+            });
+            let direction = angle_direction(normalized);
+            painter.line_segment(
+                [
+                    center + 0.3 * body_radius * direction,
+                    center + body_radius * direction,
+                ],
+                visuals.fg_stroke,
+            );
+        }
+
+        response
+    }
+
+    fn label_ui(&mut self, ui: &mut Ui) {
+        let value_text = self
+            .value
+            .format((ui.input().aim_radius() / DRAG_DISTANCE) as f64);
+        let text_color = self
+            .text_color
+            .unwrap_or_else(|| ui.style().visuals.text_color());
+        ui.add(
+            Label::new(value_text)
+                .multiline(false)
+                .text_style(TextStyle::Monospace)
+                .text_color(text_color),
+        );
+        if let Some(label_text) = self.text.as_deref() {
+            ui.add(
+                Label::new(label_text)
+                    .multiline(false)
+                    .text_color(text_color),
+            );
+        }
+    }
+}
+
+/// Turn the knob by this many points (positive is clockwise), without wrapping around.
+fn turn(normalized: f64, points: f32) -> f64 {
+    clamp(normalized + (points / DRAG_DISTANCE) as f64, 0.0..=1.0)
+}
+
+/// Direction from the center of the knob to the given normalized value.
+fn angle_direction(normalized: f32) -> Vec2 {
+    Vec2::angled(START_ANGLE + SWEEP_ANGLE * normalized)
+}
+
+fn arc_points(center: Pos2, radius: f32, from: f32, to: f32) -> Vec<Pos2> {
+    let (from, to) = if from <= to { (from, to) } else { (to, from) };
+    let num_points = ((to - from) * 32.0).ceil().at_least(1.0) as usize + 1;
+    (0..num_points)
+        .map(|i| {
+            let t = lerp(from..=to, i as f32 / (num_points - 1) as f32);
+            center + radius * angle_direction(t)
+        })
+        .collect()
+}
+
+impl<'a> Widget for Knob<'a> {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        if self.text.is_some() {
+            ui.horizontal(|ui| {
+                let response = self.knob_ui(ui);
+                self.label_ui(ui);
+                response
+            })
+            .0
+        } else {
+            self.knob_ui(ui)
+        }
+    }
+}
+
+#[test]
+fn test_angle_from_value() {
+    let mut gain = 0.0;
+    let value = SliderValue::f32(&mut gain, 0.0..=10.0);
+    let direction = |v| angle_direction(value.normalized_from_value(v) as f32);
+    let is_close = |a: Vec2, b: Vec2| (a - b).length() < 1e-6;
+    // Lower left, top, lower right (y is down):
+    assert!(is_close(direction(0.0), vec2(-1.0, 1.0).normalized()));
+    assert!(is_close(direction(5.0), vec2(0.0, -1.0)));
+    assert!(is_close(direction(10.0), vec2(1.0, 1.0).normalized()));
+    // Values outside the range point at the ends of the track:
+    assert!(is_close(direction(-5.0), direction(0.0)));
+    assert!(is_close(direction(15.0), direction(10.0)));
+    // And back:
+    assert_eq!(value.value_from_normalized(0.5), 5.0);
+    assert_eq!(value.value_from_normalized(1.5), 10.0);
+}
+
+#[test]
+fn test_turn_does_not_wrap_around() {
+    assert_eq!(turn(0.25, 0.5 * DRAG_DISTANCE), 0.75);
+    assert_eq!(turn(0.9, DRAG_DISTANCE), 1.0);
+    assert_eq!(turn(0.1, -DRAG_DISTANCE), 0.0);
+}
+
+#[test]
+fn test_drag_past_the_end() {
+    let mut ctx = Context::new();
+    let mut raw_input = RawInput {
+        screen_size: vec2(400.0, 300.0),
+        ..Default::default()
+    };
+    let mut gain = 9.0;
+    let mut knob_center = Pos2::default();
+    // Lay out, hover, press, drag far up, drag far down:
+    for &(dy, mouse_down, expected) in &[
+        (0.0, false, 9.0),
+        (0.0, false, 9.0),
+        (0.0, true, 9.0),
+        (-1000.0, true, 10.0),
+        (1000.0, true, 0.0),
+    ] {
+        raw_input.mouse_pos = Some(knob_center + vec2(0.0, dy));
+        raw_input.mouse_down = mouse_down;
+        ctx.begin_frame(raw_input.clone());
+        Area::new("knob")
+            .fixed_pos(pos2(10.0, 10.0))
+            .show(&ctx, |ui| {
+                let response = ui.add(Knob::f32(&mut gain, 0.0..=10.0).smart_aim(false));
+                knob_center = response.rect.center();
+            });
+        let _ = ctx.end_frame();
+        assert_eq!(gain, expected);
+    }
+}
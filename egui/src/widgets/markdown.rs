@@ -0,0 +1,529 @@
+//! A minimal markdown viewer.
+//!
+//! Supports a small subset of [CommonMark](https://commonmark.org/):
+//! headings, paragraphs with `*emphasis*` and `**strong**` text, `inline code`,
+//! fenced code blocks, bullet and numbered lists, `[links](url)` and horizontal rules.
+
+use crate::{paint::*, *};
+
+/// Shows some markdown text.
+///
+/// Egui has no italic or bold fonts,
+/// so *emphasis* is shown in a brighter color, and **strong** text is also underlined.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// ui.add(egui::MarkdownViewer::new("# Hello\nSome *markdown* text."));
+/// ```
+pub struct MarkdownViewer<'a> {
+    markdown: &'a str,
+}
+
+impl<'a> MarkdownViewer<'a> {
+    pub fn new(markdown: &'a str) -> Self {
+        Self { markdown }
+    }
+}
+
+impl<'a> Widget for MarkdownViewer<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let blocks = parse_blocks(self.markdown);
+        ui.vertical(|ui| blocks_ui(ui, &blocks)).1
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum Block {
+    /// Heading of any level
+    Heading(String),
+    Paragraph(String),
+    Code(String),
+    /// If `Some`, the list is numbered starting at that number
+    List(Option<usize>, Vec<ListItem>),
+    Rule,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ListItem {
+    text: String,
+    children: Vec<Block>,
+}
+
+/// One parsed list line.
+struct ListEntry {
+    indent: usize,
+    number: Option<usize>,
+    text: String,
+}
+
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut paragraph = String::new();
+    let mut list_entries: Vec<ListEntry> = vec![];
+    let mut lines = markdown.lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        let starts_new_block = trimmed.is_empty()
+            || trimmed.starts_with("```")
+            || heading(trimmed).is_some()
+            || is_rule(trimmed)
+            || list_entry(line).is_some();
+        if starts_new_block {
+            flush_paragraph(&mut blocks, &mut paragraph);
+        }
+        let continues_list = !list_entries.is_empty()
+            && (list_entry(line).is_some() || (!trimmed.is_empty() && !starts_new_block));
+        if !continues_list {
+            flush_list(&mut blocks, &mut list_entries);
+        }
+
+        if trimmed.is_empty() {
+            // End of paragraph or list
+        } else if trimmed.starts_with("```") {
+            let mut code = String::new();
+            let is_closed = lines.clone().any(|line| line.trim().starts_with("```"));
+            for line in &mut lines {
+                if line.trim().starts_with("```") {
+                    break;
+                }
+                if !is_closed && line.trim().is_empty() {
+                    break; // Without a closing fence, the code ends with the paragraph
+                }
+                code += line;
+                code += "\n";
+            }
+            code.pop(); // trailing newline
+            blocks.push(Block::Code(code));
+        } else if let Some(text) = heading(trimmed) {
+            blocks.push(Block::Heading(text.to_owned()));
+        } else if is_rule(trimmed) {
+            blocks.push(Block::Rule);
+        } else if let Some(entry) = list_entry(line) {
+            list_entries.push(entry);
+        } else if let Some(last_entry) = list_entries.last_mut() {
+            // Lazy continuation of a list item:
+            last_entry.text += " ";
+            last_entry.text += trimmed;
+        } else {
+            if !paragraph.is_empty() {
+                paragraph += " ";
+            }
+            paragraph += trimmed;
+        }
+    }
+
+    flush_paragraph(&mut blocks, &mut paragraph);
+    flush_list(&mut blocks, &mut list_entries);
+    blocks
+}
+
+fn flush_paragraph(blocks: &mut Vec<Block>, paragraph: &mut String) {
+    if !paragraph.is_empty() {
+        blocks.push(Block::Paragraph(std::mem::take(paragraph)));
+    }
+}
+
+fn flush_list(blocks: &mut Vec<Block>, entries: &mut Vec<ListEntry>) {
+    let entries = std::mem::take(entries);
+    let mut entries = entries.into_iter().peekable();
+    while entries.peek().is_some() {
+        blocks.push(build_list(&mut entries));
+    }
+}
+
+/// Builds one list out of consecutive entries at the same indentation,
+/// with more indented entries becoming nested lists.
+fn build_list(entries: &mut std::iter::Peekable<impl Iterator<Item = ListEntry>>) -> Block {
+    let first = entries.next().unwrap();
+    let indent = first.indent;
+    let ordered = first.number.is_some();
+    let start = first.number;
+    let mut items = vec![ListItem {
+        text: first.text,
+        children: vec![],
+    }];
+
+    while let Some(next) = entries.peek() {
+        if next.indent > indent {
+            let child = build_list(entries);
+            items.last_mut().unwrap().children.push(child);
+        } else if next.indent == indent && next.number.is_some() == ordered {
+            let next = entries.next().unwrap();
+            items.push(ListItem {
+                text: next.text,
+                children: vec![],
+            });
+        } else {
+            break;
+        }
+    }
+
+    Block::List(start, items)
+}
+
+/// `## Heading` -> `Some("Heading")`
+fn heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' ')) {
+        Some(rest.trim().trim_end_matches('#').trim_end())
+    } else {
+        None
+    }
+}
+
+/// `---`, `***` or `___`, possibly with spaces in between.
+fn is_rule(line: &str) -> bool {
+    let mut chars = line.chars().filter(|c| !c.is_whitespace());
+    match chars.next() {
+        Some(first) if "-*_".contains(first) => {
+            let count = 1 + chars.clone().count();
+            count >= 3 && chars.all(|c| c == first)
+        }
+        _ => false,
+    }
+}
+
+/// `- item`, `* item`, `+ item`, `1. item` or `1) item`
+fn list_entry(line: &str) -> Option<ListEntry> {
+    let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+    let line = line.trim_start();
+
+    for marker in &["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(marker) {
+            return Some(ListEntry {
+                indent,
+                number: None,
+                text: text.trim().to_owned(),
+            });
+        }
+    }
+
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    let rest = &line[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        Some(ListEntry {
+            indent,
+            number: line[..digits].parse().ok(),
+            text: rest[2..].trim().to_owned(),
+        })
+    } else {
+        None
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+fn blocks_ui(ui: &mut Ui, blocks: &[Block]) {
+    for (i, block) in blocks.iter().enumerate() {
+        match block {
+            Block::Heading(text) => {
+                ui.add(inline_rich_text(ui, text, TextStyle::Heading));
+            }
+            Block::Paragraph(text) => {
+                let spans = parse_inline(text);
+                match spans.as_slice() {
+                    [Inline::Link { text, url }] => {
+                        ui.add(Hyperlink::new(url.clone()).text(text.clone()));
+                    }
+                    _ => {
+                        ui.add(rich_text(ui, &spans, TextStyle::Body));
+                    }
+                }
+            }
+            Block::Code(code) => {
+                Frame::dark_canvas(ui.style()).show(ui, |ui| {
+                    ui.add(Label::new(code.clone()).monospace());
+                });
+            }
+            Block::List(start, items) => {
+                list_ui(ui, i, *start, items);
+            }
+            Block::Rule => {
+                ui.add(Separator::new());
+            }
+        }
+    }
+}
+
+fn list_ui(ui: &mut Ui, id_source: impl std::hash::Hash, start: Option<usize>, items: &[ListItem]) {
+    ui.indent(id_source, |ui| {
+        for (i, item) in items.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let marker = match start {
+                    Some(start) => format!("{}.", start + i),
+                    None => "•".to_owned(),
+                };
+                ui.label(marker);
+                ui.add(inline_rich_text(ui, &item.text, TextStyle::Body));
+            });
+            for (j, child) in item.children.iter().enumerate() {
+                if let Block::List(start, items) = child {
+                    list_ui(ui, (i, j), *start, items);
+                }
+            }
+        }
+    });
+}
+
+fn inline_rich_text(ui: &Ui, text: &str, text_style: TextStyle) -> RichText {
+    rich_text(ui, &parse_inline(text), text_style)
+}
+
+fn rich_text(ui: &Ui, spans: &[Inline], text_style: TextStyle) -> RichText {
+    let visuals = &ui.style().visuals;
+    let bright_color = visuals.widgets.active.text_color();
+    let code_background = Srgba::black_alpha(200);
+
+    let mut rich_text = RichText::new();
+    for span in spans {
+        let span = match span {
+            Inline::Text {
+                text,
+                emphasis,
+                strong,
+            } => {
+                let mut span = TextSpan::new(text.clone()).text_style(text_style);
+                if *emphasis || *strong {
+                    span = span.text_color(bright_color);
+                }
+                if *strong {
+                    span = span.underline();
+                }
+                span
+            }
+            Inline::Code(code) => TextSpan::new(code.clone())
+                .monospace()
+                .background(code_background),
+            Inline::Link { text, url } => TextSpan::new(text.clone())
+                .text_style(text_style)
+                .url(url.clone()),
+        };
+        rich_text = rich_text.span(span);
+    }
+    rich_text
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum Inline {
+    Text {
+        text: String,
+        emphasis: bool,
+        strong: bool,
+    },
+    Code(String),
+    Link {
+        text: String,
+        url: String,
+    },
+}
+
+fn parse_inline(text: &str) -> Vec<Inline> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = vec![];
+    let mut current = String::new();
+    let mut emphasis = false;
+    let mut strong = false;
+
+    let flush = |spans: &mut Vec<Inline>, current: &mut String, emphasis, strong| {
+        if !current.is_empty() {
+            spans.push(Inline::Text {
+                text: std::mem::take(current),
+                emphasis,
+                strong,
+            });
+        }
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let prev = if i > 0 { chars[i - 1] } else { ' ' };
+        let next = chars.get(i + 1).copied().unwrap_or(' ');
+
+        if c == '\\' && i + 1 < chars.len() {
+            current.push(next);
+            i += 2;
+        } else if c == '`' {
+            if let Some(len) = chars[i + 1..].iter().position(|&c| c == '`') {
+                flush(&mut spans, &mut current, emphasis, strong);
+                spans.push(Inline::Code(chars[i + 1..i + 1 + len].iter().collect()));
+                i += len + 2;
+            } else {
+                current.push(c);
+                i += 1;
+            }
+        } else if c == '[' {
+            match parse_link(&chars[i..]) {
+                Some((link_text, url, len)) => {
+                    flush(&mut spans, &mut current, emphasis, strong);
+                    spans.push(Inline::Link {
+                        text: link_text,
+                        url,
+                    });
+                    i += len;
+                }
+                None => {
+                    current.push(c);
+                    i += 1;
+                }
+            }
+        } else if (c == '*' || c == '_') && next == c && is_delimiter(&chars, i, 2, strong) {
+            flush(&mut spans, &mut current, emphasis, strong);
+            strong = !strong;
+            i += 2;
+        } else if (c == '*' || (c == '_' && !(prev.is_alphanumeric() && next.is_alphanumeric())))
+            && is_delimiter(&chars, i, 1, emphasis)
+        {
+            flush(&mut spans, &mut current, emphasis, strong);
+            emphasis = !emphasis;
+            i += 1;
+        } else {
+            current.push(c);
+            i += 1;
+        }
+    }
+    flush(&mut spans, &mut current, emphasis, strong);
+    spans
+}
+
+/// Does the `len` chars long `*` or `_` run at `i` open (or if `is_open`, close) emphasis?
+///
+/// It must be next to the emphasized text, so `2 * 3` is not emphasized,
+/// and it only opens if it is closed later on.
+fn is_delimiter(chars: &[char], i: usize, len: usize, is_open: bool) -> bool {
+    if is_open {
+        return i > 0 && !chars[i - 1].is_whitespace();
+    }
+    let delimiter = &chars[i..i + len];
+    let is_text = |c: char| !c.is_whitespace() && c != delimiter[0];
+    let text_start = i + len;
+    let is_closed = (text_start + 1..chars.len())
+        .any(|j| chars[j..].starts_with(delimiter) && is_text(chars[j - 1]));
+    matches!(chars.get(text_start), Some(&c) if is_text(c)) && is_closed
+}
+
+/// `[text](url)` -> `Some((text, url, length in chars))`
+fn parse_link(chars: &[char]) -> Option<(String, String, usize)> {
+    let text_len = chars.iter().position(|&c| c == ']')?;
+    if chars.get(text_len + 1) != Some(&'(') {
+        return None;
+    }
+    let url_start = text_len + 2;
+    let url_len = chars[url_start..].iter().position(|&c| c == ')')?;
+    let text = chars[1..text_len].iter().collect();
+    let url = chars[url_start..url_start + url_len].iter().collect();
+    Some((text, url, url_start + url_len + 1))
+}
+
+#[test]
+fn test_markdown_parsing() {
+    let markdown = "# Title\nSome *text*\nmore `code`.\n\n- a\n  - b\n- [c](url)\n\n---\n```\nfn main() {}\n```";
+    let blocks = parse_blocks(markdown);
+    assert_eq!(
+        blocks,
+        vec![
+            Block::Heading("Title".to_owned()),
+            Block::Paragraph("Some *text* more `code`.".to_owned()),
+            Block::List(
+                None,
+                vec![
+                    ListItem {
+                        text: "a".to_owned(),
+                        children: vec![Block::List(
+                            None,
+                            vec![ListItem {
+                                text: "b".to_owned(),
+                                children: vec![],
+                            }]
+                        )],
+                    },
+                    ListItem {
+                        text: "[c](url)".to_owned(),
+                        children: vec![],
+                    },
+                ]
+            ),
+            Block::Rule,
+            Block::Code("fn main() {}".to_owned()),
+        ]
+    );
+
+    assert_eq!(
+        parse_inline("a *b* **c** `d` [e](f) snake_case"),
+        vec![
+            Inline::Text {
+                text: "a ".to_owned(),
+                emphasis: false,
+                strong: false,
+            },
+            Inline::Text {
+                text: "b".to_owned(),
+                emphasis: true,
+                strong: false,
+            },
+            Inline::Text {
+                text: " ".to_owned(),
+                emphasis: false,
+                strong: false,
+            },
+            Inline::Text {
+                text: "c".to_owned(),
+                emphasis: false,
+                strong: true,
+            },
+            Inline::Text {
+                text: " ".to_owned(),
+                emphasis: false,
+                strong: false,
+            },
+            Inline::Code("d".to_owned()),
+            Inline::Text {
+                text: " ".to_owned(),
+                emphasis: false,
+                strong: false,
+            },
+            Inline::Link {
+                text: "e".to_owned(),
+                url: "f".to_owned(),
+            },
+            Inline::Text {
+                text: " snake_case".to_owned(),
+                emphasis: false,
+                strong: false,
+            },
+        ]
+    );
+
+    // Without closing delimiters, or not next to any text, there is no emphasis:
+    for text in &[
+        "2 * 3 = 6",
+        "*not closed",
+        "**not closed either",
+        "a ** b **",
+    ] {
+        assert_eq!(
+            parse_inline(text),
+            vec![Inline::Text {
+                text: text.to_string(),
+                emphasis: false,
+                strong: false,
+            }]
+        );
+    }
+
+    // An unterminated code fence ends with the paragraph:
+    assert_eq!(
+        parse_blocks("```\nlet a = 1;\n\nSome text"),
+        vec![
+            Block::Code("let a = 1;".to_owned()),
+            Block::Paragraph("Some text".to_owned()),
+        ]
+    );
+}
@@ -8,13 +8,33 @@
 
 use crate::{layout::Direction, *};
 
+pub(crate) mod code_editor;
 pub mod color_picker;
+mod curve_edit;
+pub(crate) mod date_picker;
 mod drag_value;
 mod image;
+mod knob;
+mod markdown;
+mod rich_text;
 mod slider;
 pub(crate) mod text_edit;
-
-pub use {drag_value::DragValue, image::Image, slider::*, text_edit::*};
+mod text_layout;
+
+pub use {
+    code_editor::{
+        CodeEditor, ColoredSpan, Highlighter, PlainText, RustHighlighter, TomlHighlighter,
+    },
+    curve_edit::{Curve, CurveEdit, CurveKey, Interpolation},
+    date_picker::{Date, DatePicker, TimeOfDay},
+    drag_value::DragValue,
+    image::Image,
+    knob::Knob,
+    markdown::MarkdownViewer,
+    rich_text::{RichText, TextSpan},
+    slider::*,
+    text_edit::*,
+};
 
 use paint::*;
 
@@ -406,8 +426,8 @@ impl<'a> Widget for Checkbox<'a> {
         let (small_icon_rect, big_icon_rect) = ui.style().spacing.icon_rectangles(response.rect);
         ui.painter().add(PaintCmd::Rect {
             rect: big_icon_rect,
-            corner_radius: visuals.corner_radius,
-            fill: visuals.bg_fill,
+            corner_radius: visuals.corner_radius.into(),
+            fill: visuals.bg_fill.into(),
             stroke: visuals.bg_stroke,
         });
 
@@ -0,0 +1,223 @@
+use crate::{paint::*, *};
+
+/// A piece of text in a `RichText`, with its own style.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSpan {
+    pub(crate) text: String,
+    pub(crate) text_style: Option<TextStyle>,
+    pub(crate) text_color: Option<Srgba>,
+    pub(crate) underline: bool,
+    pub(crate) background: Srgba,
+    pub(crate) url: Option<String>,
+}
+
+impl TextSpan {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            text_style: None,
+            text_color: None,
+            underline: false,
+            background: color::TRANSPARENT,
+            url: None,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// If you do not set a `TextStyle`, the default `style.text_style`.
+    pub fn text_style(mut self, text_style: TextStyle) -> Self {
+        self.text_style = Some(text_style);
+        self
+    }
+
+    pub fn heading(self) -> Self {
+        self.text_style(TextStyle::Heading)
+    }
+
+    pub fn monospace(self) -> Self {
+        self.text_style(TextStyle::Monospace)
+    }
+
+    pub fn small(self) -> Self {
+        self.text_style(TextStyle::Small)
+    }
+
+    pub fn text_color(mut self, text_color: Srgba) -> Self {
+        self.text_color = Some(text_color);
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Paint this color behind the text.
+    pub fn background(mut self, background: Srgba) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Make this span a link to the given url.
+    /// Clicking it will set `Output::open_url`.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl From<&str> for TextSpan {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for TextSpan {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Text where different parts can have different styles, colors and links.
+/// All spans are laid out together, and word-wrapped as one paragraph.
+///
+/// ```
+/// # let mut ui = egui::Ui::__test();
+/// use egui::{RichText, TextSpan};
+/// ui.add(
+///     RichText::new()
+///         .span("Read ")
+///         .span(TextSpan::new("the docs").url("https://docs.rs/egui"))
+///         .span(" for more."),
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RichText {
+    spans: Vec<TextSpan>,
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn span(mut self, span: impl Into<TextSpan>) -> Self {
+        self.spans.push(span.into());
+        self
+    }
+
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.iter().all(|span| span.text.is_empty())
+    }
+
+    pub fn layout(&self, ui: &Ui) -> font::Galley {
+        self.layout_width(ui, ui.available().width())
+    }
+
+    pub fn layout_width(&self, ui: &Ui, max_width: f32) -> font::Galley {
+        let style = ui.style();
+        let mut text = String::new();
+        let mut sections = Vec::with_capacity(self.spans.len());
+        let mut char_count = 0;
+        for span in &self.spans {
+            let span_char_count = span.text.chars().count();
+            let default_color = if span.url.is_some() {
+                color::LIGHT_BLUE
+            } else {
+                style.visuals.text_color()
+            };
+            sections.push(font::TextSection {
+                char_range: char_count..char_count + span_char_count,
+                text_style: span.text_style.unwrap_or(style.body_text_style),
+                color: span.text_color.unwrap_or(default_color),
+                underline: span.underline,
+                background: span.background,
+            });
+            text += &span.text;
+            char_count += span_char_count;
+        }
+        ui.fonts().layout_sections(text, sections, max_width)
+    }
+}
+
+impl Widget for RichText {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let mut galley = self.layout(ui);
+        let rect = ui.allocate_space(galley.size);
+
+        if self.spans.iter().all(|span| span.url.is_none()) {
+            ui.painter()
+                .galley(rect.min, galley, TextStyle::Body, color::TRANSPARENT);
+            return ui.interact_hover(rect);
+        }
+
+        let id = ui.make_position_id();
+        let response = ui.interact(rect, id, Sense::click());
+
+        let hovered_span_idx = ui
+            .input()
+            .mouse
+            .pos
+            .filter(|_| response.hovered)
+            .and_then(|mouse_pos| galley.char_under(mouse_pos - rect.min))
+            .and_then(|char_idx| galley.section_idx_at(char_idx));
+        let hovered_link =
+            hovered_span_idx.and_then(|idx| Some((idx, self.spans[idx].url.clone()?)));
+
+        if let Some((span_idx, url)) = &hovered_link {
+            ui.ctx().output().cursor_icon = CursorIcon::PointingHand;
+            if response.clicked {
+                ui.ctx().output().open_url = Some(url.clone());
+            }
+            galley.sections[*span_idx].underline = true;
+        }
+
+        ui.painter()
+            .galley(rect.min, galley, TextStyle::Body, color::TRANSPARENT);
+
+        match hovered_link {
+            Some((_, url)) => response.on_hover_text(url),
+            None => response,
+        }
+    }
+}
+
+#[test]
+fn test_click_link() {
+    let mut ctx = Context::new();
+    let mut raw_input = RawInput {
+        screen_size: vec2(400.0, 300.0),
+        ..Default::default()
+    };
+    let mut link_rect = Rect::nothing();
+    let mut open_url = None;
+    // Lay out, press, release:
+    for &(mouse_over_link, mouse_down) in &[(false, false), (true, true), (true, false)] {
+        raw_input.mouse_pos = Some(link_rect.center()).filter(|_| mouse_over_link);
+        raw_input.mouse_down = mouse_down;
+        ctx.begin_frame(raw_input.clone());
+        Area::new("rich_text")
+            .fixed_pos(pos2(10.0, 10.0))
+            .show(&ctx, |ui| {
+                let rich_text = RichText::new()
+                    .span("Read ")
+                    .span(TextSpan::new("the docs").url("https://docs.rs/egui"));
+                let galley = rich_text.layout(ui);
+                let rect = ui.add(rich_text).rect;
+                // The rect of the second span:
+                let link_start = galley.lines[0].x_offsets[5];
+                link_rect = Rect::from_min_max(pos2(rect.min.x + link_start, rect.min.y), rect.max);
+            });
+        open_url = ctx.end_frame().0.open_url;
+    }
+    assert_eq!(open_url.as_deref(), Some("https://docs.rs/egui"));
+}
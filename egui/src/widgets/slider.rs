@@ -35,21 +35,17 @@ struct SliderSpec {
     smallest_positive: f64,
 }
 
-/// Control a number by a horizontal slider.
-/// The range can include any numbers, and go from low-to-high or from high-to-low.
-pub struct Slider<'a> {
+/// The number controlled by a `Slider` or a `Knob`, and how it maps to the normalized [0-1] range.
+pub(crate) struct SliderValue<'a> {
     get_set_value: GetSetValue<'a>,
     range: RangeInclusive<f64>,
     spec: SliderSpec,
     smart_aim: bool,
-    // TODO: label: Option<Label>
-    text: Option<String>,
     precision: Option<usize>,
-    text_color: Option<Srgba>,
 }
 
-impl<'a> Slider<'a> {
-    pub fn from_get_set(
+impl<'a> SliderValue<'a> {
+    pub(crate) fn from_get_set(
         range: RangeInclusive<f64>,
         get_set_value: impl 'a + FnMut(Option<f64>) -> f64,
     ) -> Self {
@@ -61,81 +57,209 @@ impl<'a> Slider<'a> {
                 smallest_positive: 1e-6,
             },
             smart_aim: true,
-            text: None,
             precision: None,
-            text_color: None,
         }
     }
 
-    pub fn f32(value: &'a mut f32, range: RangeInclusive<f32>) -> Self {
-        Self {
-            ..Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
-                if let Some(v) = v {
-                    *value = v as f32
-                }
-                *value as f64
-            })
+    pub(crate) fn f32(value: &'a mut f32, range: RangeInclusive<f32>) -> Self {
+        Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
+            if let Some(v) = v {
+                *value = v as f32
+            }
+            *value as f64
+        })
+    }
+
+    pub(crate) fn f64(value: &'a mut f64, range: RangeInclusive<f64>) -> Self {
+        Self::from_get_set(range, move |v: Option<f64>| {
+            if let Some(v) = v {
+                *value = v
+            }
+            *value
+        })
+    }
+
+    pub(crate) fn u8(value: &'a mut u8, range: RangeInclusive<u8>) -> Self {
+        Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
+            if let Some(v) = v {
+                *value = v.round() as u8
+            }
+            *value as f64
+        })
+        .integer()
+    }
+
+    pub(crate) fn i32(value: &'a mut i32, range: RangeInclusive<i32>) -> Self {
+        Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
+            if let Some(v) = v {
+                *value = v.round() as i32
+            }
+            *value as f64
+        })
+        .integer()
+    }
+
+    pub(crate) fn u32(value: &'a mut u32, range: RangeInclusive<u32>) -> Self {
+        Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
+            if let Some(v) = v {
+                *value = v.round() as u32
+            }
+            *value as f64
+        })
+        .integer()
+    }
+
+    pub(crate) fn usize(value: &'a mut usize, range: RangeInclusive<usize>) -> Self {
+        let range = (*range.start() as f64)..=(*range.end() as f64);
+        Self::from_get_set(range, move |v: Option<f64>| {
+            if let Some(v) = v {
+                *value = v.round() as usize
+            }
+            *value as f64
+        })
+        .integer()
+    }
+
+    pub(crate) fn logarithmic(mut self, logarithmic: bool) -> Self {
+        self.spec.logarithmic = logarithmic;
+        self
+    }
+
+    pub(crate) fn smallest_positive(mut self, smallest_positive: f64) -> Self {
+        self.spec.smallest_positive = smallest_positive;
+        self
+    }
+
+    pub(crate) fn smart_aim(mut self, smart_aim: bool) -> Self {
+        self.smart_aim = smart_aim;
+        self
+    }
+
+    pub(crate) fn precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    pub(crate) fn integer(self) -> Self {
+        self.precision(0).smallest_positive(1.0)
+    }
+
+    pub(crate) fn range(&self) -> RangeInclusive<f64> {
+        self.range.clone()
+    }
+
+    pub(crate) fn get(&mut self) -> f64 {
+        get(&mut self.get_set_value)
+    }
+
+    /// Rounds to the precision, if any.
+    pub(crate) fn set(&mut self, mut value: f64) {
+        if let Some(precision) = self.precision {
+            value = round_to_precision(value, precision);
         }
+        set(&mut self.get_set_value, value);
     }
 
-    pub fn f64(value: &'a mut f64, range: RangeInclusive<f64>) -> Self {
-        Self {
-            ..Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
-                if let Some(v) = v {
-                    *value = v
-                }
-                *value
-            })
+    pub(crate) fn normalized(&mut self) -> f64 {
+        let value = self.get();
+        self.normalized_from_value(value)
+    }
+
+    /// Always clamps.
+    pub(crate) fn value_from_normalized(&self, normalized: f64) -> f64 {
+        value_from_normalized(normalized, self.range(), &self.spec)
+    }
+
+    /// Always clamps.
+    pub(crate) fn normalized_from_value(&self, value: f64) -> f64 {
+        normalized_from_value(value, self.range(), &self.spec)
+    }
+
+    /// The value at this normalized position, or (with smart aim) a nice round value
+    /// within `aim_radius` of it. `aim_radius` is in the normalized range too.
+    pub(crate) fn aimed_value(&self, normalized: f64, aim_radius: f64) -> f64 {
+        if self.smart_aim {
+            crate::math::smart_aim::best_in_range_f64(
+                self.value_from_normalized(normalized - aim_radius),
+                self.value_from_normalized(normalized + aim_radius),
+            )
+        } else {
+            self.value_from_normalized(normalized)
         }
     }
 
-    pub fn u8(value: &'a mut u8, range: RangeInclusive<u8>) -> Self {
-        Self {
-            ..Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
-                if let Some(v) = v {
-                    *value = v.round() as u8
-                }
-                *value as f64
-            })
+    /// Format the value with the precision, or with as many decimals
+    /// as moving `aim_radius` (normalized) would change.
+    pub(crate) fn format(&mut self, aim_radius: f64) -> String {
+        let value = self.get();
+
+        if let Some(precision) = self.precision {
+            format_with_minimum_precision(value as f32, precision)
+        } else if value == 0.0 {
+            "0".to_owned()
+        } else {
+            // pick precision based upon how much moving the slider would change the value:
+            let normalized = self.normalized_from_value(value);
+            let left_value = self.value_from_normalized(normalized - aim_radius);
+            let right_value = self.value_from_normalized(normalized + aim_radius);
+            let range = (left_value - right_value).abs();
+            if range == 0.0 {
+                value.to_string()
+            } else {
+                let precision = ((-range.log10()).ceil().at_least(0.0) as usize).at_most(16);
+                format_with_minimum_precision(value as f32, precision)
+            }
         }
-        .integer()
     }
+}
 
-    pub fn i32(value: &'a mut i32, range: RangeInclusive<i32>) -> Self {
+/// Control a number by a horizontal slider.
+/// The range can include any numbers, and go from low-to-high or from high-to-low.
+pub struct Slider<'a> {
+    value: SliderValue<'a>,
+    // TODO: label: Option<Label>
+    text: Option<String>,
+    text_color: Option<Srgba>,
+}
+
+impl<'a> Slider<'a> {
+    fn new(value: SliderValue<'a>) -> Self {
         Self {
-            ..Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
-                if let Some(v) = v {
-                    *value = v.round() as i32
-                }
-                *value as f64
-            })
+            value,
+            text: None,
+            text_color: None,
         }
-        .integer()
+    }
+
+    pub fn from_get_set(
+        range: RangeInclusive<f64>,
+        get_set_value: impl 'a + FnMut(Option<f64>) -> f64,
+    ) -> Self {
+        Self::new(SliderValue::from_get_set(range, get_set_value))
+    }
+
+    pub fn f32(value: &'a mut f32, range: RangeInclusive<f32>) -> Self {
+        Self::new(SliderValue::f32(value, range))
+    }
+
+    pub fn f64(value: &'a mut f64, range: RangeInclusive<f64>) -> Self {
+        Self::new(SliderValue::f64(value, range))
+    }
+
+    pub fn u8(value: &'a mut u8, range: RangeInclusive<u8>) -> Self {
+        Self::new(SliderValue::u8(value, range))
+    }
+
+    pub fn i32(value: &'a mut i32, range: RangeInclusive<i32>) -> Self {
+        Self::new(SliderValue::i32(value, range))
     }
 
     pub fn u32(value: &'a mut u32, range: RangeInclusive<u32>) -> Self {
-        Self {
-            ..Self::from_get_set(to_f64_range(range), move |v: Option<f64>| {
-                if let Some(v) = v {
-                    *value = v.round() as u32
-                }
-                *value as f64
-            })
-        }
-        .integer()
+        Self::new(SliderValue::u32(value, range))
     }
 
     pub fn usize(value: &'a mut usize, range: RangeInclusive<usize>) -> Self {
-        let range = (*range.start() as f64)..=(*range.end() as f64);
-        Self {
-            ..Self::from_get_set(range, move |v: Option<f64>| {
-                if let Some(v) = v {
-                    *value = v.round() as usize
-                }
-                *value as f64
-            })
-        }
-        .integer()
+        Self::new(SliderValue::usize(value, range))
     }
 
     pub fn text(mut self, text: impl Into<String>) -> Self {
@@ -153,7 +277,7 @@ impl<'a> Slider<'a> {
     /// e.g. from one to a million.
     /// The default is OFF.
     pub fn logarithmic(mut self, logarithmic: bool) -> Self {
-        self.spec.logarithmic = logarithmic;
+        self.value = self.value.logarithmic(logarithmic);
         self
     }
 
@@ -161,14 +285,14 @@ impl<'a> Slider<'a> {
     /// what is the smallest positive value you want to be able to select?
     /// The default is `1` for integer sliders and `1e-6` for real sliders.
     pub fn smallest_positive(mut self, smallest_positive: f64) -> Self {
-        self.spec.smallest_positive = smallest_positive;
+        self.value = self.value.smallest_positive(smallest_positive);
         self
     }
 
     /// Turn smart aim on/off. Default is ON.
     /// There is almost no point in turning this off.
     pub fn smart_aim(mut self, smart_aim: bool) -> Self {
-        self.smart_aim = smart_aim;
+        self.value = self.value.smart_aim(smart_aim);
         self
     }
 
@@ -177,7 +301,7 @@ impl<'a> Slider<'a> {
     /// Normally you don't need to pick a precision, as the slider will intelligently pick a precision for you.
     /// Regardless of precision the slider will use "smart aim" to help the user select nice, round values.
     pub fn precision(mut self, precision: usize) -> Self {
-        self.precision = Some(precision);
+        self.value = self.value.precision(precision);
         self
     }
 
@@ -189,28 +313,15 @@ impl<'a> Slider<'a> {
     }
 
     fn get_value(&mut self) -> f64 {
-        get(&mut self.get_set_value)
-    }
-
-    fn set_value(&mut self, mut value: f64) {
-        if let Some(precision) = self.precision {
-            value = round_to_precision(value, precision);
-        }
-        set(&mut self.get_set_value, value);
-    }
-
-    fn range(&self) -> RangeInclusive<f64> {
-        self.range.clone()
+        self.value.get()
     }
 
-    /// For instance, `x` is the mouse position and `x_range` is the physical location of the slider on the screen.
-    fn value_from_x(&self, x: f32, x_range: RangeInclusive<f32>) -> f64 {
-        let normalized = remap_clamp(x, x_range, 0.0..=1.0) as f64;
-        value_from_normalized(normalized, self.range(), &self.spec)
+    fn set_value(&mut self, value: f64) {
+        self.value.set(value);
     }
 
     fn x_from_value(&self, value: f64, x_range: RangeInclusive<f32>) -> f32 {
-        let normalized = normalized_from_value(value, self.range(), &self.spec);
+        let normalized = self.value.normalized_from_value(value);
         lerp(x_range, normalized as f32)
     }
 }
@@ -224,6 +335,11 @@ fn x_range(rect: &Rect) -> RangeInclusive<f32> {
     (rect.left() + handle_radius)..=(rect.right() - handle_radius)
 }
 
+/// How far the mouse can be off, as a fraction of the slider.
+fn normalized_aim_radius(ui: &Ui, x_range: RangeInclusive<f32>) -> f64 {
+    (ui.input().aim_radius() / (x_range.end() - x_range.start())) as f64
+}
+
 impl<'a> Slider<'a> {
     /// Just the slider, no text
     fn allocate_slide_space(&self, ui: &mut Ui, height: f32) -> Response {
@@ -240,15 +356,9 @@ impl<'a> Slider<'a> {
 
         if let Some(mouse_pos) = ui.input().mouse.pos {
             if response.active {
-                let new_value = if self.smart_aim {
-                    let aim_radius = ui.input().aim_radius();
-                    crate::math::smart_aim::best_in_range_f64(
-                        self.value_from_x(mouse_pos.x - aim_radius, x_range.clone()),
-                        self.value_from_x(mouse_pos.x + aim_radius, x_range.clone()),
-                    )
-                } else {
-                    self.value_from_x(mouse_pos.x, x_range.clone())
-                };
+                let normalized = remap(mouse_pos.x, x_range.clone(), 0.0..=1.0) as f64;
+                let aim_radius = normalized_aim_radius(ui, x_range.clone());
+                let new_value = self.value.aimed_value(normalized, aim_radius);
                 self.set_value(new_value);
             }
         }
@@ -266,8 +376,8 @@ impl<'a> Slider<'a> {
 
             ui.painter().add(PaintCmd::Rect {
                 rect: rail_rect,
-                corner_radius: rail_radius,
-                fill: ui.style().visuals.widgets.inactive.bg_fill,
+                corner_radius: rail_radius.into(),
+                fill: ui.style().visuals.widgets.inactive.bg_fill.into(),
                 stroke: ui.style().visuals.widgets.inactive.bg_stroke,
             });
 
@@ -298,8 +408,8 @@ impl<'a> Slider<'a> {
         let kb_edit_id = ui.make_position_id().with("edit");
         let is_kb_editing = ui.memory().has_kb_focus(kb_edit_id);
 
-        let aim_radius = ui.input().aim_radius();
-        let value_text = self.format_value(aim_radius, x_range);
+        let aim_radius = normalized_aim_radius(ui, x_range);
+        let value_text = self.value.format(aim_radius);
 
         if is_kb_editing {
             let button_width = ui.style().spacing.interact_size.x;
@@ -338,29 +448,6 @@ impl<'a> Slider<'a> {
             }
         }
     }
-
-    fn format_value(&mut self, aim_radius: f32, x_range: RangeInclusive<f32>) -> String {
-        let value = self.get_value();
-
-        if let Some(precision) = self.precision {
-            format_with_minimum_precision(value as f32, precision)
-        } else if value == 0.0 {
-            "0".to_owned()
-        } else {
-            // pick precision based upon how much moving the slider would change the value:
-            let value_from_x = |x: f32| self.value_from_x(x, x_range.clone());
-            let x_from_value = |value: f64| self.x_from_value(value, x_range.clone());
-            let left_value = value_from_x(x_from_value(value) - aim_radius);
-            let right_value = value_from_x(x_from_value(value) + aim_radius);
-            let range = (left_value - right_value).abs();
-            if range == 0.0 {
-                value.to_string()
-            } else {
-                let precision = ((-range.log10()).ceil().at_least(0.0) as usize).at_most(16);
-                format_with_minimum_precision(value as f32, precision)
-            }
-        }
-    }
 }
 
 impl<'a> Widget for Slider<'a> {
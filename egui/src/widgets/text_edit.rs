@@ -1,11 +1,37 @@
 use crate::{paint::*, *};
 
-#[derive(Clone, Copy, Debug, Default)]
+use super::text_layout::{byte_index, ParagraphCache, TextLayout};
+
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub(crate) struct State {
-    /// Character based, NOT bytes.
-    /// TODO: store as line + row
+    /// Byte offset into the text, always on a character boundary.
     pub cursor: Option<usize>,
+
+    /// Where the selection started, if any text is selected.
+    /// The selection spans from here to `cursor`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub select_from: Option<usize>,
+
+    /// The text of an ongoing IME composition, shown at the cursor.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub preedit: Option<String>,
+
+    /// The x position we try to keep when moving the cursor up and down.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cursor_x: Option<f32>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub paragraph_cache: ParagraphCache,
+
+    /// Index of the suggestion selected with the arrow keys, if any.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub selected_suggestion: Option<usize>,
+
+    /// Where the suggestions were shown last frame (in screen coordinates),
+    /// so clicking them does not lose focus.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub suggestions_rect: Option<Rect>,
 }
 
 /// A text region that the user can edit the contents of.
@@ -20,7 +46,6 @@ pub(crate) struct State {
 ///     // use my_string
 /// }
 /// ```
-#[derive(Debug)]
 pub struct TextEdit<'t> {
     text: &'t mut String,
     id: Option<Id>,
@@ -30,6 +55,41 @@ pub struct TextEdit<'t> {
     multiline: bool,
     enabled: bool,
     desired_width: Option<f32>,
+    password: bool,
+    hint_text: String,
+    char_limit: usize,
+    filter: Option<fn(char) -> bool>,
+    frame: bool,
+    code_editing: bool,
+    layouter: Option<Layouter<'t>>,
+    suggestions: Option<Suggestions<'t>>,
+}
+
+/// Lays out the given text, wrapping it at the given width.
+pub(crate) type Layouter<'t> = &'t dyn Fn(&Ui, &str, f32) -> font::Galley;
+
+/// Returns the candidates to suggest for the given text.
+pub type Suggestions<'t> = &'t dyn Fn(&str) -> Vec<String>;
+
+impl<'t> std::fmt::Debug for TextEdit<'t> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextEdit")
+            .field("text", &self.text)
+            .field("id", &self.id)
+            .field("id_source", &self.id_source)
+            .field("text_style", &self.text_style)
+            .field("text_color", &self.text_color)
+            .field("multiline", &self.multiline)
+            .field("enabled", &self.enabled)
+            .field("desired_width", &self.desired_width)
+            .field("password", &self.password)
+            .field("hint_text", &self.hint_text)
+            .field("char_limit", &self.char_limit)
+            .field("frame", &self.frame)
+            .field("code_editing", &self.code_editing)
+            .field("suggestions", &self.suggestions.is_some())
+            .finish()
+    }
 }
 
 impl<'t> TextEdit<'t> {
@@ -43,6 +103,14 @@ impl<'t> TextEdit<'t> {
             multiline: true,
             enabled: true,
             desired_width: None,
+            password: false,
+            hint_text: Default::default(),
+            char_limit: usize::MAX,
+            filter: None,
+            frame: true,
+            code_editing: false,
+            layouter: None,
+            suggestions: None,
         }
     }
 
@@ -87,6 +155,72 @@ impl<'t> TextEdit<'t> {
         self.desired_width = Some(desired_width);
         self
     }
+
+    /// If `true`, hide the text behind bullets and refuse to copy or cut it.
+    pub fn password(mut self, password: bool) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// Show this greyed out text when the `TextEdit` is empty.
+    pub fn hint_text(mut self, hint_text: impl Into<String>) -> Self {
+        self.hint_text = hint_text.into();
+        self
+    }
+
+    /// Do not allow the text to be longer than this many characters.
+    pub fn char_limit(mut self, char_limit: usize) -> Self {
+        self.char_limit = char_limit;
+        self
+    }
+
+    /// Only allow typing or pasting characters for which the filter returns `true`.
+    ///
+    /// ```
+    /// # let mut ui = egui::Ui::__test();
+    /// # let mut id = String::new();
+    /// ui.add(egui::TextEdit::new(&mut id).filter(|c| c.is_ascii_digit()));
+    /// ```
+    pub fn filter(mut self, filter: fn(char) -> bool) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Default is `true`. If set to `false` no background or outline is painted.
+    pub(crate) fn frame(mut self, frame: bool) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Auto-indent on enter, and indent/outdent with tab/shift+tab.
+    pub(crate) fn code_editing(mut self, code_editing: bool) -> Self {
+        self.code_editing = code_editing;
+        self
+    }
+
+    /// Replace the default layout of the text, e.g. to add syntax highlighting.
+    /// Is called with the text and the width to wrap it at.
+    pub(crate) fn layouter(mut self, layouter: Layouter<'t>) -> Self {
+        self.layouter = Some(layouter);
+        self
+    }
+
+    /// Show a list of suggestions below the text while editing it.
+    ///
+    /// The given function is called with the current text and returns the candidates.
+    /// Only the candidates containing the text (ignoring case) are shown, with the match highlighted.
+    /// Use the arrow keys to select a suggestion, and Enter or Tab to accept it.
+    ///
+    /// ```
+    /// # let mut ui = egui::Ui::__test();
+    /// # let mut command = String::new();
+    /// let commands = |_: &str| vec!["open".to_owned(), "close".to_owned(), "quit".to_owned()];
+    /// ui.add(egui::TextEdit::new(&mut command).multiline(false).suggestions(&commands));
+    /// ```
+    pub fn suggestions(mut self, suggestions: Suggestions<'t>) -> Self {
+        self.suggestions = Some(suggestions);
+        self
+    }
 }
 
 impl<'t> Widget for TextEdit<'t> {
@@ -100,23 +234,71 @@ impl<'t> Widget for TextEdit<'t> {
             multiline,
             enabled,
             desired_width,
+            password,
+            hint_text,
+            char_limit,
+            filter,
+            frame,
+            code_editing,
+            layouter,
+            suggestions,
         } = self;
 
         let desired_width = desired_width.unwrap_or_else(|| ui.style().spacing.text_edit_width);
+        let filter = filter.unwrap_or(|_| true);
 
         let id = id.unwrap_or_else(|| ui.make_persistent_id(id_source));
 
-        let mut state = ui.memory().text_edit.get(&id).cloned().unwrap_or_default();
+        let mut state = ui.memory().text_edit.remove(&id).unwrap_or_default();
 
         let text_style = text_style.unwrap_or_else(|| ui.style().body_text_style);
-        let font = &ui.fonts()[text_style];
-        let line_spacing = font.line_spacing();
+        let line_spacing = ui.fonts()[text_style].line_spacing();
         let available_width = ui.available().width();
-        let mut galley = if multiline {
-            font.layout_multiline(text.clone(), available_width)
-        } else {
-            font.layout_single_line(text.clone())
+        // Only the visible part of a long text needs to be laid out, e.g. in a `ScrollArea`:
+        let text_top = ui.available().min.y;
+        let visible_y = ui.clip_rect().min.y - text_top..ui.clip_rect().max.y - text_top;
+        let layout = |ui: &Ui, text: &str, cursor: Option<usize>, cache: &mut ParagraphCache| {
+            if password {
+                let bullets = PASSWORD_CHAR.to_string().repeat(text.chars().count());
+                let font = &ui.fonts()[text_style];
+                let galley = if multiline {
+                    font.layout_multiline(bullets, available_width)
+                } else {
+                    font.layout_single_line(bullets)
+                };
+                TextLayout::from_galley(text, galley)
+            } else if let Some(layouter) = layouter {
+                let wrap_width = if multiline {
+                    available_width
+                } else {
+                    f32::INFINITY
+                };
+                TextLayout::from_galley(text, layouter(ui, text, wrap_width))
+            } else if multiline {
+                TextLayout::from_paragraphs(
+                    ui.fonts(),
+                    text_style,
+                    text,
+                    available_width,
+                    visible_y.clone(),
+                    cursor,
+                    cache,
+                )
+            } else {
+                let galley = ui.fonts()[text_style].layout_single_line(text.to_owned());
+                TextLayout::from_galley(text, galley)
+            }
         };
+
+        // The text may have been changed from the outside:
+        for cursor in state.cursor.iter_mut().chain(&mut state.select_from) {
+            *cursor = (*cursor).min(text.len());
+            while !text.is_char_boundary(*cursor) {
+                *cursor -= 1;
+            }
+        }
+
+        let mut galley = layout(ui, text, state.cursor, &mut state.paragraph_cache);
         let desired_size = vec2(
             galley.size.x.max(desired_width.min(available_width)),
             galley.size.y.max(line_spacing),
@@ -127,14 +309,21 @@ impl<'t> Widget for TextEdit<'t> {
         } else {
             Sense::nothing()
         };
-        let response = ui.interact(rect, id, sense); // TODO: implement drag-select
+        let response = ui.interact(rect, id, sense);
 
-        if response.clicked && enabled {
-            ui.memory().request_kb_focus(id);
+        if response.active && enabled {
+            // Click to move the cursor, drag to select:
             if let Some(mouse_pos) = ui.input().mouse.pos {
-                state.cursor = Some(galley.char_at(mouse_pos - response.rect.min).char_idx);
+                let cursor = galley.cursor_at(text, mouse_pos - response.rect.min);
+                if ui.input().mouse.pressed {
+                    ui.memory().request_kb_focus(id);
+                    state.select_from = Some(cursor);
+                }
+                state.cursor = Some(cursor);
             }
-        } else if ui.input().mouse.click || (ui.input().mouse.pressed && !response.hovered) {
+        } else if (ui.input().mouse.click || (ui.input().mouse.pressed && !response.hovered))
+            && !is_mouse_over(ui, state.suggestions_rect)
+        {
             // User clicked somewhere else
             ui.memory().surrender_kb_focus(id);
         }
@@ -146,20 +335,107 @@ impl<'t> Widget for TextEdit<'t> {
             ui.output().cursor_icon = CursorIcon::Text;
         }
 
+        let mut text_with_preedit = None;
         if ui.memory().has_kb_focus(id) && enabled {
-            let mut cursor = state.cursor.unwrap_or_else(|| text.chars().count());
-            cursor = clamp(cursor, 0..=text.chars().count());
+            let mut cursor = state.cursor.unwrap_or(text.len());
+            let mut select_from = state.select_from;
+            let mut suggested = suggestions.map_or(vec![], |suggestions| {
+                matching_suggestions(suggestions(text), text)
+            });
+            // There may be fewer suggestions than when one was selected:
+            state.selected_suggestion = state.selected_suggestion.filter(|&i| i < suggested.len());
+
+            for (event, modifiers) in ui.input().events_with_modifiers() {
+                let keeps_cursor_x = matches!(
+                    event,
+                    Event::Key { key, .. } if is_vertical_movement(*key) || is_modifier(*key)
+                );
+                if !keeps_cursor_x {
+                    state.cursor_x = None;
+                }
+                let edits_text = matches!(
+                    event,
+                    Event::Text(_)
+                        | Event::Key {
+                            key: Key::Backspace,
+                            ..
+                        }
+                        | Event::Key {
+                            key: Key::Delete,
+                            ..
+                        }
+                );
+                if edits_text {
+                    state.selected_suggestion = None;
+                }
+                // In a multiline editor the arrow keys move between rows,
+                // until a suggestion has been highlighted with the mouse:
+                let navigates_suggestions =
+                    !suggested.is_empty() && (!multiline || state.selected_suggestion.is_some());
 
-            for event in &ui.input().events {
                 match event {
-                    Event::Copy | Event::Cut => {
-                        // TODO: cut
-                        ui.ctx().output().copied_text = text.clone();
+                    Event::Key {
+                        key: Key::Down,
+                        pressed: true,
+                    } if navigates_suggestions => {
+                        let last = suggested.len() - 1;
+                        state.selected_suggestion =
+                            Some(state.selected_suggestion.map_or(0, |i| (i + 1).min(last)));
+                    }
+                    Event::Key {
+                        key: Key::Up,
+                        pressed: true,
+                    } if navigates_suggestions => {
+                        state.selected_suggestion =
+                            state.selected_suggestion.and_then(|i| i.checked_sub(1));
+                    }
+                    Event::Key {
+                        key: Key::Enter,
+                        pressed: true,
+                    } if state.selected_suggestion.is_some() => {
+                        let selected = state.selected_suggestion.and_then(|i| suggested.get(i));
+                        if let Some((suggestion, _)) = selected {
+                            *text = suggestion.clone();
+                        }
+                        cursor = text.len();
+                        select_from = None;
+                        state.selected_suggestion = None;
+                        suggested.clear();
+                    }
+                    Event::Key {
+                        key: Key::Tab,
+                        pressed: true,
+                    } if !suggested.is_empty() => {
+                        let selected = suggested.get(state.selected_suggestion.unwrap_or(0));
+                        if let Some((suggestion, _)) = selected {
+                            *text = suggestion.clone();
+                        }
+                        cursor = text.len();
+                        select_from = None;
+                        state.selected_suggestion = None;
+                        suggested.clear();
+                    }
+                    Event::Copy | Event::Cut if !password => {
+                        match selection(cursor, select_from) {
+                            Some(selected) => {
+                                ui.ctx().output().copied_text = text[selected].to_owned();
+                                if event == &Event::Cut {
+                                    delete_selection(&mut cursor, &mut select_from, text);
+                                }
+                            }
+                            None => {
+                                // TODO: cut
+                                ui.ctx().output().copied_text = text.clone();
+                            }
+                        }
                     }
                     Event::Text(text_to_insert) => {
                         // newlines are handled by `Key::Enter`.
                         if text_to_insert != "\n" && text_to_insert != "\r" {
-                            insert_text(&mut cursor, text, text_to_insert);
+                            delete_selection(&mut cursor, &mut select_from, text);
+                            let filtered: String =
+                                text_to_insert.chars().filter(|&c| filter(c)).collect();
+                            insert_text_limited(&mut cursor, text, &filtered, char_limit);
                         }
                     }
                     Event::Key {
@@ -167,7 +443,14 @@ impl<'t> Widget for TextEdit<'t> {
                         pressed: true,
                     } => {
                         if multiline {
-                            insert_text(&mut cursor, text, "\n");
+                            delete_selection(&mut cursor, &mut select_from, text);
+                        }
+                        if multiline && code_editing {
+                            let indent = auto_indent(text, cursor);
+                            let new_line = format!("\n{}", indent);
+                            insert_text_limited(&mut cursor, text, &new_line, char_limit);
+                        } else if multiline {
+                            insert_text_limited(&mut cursor, text, "\n", char_limit);
                         } else {
                             // Common to end input with enter
                             ui.memory().surrender_kb_focus(id);
@@ -181,21 +464,87 @@ impl<'t> Widget for TextEdit<'t> {
                         ui.memory().surrender_kb_focus(id);
                         break;
                     }
+                    Event::Key {
+                        key: Key::Tab,
+                        pressed: true,
+                    } if code_editing => match &mut select_from {
+                        Some(select_from) if *select_from != cursor => {
+                            if modifiers.shift {
+                                outdent_lines(&mut cursor, select_from, text);
+                            } else {
+                                indent_lines(&mut cursor, select_from, text);
+                            }
+                        }
+                        _ => {
+                            if modifiers.shift {
+                                outdent_line(&mut cursor, text);
+                            } else {
+                                indent_at_cursor(&mut cursor, text);
+                            }
+                        }
+                    },
+                    Event::Key { key, pressed: true } if is_vertical_movement(*key) => {
+                        update_selection(&mut select_from, cursor, modifiers.shift);
+                        galley = layout(ui, text, Some(cursor), &mut state.paragraph_cache);
+                        let page_rows = (ui.clip_rect().height() / line_spacing).floor().max(1.0);
+                        let num_rows = match key {
+                            Key::Up => -1,
+                            Key::Down => 1,
+                            Key::PageUp => -(page_rows as isize),
+                            _ => page_rows as isize,
+                        };
+                        let x = *state
+                            .cursor_x
+                            .get_or_insert_with(|| galley.cursor_pos(text, cursor).x);
+                        cursor = galley.move_rows(text, cursor, x, num_rows);
+                    }
                     Event::Key { key, pressed: true } => {
-                        on_key_press(&mut cursor, text, *key);
+                        let is_deletion = matches!(key, Key::Backspace | Key::Delete);
+                        if is_deletion && delete_selection(&mut cursor, &mut select_from, text) {
+                            // Only the selected text is deleted
+                        } else {
+                            if is_horizontal_movement(*key) {
+                                update_selection(&mut select_from, cursor, modifiers.shift);
+                            }
+                            on_key_press(&mut cursor, text, *key, modifiers);
+                        }
+                    }
+                    Event::CompositionStart => {
+                        state.preedit = Some(String::new());
+                    }
+                    Event::CompositionUpdate(preedit) => {
+                        state.preedit = Some(preedit.clone());
+                    }
+                    Event::CompositionEnd(committed) => {
+                        state.preedit = None;
+                        delete_selection(&mut cursor, &mut select_from, text);
+                        let filtered: String = committed.chars().filter(|&c| filter(c)).collect();
+                        insert_text_limited(&mut cursor, text, &filtered, char_limit);
                     }
                     _ => {}
                 }
             }
             state.cursor = Some(cursor);
+            state.select_from = select_from;
 
             // layout again to avoid frame delay:
-            let font = &ui.fonts()[text_style];
-            galley = if multiline {
-                font.layout_multiline(text.clone(), available_width)
-            } else {
-                font.layout_single_line(text.clone())
-            };
+            if let Some(preedit) = state.preedit.as_ref().filter(|p| !p.is_empty()) {
+                let mut shown_text = text.clone();
+                shown_text.insert_str(cursor, preedit);
+                text_with_preedit = Some(shown_text);
+            }
+            let shown_text = text_with_preedit.as_deref().unwrap_or(text);
+            let shown_cursor = cursor + state.preedit.as_ref().map_or(0, |p| p.len());
+            galley = layout(
+                ui,
+                shown_text,
+                Some(shown_cursor),
+                &mut state.paragraph_cache,
+            );
+        } else {
+            state.preedit = None;
+            state.select_from = None;
+            state.selected_suggestion = None;
 
             // dbg!(&galley);
         }
@@ -203,17 +552,21 @@ impl<'t> Widget for TextEdit<'t> {
         let painter = ui.painter();
         let visuals = ui.style().interact(&response);
 
-        {
+        if frame {
             let bg_rect = response.rect.expand(2.0); // breathing room for content
             painter.add(PaintCmd::Rect {
                 rect: bg_rect,
-                corner_radius: visuals.corner_radius,
-                fill: ui.style().visuals.dark_bg_color,
+                corner_radius: visuals.corner_radius.into(),
+                fill: ui.style().visuals.dark_bg_color.into(),
                 // fill: visuals.bg_fill,
                 stroke: visuals.bg_stroke,
             });
         }
 
+        let text_color = text_color
+            .or(ui.style().visuals.override_text_color)
+            .unwrap_or_else(|| visuals.text_color());
+
         if ui.memory().has_kb_focus(id) {
             let cursor_blink_hz = ui.style().visuals.cursor_blink_hz;
             let show_cursor = if 0.0 < cursor_blink_hz {
@@ -223,21 +576,66 @@ impl<'t> Widget for TextEdit<'t> {
                 true
             };
 
-            if show_cursor {
-                if let Some(cursor) = state.cursor {
-                    let cursor_pos = response.rect.min + galley.char_start_pos(cursor);
+            if let Some(cursor) = state.cursor {
+                let shown_text = text_with_preedit.as_deref().unwrap_or(text);
+                let preedit_len = state.preedit.as_ref().map_or(0, |p| p.len());
+                if let (Some(selected), 0) = (selection(cursor, state.select_from), preedit_len) {
+                    let selection_color = ui.style().visuals.widgets.hovered.bg_fill;
+                    galley.highlight(painter, response.rect.min, text, selected, selection_color);
+                }
+                if preedit_len > 0 {
+                    let preedit_range = cursor..cursor + preedit_len;
+                    galley.underline(
+                        painter,
+                        response.rect.min,
+                        shown_text,
+                        preedit_range,
+                        text_color,
+                    );
+                }
+
+                let cursor_pos =
+                    response.rect.min + galley.cursor_pos(shown_text, cursor + preedit_len);
+                let cursor_rect = Rect::from_min_size(cursor_pos, vec2(0.0, line_spacing));
+                // Backends put the IME candidate window here, so it must be on the screen:
+                ui.output().text_cursor_rect = Some(ui.to_screen_rect(cursor_rect));
+
+                if show_cursor {
                     painter.line_segment(
-                        [cursor_pos, cursor_pos + vec2(0.0, line_spacing)],
+                        [cursor_rect.left_top(), cursor_rect.left_bottom()],
                         (ui.style().visuals.text_cursor_width, color::WHITE),
                     );
                 }
             }
         }
 
-        let text_color = text_color
-            .or(ui.style().visuals.override_text_color)
-            .unwrap_or_else(|| visuals.text_color());
-        painter.galley(response.rect.min, galley, text_style, text_color);
+        if text.is_empty() && !hint_text.is_empty() {
+            let font = &ui.fonts()[text_style];
+            let hint_galley = if multiline {
+                font.layout_multiline(hint_text, available_width)
+            } else {
+                font.layout_single_line(hint_text)
+            };
+            let hint_color = ui.style().visuals.widgets.disabled.text_color();
+            painter.galley(response.rect.min, hint_galley, text_style, hint_color);
+        }
+        galley.paint(painter, response.rect.min, text_style, text_color);
+
+        state.suggestions_rect = None;
+        let has_kb_focus = ui.memory().has_kb_focus(id);
+        if let (Some(suggestions), true) = (suggestions, has_kb_focus) {
+            let suggested = matching_suggestions(suggestions(text), text);
+            if !suggested.is_empty() {
+                let below = ui.to_screen_rect(response.rect.expand(2.0)); // same as the frame
+                if let Some(accepted) = show_suggestions(ui, id, below, &suggested, &mut state) {
+                    *text = suggested[accepted].0.clone();
+                    state.cursor = Some(text.len());
+                    state.select_from = None;
+                    state.selected_suggestion = None;
+                }
+            }
+        }
+
         ui.memory().text_edit.insert(id, state);
 
         Response {
@@ -247,72 +645,403 @@ impl<'t> Widget for TextEdit<'t> {
     }
 }
 
+/// The most suggestions we show at once. Type more to narrow them down.
+const MAX_SUGGESTIONS: usize = 10;
+
+/// A suggestion, and the byte range in it that matches the text (if any).
+type Suggestion = (String, Option<std::ops::Range<usize>>);
+
+/// The candidates containing the text (ignoring case), except the text itself.
+fn matching_suggestions(candidates: Vec<String>, text: &str) -> Vec<Suggestion> {
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate != text)
+        .filter_map(|candidate| {
+            if text.is_empty() {
+                Some((candidate, None))
+            } else {
+                let matched = find_ignoring_case(&candidate, text)?;
+                Some((candidate, Some(matched)))
+            }
+        })
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+/// Byte range of the first occurrence of `needle` in `haystack`, ignoring case.
+pub(crate) fn find_ignoring_case(haystack: &str, needle: &str) -> Option<std::ops::Range<usize>> {
+    let lowercase = |s: &str| {
+        s.chars()
+            .flat_map(char::to_lowercase)
+            .collect::<Vec<char>>()
+    };
+    let needle = lowercase(needle);
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut num_chars = 0;
+        let mut matched = vec![];
+        for c in haystack[start..].chars() {
+            if matched.len() >= needle.len() {
+                break;
+            }
+            matched.extend(c.to_lowercase());
+            num_chars += 1;
+        }
+        if matched == needle {
+            let end = start + byte_index(&haystack[start..], num_chars);
+            Some(start..end)
+        } else {
+            None
+        }
+    })
+}
+
+/// `rect` is in screen coordinates.
+fn is_mouse_over(ui: &Ui, rect: Option<Rect>) -> bool {
+    match (rect, ui.ctx().input().mouse.pos) {
+        (Some(rect), Some(mouse_pos)) => rect.contains(mouse_pos),
+        _ => false,
+    }
+}
+
+/// Show the suggestions in a popup just below the given rectangle, in screen coordinates.
+/// Returns the index of the suggestion that was clicked, if any.
+fn show_suggestions(
+    ui: &Ui,
+    id: Id,
+    below: Rect,
+    suggested: &[Suggestion],
+    state: &mut State,
+) -> Option<usize> {
+    let mut accepted = None;
+    let parent_clip_rect = ui.to_screen_rect(ui.clip_rect());
+
+    let response = Area::new(id.with("suggestions"))
+        .order(Order::Foreground)
+        .fixed_pos(below.left_bottom())
+        .show(ui.ctx(), |ui| {
+            ui.set_clip_rect(parent_clip_rect); // for when the text edit is in a scroll area.
+            let frame = Frame::popup(ui.style());
+            let frame_margin = frame.margin;
+            frame.show(ui, |ui| {
+                ui.with_layout(Layout::justified(Direction::Vertical), |ui| {
+                    ui.set_min_width(below.width() - 2.0 * frame_margin.x);
+                    for (i, (suggestion, matched)) in suggested.iter().enumerate() {
+                        let mut rich_text = RichText::new();
+                        match matched {
+                            Some(matched) => {
+                                let highlight = ui.style().visuals.widgets.active.text_color();
+                                rich_text = rich_text
+                                    .span(&suggestion[..matched.start])
+                                    .span(
+                                        TextSpan::new(&suggestion[matched.clone()])
+                                            .text_color(highlight)
+                                            .underline(),
+                                    )
+                                    .span(&suggestion[matched.end..]);
+                            }
+                            None => {
+                                rich_text = rich_text.span(suggestion.as_str());
+                            }
+                        }
+
+                        let where_to_put_background = ui.painter().add(PaintCmd::Noop);
+                        let rect = ui.add(rich_text).rect;
+                        let response = ui.interact(rect, id.with(i), Sense::click());
+                        if response.hovered && ui.input().mouse.delta != Vec2::zero() {
+                            // The mouse takes over from the arrow keys:
+                            state.selected_suggestion = Some(i);
+                        }
+                        if state.selected_suggestion == Some(i) {
+                            let visuals = ui.style().visuals.widgets.hovered;
+                            ui.painter().set(
+                                where_to_put_background,
+                                PaintCmd::Rect {
+                                    rect: rect.expand(1.0),
+                                    corner_radius: visuals.corner_radius.into(),
+                                    fill: visuals.bg_fill.into(),
+                                    stroke: Default::default(),
+                                },
+                            );
+                        }
+                        if response.clicked {
+                            accepted = Some(i);
+                        }
+                    }
+                });
+            })
+        });
+
+    state.suggestions_rect = Some(response.rect);
+    accepted
+}
+
+/// The character shown instead of each character of a password.
+const PASSWORD_CHAR: char = '•';
+
+/// Like `insert_text`, but only inserts as much as fits within `char_limit`.
+fn insert_text_limited(
+    cursor: &mut usize,
+    text: &mut String,
+    text_to_insert: &str,
+    char_limit: usize,
+) {
+    if char_limit == usize::MAX {
+        insert_text(cursor, text, text_to_insert);
+    } else {
+        let room = char_limit.saturating_sub(text.chars().count());
+        let text_to_insert = &text_to_insert[..byte_index(text_to_insert, room)];
+        insert_text(cursor, text, text_to_insert);
+    }
+}
+
 fn insert_text(cursor: &mut usize, text: &mut String, text_to_insert: &str) {
     // eprintln!("insert_text {:?}", text_to_insert);
+    text.insert_str(*cursor, text_to_insert);
+    *cursor += text_to_insert.len();
+}
+
+/// Byte offset of the start of the paragraph containing the cursor.
+fn line_start(text: &str, cursor: usize) -> usize {
+    text[..cursor].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+/// Byte offset of the end of the paragraph containing the cursor (before its `\n`).
+fn line_end(text: &str, cursor: usize) -> usize {
+    text[cursor..]
+        .find('\n')
+        .map_or(text.len(), |newline| cursor + newline)
+}
 
-    let mut char_it = text.chars();
-    let mut new_text = String::with_capacity(text.capacity());
-    for _ in 0..*cursor {
-        let c = char_it.next().unwrap();
-        new_text.push(c);
+fn prev_char_boundary(text: &str, cursor: usize) -> usize {
+    text[..cursor]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(byte_idx, _)| byte_idx)
+}
+
+fn next_char_boundary(text: &str, cursor: usize) -> usize {
+    text[cursor..]
+        .chars()
+        .next()
+        .map_or(cursor, |c| cursor + c.len_utf8())
+}
+
+/// How many spaces a tab corresponds to when editing code.
+const CODE_INDENT_WIDTH: usize = 4;
+
+/// Indentation for a new line inserted at the cursor:
+/// same as the current line, plus one level after an opening bracket.
+fn auto_indent(text: &str, cursor: usize) -> String {
+    let before_cursor = &text[line_start(text, cursor)..cursor];
+    let mut indent: String = before_cursor
+        .chars()
+        .take_while(|&c| c == ' ' || c == '\t')
+        .collect();
+    if before_cursor.trim_end().ends_with(&['{', '(', '['][..]) {
+        indent += &" ".repeat(CODE_INDENT_WIDTH);
     }
-    *cursor += text_to_insert.chars().count();
-    new_text += text_to_insert;
-    new_text.extend(char_it);
-    *text = new_text;
+    indent
 }
 
-fn on_key_press(cursor: &mut usize, text: &mut String, key: Key) {
-    // eprintln!("on_key_press before: '{}', cursor at {}", text, cursor);
+/// Insert spaces up to the next tab stop.
+fn indent_at_cursor(cursor: &mut usize, text: &mut String) {
+    let column = text[line_start(text, *cursor)..*cursor].chars().count();
+    let num_spaces = CODE_INDENT_WIDTH - column % CODE_INDENT_WIDTH;
+    insert_text(cursor, text, &" ".repeat(num_spaces));
+}
 
-    match key {
-        Key::Backspace if *cursor > 0 => {
-            *cursor -= 1;
+/// The selected byte range, if any text is selected.
+fn selection(cursor: usize, select_from: Option<usize>) -> Option<std::ops::Range<usize>> {
+    let select_from = select_from.filter(|&select_from| select_from != cursor)?;
+    Some(cursor.min(select_from)..cursor.max(select_from))
+}
+
+/// Moving the cursor with shift held extends the selection, and without it deselects.
+fn update_selection(select_from: &mut Option<usize>, cursor: usize, shift: bool) {
+    if !shift {
+        *select_from = None;
+    } else if select_from.is_none() {
+        *select_from = Some(cursor);
+    }
+}
+
+/// Delete the selected text, if any, and deselect.
+/// Returns `true` if any text was deleted.
+fn delete_selection(
+    cursor: &mut usize,
+    select_from: &mut Option<usize>,
+    text: &mut String,
+) -> bool {
+    match selection(*cursor, select_from.take()) {
+        Some(selected) => {
+            *cursor = selected.start;
+            text.replace_range(selected, "");
+            true
+        }
+        None => false,
+    }
+}
 
-            let mut char_it = text.chars();
-            let mut new_text = String::with_capacity(text.capacity());
-            for _ in 0..*cursor {
-                new_text.push(char_it.next().unwrap())
+/// Byte offsets of the starts of the lines touched by the selection,
+/// not counting the line the selection ends at the very start of.
+fn selected_line_starts(text: &str, cursor: usize, select_from: usize) -> Vec<usize> {
+    let (start, end) = (cursor.min(select_from), cursor.max(select_from));
+    let mut line_starts = vec![line_start(text, start)];
+    line_starts.extend(
+        text[start..end]
+            .match_indices('\n')
+            .map(|(newline, _)| start + newline + 1)
+            .filter(|&line_start| line_start < end),
+    );
+    line_starts
+}
+
+/// Indent all selected lines one level, keeping the same text selected.
+fn indent_lines(cursor: &mut usize, select_from: &mut usize, text: &mut String) {
+    let indent = " ".repeat(CODE_INDENT_WIDTH);
+    for line_start in selected_line_starts(text, *cursor, *select_from)
+        .into_iter()
+        .rev()
+    {
+        text.insert_str(line_start, &indent);
+        let moved = |offset: usize| {
+            if offset >= line_start {
+                offset + indent.len()
+            } else {
+                offset
             }
-            new_text.extend(char_it.skip(1));
-            *text = new_text;
+        };
+        *cursor = moved(*cursor);
+        *select_from = moved(*select_from);
+    }
+}
+
+/// Remove one level of indentation from all selected lines, keeping the same text selected.
+fn outdent_lines(cursor: &mut usize, select_from: &mut usize, text: &mut String) {
+    for line_start in selected_line_starts(text, *cursor, *select_from)
+        .into_iter()
+        .rev()
+    {
+        let num_bytes = indentation_to_remove(&text[line_start..]);
+        text.replace_range(line_start..line_start + num_bytes, "");
+        let moved = |offset: usize| {
+            if offset >= line_start {
+                offset - num_bytes.min(offset - line_start)
+            } else {
+                offset
+            }
+        };
+        *cursor = moved(*cursor);
+        *select_from = moved(*select_from);
+    }
+}
+
+/// How many bytes of indentation to remove from the start of the line to outdent it one level.
+fn indentation_to_remove(line: &str) -> usize {
+    if line.starts_with('\t') {
+        1
+    } else {
+        line.bytes()
+            .take(CODE_INDENT_WIDTH)
+            .take_while(|&b| b == b' ')
+            .count()
+    }
+}
+
+/// Remove one level of indentation from the start of the cursor line.
+fn outdent_line(cursor: &mut usize, text: &mut String) {
+    let line_start = line_start(text, *cursor);
+    let num_bytes = indentation_to_remove(&text[line_start..line_end(text, *cursor)]);
+    text.replace_range(line_start..line_start + num_bytes, "");
+    *cursor -= num_bytes.min(*cursor - line_start);
+}
+
+fn is_vertical_movement(key: Key) -> bool {
+    matches!(key, Key::Up | Key::Down | Key::PageUp | Key::PageDown)
+}
+
+fn is_horizontal_movement(key: Key) -> bool {
+    matches!(key, Key::Left | Key::Right | Key::Home | Key::End)
+}
+
+fn is_modifier(key: Key) -> bool {
+    matches!(key, Key::Alt | Key::Control | Key::Shift | Key::Logo)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Where the cursor ends up when jumping one word to the left.
+fn prev_word_boundary(text: &str, cursor: usize) -> usize {
+    text[..cursor]
+        .char_indices()
+        .rev()
+        .skip_while(|&(_, c)| !is_word_char(c))
+        .find(|&(_, c)| !is_word_char(c))
+        .map_or(0, |(byte_idx, c)| byte_idx + c.len_utf8())
+}
+
+/// Where the cursor ends up when jumping one word to the right.
+fn next_word_boundary(text: &str, cursor: usize) -> usize {
+    text[cursor..]
+        .char_indices()
+        .skip_while(|&(_, c)| !is_word_char(c))
+        .find(|&(_, c)| !is_word_char(c))
+        .map_or(text.len(), |(byte_idx, _)| cursor + byte_idx)
+}
+
+/// Ctrl+arrow keys (or Alt+arrow keys on Mac) move and delete whole words.
+fn on_key_press(cursor: &mut usize, text: &mut String, key: Key, modifiers: Modifiers) {
+    // eprintln!("on_key_press before: '{}', cursor at {}", text, cursor);
+
+    let word_wise = modifiers.ctrl || modifiers.alt;
+
+    match key {
+        Key::Backspace if word_wise => {
+            let word_start = prev_word_boundary(text, *cursor);
+            text.replace_range(word_start..*cursor, "");
+            *cursor = word_start;
+        }
+        Key::Delete if word_wise => {
+            let word_end = next_word_boundary(text, *cursor);
+            text.replace_range(*cursor..word_end, "");
+        }
+        Key::Backspace => {
+            let char_start = prev_char_boundary(text, *cursor);
+            text.replace_range(char_start..*cursor, "");
+            *cursor = char_start;
         }
         Key::Delete => {
-            let mut char_it = text.chars();
-            let mut new_text = String::with_capacity(text.capacity());
-            for _ in 0..*cursor {
-                new_text.push(char_it.next().unwrap())
-            }
-            new_text.extend(char_it.skip(1));
-            *text = new_text;
+            let char_end = next_char_boundary(text, *cursor);
+            text.replace_range(*cursor..char_end, "");
         }
         Key::Enter => {} // handled earlier
+        Key::Home if modifiers.ctrl => {
+            *cursor = 0;
+        }
+        Key::End if modifiers.ctrl => {
+            *cursor = text.len();
+        }
         Key::Home => {
             // To start of paragraph:
-            let pos = line_col_from_char_idx(text, *cursor);
-            *cursor = char_idx_from_line_col(text, (pos.0, 0));
+            *cursor = line_start(text, *cursor);
         }
         Key::End => {
             // To end of paragraph:
-            let pos = line_col_from_char_idx(text, *cursor);
-            let line = line_from_number(text, pos.0);
-            *cursor = char_idx_from_line_col(text, (pos.0, line.chars().count()));
+            *cursor = line_end(text, *cursor);
         }
-        Key::Left if *cursor > 0 => {
-            *cursor -= 1;
+        Key::Left if word_wise => {
+            *cursor = prev_word_boundary(text, *cursor);
         }
-        Key::Right => {
-            *cursor = (*cursor + 1).min(text.chars().count());
+        Key::Right if word_wise => {
+            *cursor = next_word_boundary(text, *cursor);
         }
-        Key::Up => {
-            let mut pos = line_col_from_char_idx(text, *cursor);
-            pos.0 = pos.0.saturating_sub(1);
-            *cursor = char_idx_from_line_col(text, pos);
+        Key::Left => {
+            *cursor = prev_char_boundary(text, *cursor);
         }
-        Key::Down => {
-            let mut pos = line_col_from_char_idx(text, *cursor);
-            pos.0 += 1;
-            *cursor = char_idx_from_line_col(text, pos);
+        Key::Right => {
+            *cursor = next_char_boundary(text, *cursor);
         }
         _ => {}
     }
@@ -320,41 +1049,289 @@ fn on_key_press(cursor: &mut usize, text: &mut String, key: Key) {
     // eprintln!("on_key_press after:  '{}', cursor at {}\n", text, cursor);
 }
 
-fn line_col_from_char_idx(s: &str, char_idx: usize) -> (usize, usize) {
-    let mut char_count = 0;
+#[test]
+fn test_code_editing() {
+    let mut text = "fn main() {".to_owned();
+    let mut cursor = text.len();
+    let indent = auto_indent(&text, cursor);
+    insert_text(&mut cursor, &mut text, &format!("\n{}", indent));
+    assert_eq!(text, "fn main() {\n    ");
 
-    let mut last_line_nr = 0;
-    let mut last_line = s;
-    for (line_nr, line) in s.split('\n').enumerate() {
-        let line_width = line.chars().count();
-        if char_idx <= char_count + line_width {
-            return (line_nr, char_idx - char_count);
-        }
-        char_count += line_width + 1;
-        last_line_nr = line_nr;
-        last_line = line;
+    indent_at_cursor(&mut cursor, &mut text);
+    assert_eq!(text, "fn main() {\n        ");
+    assert_eq!(cursor, text.len());
+
+    outdent_line(&mut cursor, &mut text);
+    outdent_line(&mut cursor, &mut text);
+    outdent_line(&mut cursor, &mut text);
+    assert_eq!(text, "fn main() {\n");
+    assert_eq!(cursor, text.len());
+}
+
+#[test]
+fn test_indent_selected_lines() {
+    let code = "fn main() {\nlet a = 1;\nlet b = 2;\n}";
+    let selected = |text: &str, cursor, select_from| {
+        text[selection(cursor, Some(select_from)).unwrap()].to_owned()
+    };
+
+    // From the middle of the second line to the start of the last, which is not indented:
+    let mut text = code.to_owned();
+    let mut select_from = text.find("a = 1").unwrap();
+    let mut cursor = text.find('}').unwrap();
+    indent_lines(&mut cursor, &mut select_from, &mut text);
+    assert_eq!(text, "fn main() {\n    let a = 1;\n    let b = 2;\n}");
+    assert_eq!(
+        selected(&text, cursor, select_from),
+        "a = 1;\n    let b = 2;\n"
+    );
+
+    outdent_lines(&mut cursor, &mut select_from, &mut text);
+    assert_eq!(text, code);
+    assert_eq!(selected(&text, cursor, select_from), "a = 1;\nlet b = 2;\n");
+
+    // Selected backwards, from the start of a line:
+    let mut cursor = text.find("let a").unwrap();
+    let mut select_from = text.find("b = 2").unwrap();
+    indent_lines(&mut cursor, &mut select_from, &mut text);
+    indent_lines(&mut cursor, &mut select_from, &mut text);
+    assert_eq!(
+        text,
+        "fn main() {\n        let a = 1;\n        let b = 2;\n}"
+    );
+    assert_eq!(
+        selected(&text, cursor, select_from),
+        "let a = 1;\n        let "
+    );
+    assert_eq!(&text[cursor..], "let a = 1;\n        let b = 2;\n}");
+
+    outdent_lines(&mut cursor, &mut select_from, &mut text);
+    assert_eq!(text, "fn main() {\n    let a = 1;\n    let b = 2;\n}");
+    assert_eq!(selected(&text, cursor, select_from), "let a = 1;\n    let ");
+}
+
+#[test]
+fn test_ime_composition() {
+    let mut ctx = Context::new();
+    let id = Id::new("text_edit");
+    let mut text = "ab".to_owned();
+    let mut run_frame = |events: Vec<Event>| {
+        ctx.begin_frame(RawInput {
+            screen_size: vec2(400.0, 300.0),
+            events,
+            ..Default::default()
+        });
+        ctx.memory().request_kb_focus(id);
+        Area::new("text_edit").show(&ctx, |ui| {
+            ui.add(TextEdit::new(&mut text).id(id));
+        });
+        let text_cursor_rect = ctx.end_frame().0.text_cursor_rect.unwrap();
+        let state = ctx.memory().text_edit[&id].clone();
+        (text.clone(), state, text_cursor_rect)
+    };
+
+    let (_, _, cursor_rect) = run_frame(vec![]);
+
+    let composing = vec![
+        Event::CompositionStart,
+        Event::CompositionUpdate("に".to_owned()),
+    ];
+    let (text, state, preedit_cursor_rect) = run_frame(composing);
+    assert_eq!(text, "ab");
+    assert_eq!(state.preedit.as_deref(), Some("に"));
+    assert_eq!(state.cursor, Some(2));
+    // The cursor is shown after the preedit text, so the candidate window goes there:
+    assert!(preedit_cursor_rect.left() > cursor_rect.left());
+
+    let (text, state, _) = run_frame(vec![Event::CompositionEnd("日本".to_owned())]);
+    assert_eq!(text, "ab日本");
+    assert_eq!(state.preedit, None);
+    assert_eq!(state.cursor, Some(text.len()));
+}
+
+#[test]
+fn test_fewer_suggestions_than_selected() {
+    let key = |key| Event::Key { key, pressed: true };
+    for &accept in &[Key::Enter, Key::Tab] {
+        let mut ctx = Context::new();
+        let id = Id::new("text_edit");
+        let mut text = "a".to_owned();
+        let mut run_frame = |num_candidates: usize, events: Vec<Event>| {
+            let candidates = |_: &str| {
+                let fruits = ["apple", "apricot", "avocado"];
+                fruits[..num_candidates]
+                    .iter()
+                    .map(|&fruit| fruit.to_owned())
+                    .collect()
+            };
+            ctx.begin_frame(RawInput {
+                screen_size: vec2(400.0, 300.0),
+                events,
+                ..Default::default()
+            });
+            ctx.memory().request_kb_focus(id);
+            Area::new("text_edit").show(&ctx, |ui| {
+                let text_edit = TextEdit::new(&mut text).id(id).multiline(false);
+                ui.add(text_edit.suggestions(&candidates));
+            });
+            let _ = ctx.end_frame();
+            let selected_suggestion = ctx.memory().text_edit[&id].selected_suggestion;
+            (text.clone(), selected_suggestion)
+        };
+
+        let (_, selected) = run_frame(3, vec![key(Key::Down), key(Key::Down), key(Key::Down)]);
+        assert_eq!(selected, Some(2));
+
+        // The selected suggestion is gone when accepting it:
+        let (text, _) = run_frame(1, vec![key(accept)]);
+        let expected = if accept == Key::Tab { "apple" } else { "a" };
+        assert_eq!(text, expected);
     }
+}
+
+#[test]
+fn test_arrow_keys_with_suggestions_in_multiline() {
+    let key = |key| Event::Key { key, pressed: true };
+    let mut ctx = Context::new();
+    let id = Id::new("text_edit");
+    let mut text = "x\ny".to_owned();
+    let mut run_frame = |events: Vec<Event>| {
+        let candidates = |_: &str| vec!["x\ny\nz".to_owned()];
+        ctx.begin_frame(RawInput {
+            screen_size: vec2(400.0, 300.0),
+            events,
+            ..Default::default()
+        });
+        ctx.memory().request_kb_focus(id);
+        Area::new("text_edit").show(&ctx, |ui| {
+            ui.add(TextEdit::new(&mut text).id(id).suggestions(&candidates));
+        });
+        let _ = ctx.end_frame();
+        ctx.memory().text_edit[&id].clone()
+    };
+
+    let state = run_frame(vec![]);
+    assert_eq!(state.cursor, Some(3));
 
-    // safe fallback:
-    (last_line_nr, last_line.chars().count())
+    // The arrow keys move between rows, not suggestions:
+    let state = run_frame(vec![key(Key::Up)]);
+    assert_eq!(state.cursor, Some(1));
+    assert_eq!(state.selected_suggestion, None);
+    let state = run_frame(vec![key(Key::Down)]);
+    assert_eq!(state.cursor, Some(3));
+    assert_eq!(state.selected_suggestion, None);
 }
 
-fn char_idx_from_line_col(s: &str, pos: (usize, usize)) -> usize {
-    let mut char_count = 0;
-    for (line_nr, line) in s.split('\n').enumerate() {
-        if line_nr == pos.0 {
-            return char_count + pos.1.min(line.chars().count());
+/// Run a frame with a focused `TextEdit`, returning what `Context::end_frame` does.
+#[cfg(test)]
+fn run_text_edit_frame(
+    ctx: &mut std::sync::Arc<Context>,
+    text_edit: TextEdit<'_>,
+    events: Vec<Event>,
+) -> (Output, Vec<(Rect, PaintCmd)>) {
+    let id = Id::new("text_edit");
+    ctx.begin_frame(RawInput {
+        screen_size: vec2(400.0, 300.0),
+        events,
+        ..Default::default()
+    });
+    ctx.memory().request_kb_focus(id);
+    Area::new("text_edit").show(ctx, |ui| {
+        ui.add(text_edit.id(id));
+    });
+    ctx.end_frame()
+}
+
+#[test]
+fn test_password() {
+    let mut ctx = Context::new();
+    let mut text = "secret".to_owned();
+    for &password in &[false, true] {
+        for event in &[Event::Copy, Event::Cut] {
+            let text_edit = TextEdit::new(&mut text).password(password);
+            let (output, _) = run_text_edit_frame(&mut ctx, text_edit, vec![event.clone()]);
+            if password {
+                assert_eq!(output.copied_text, "");
+            } else {
+                assert_eq!(output.copied_text, "secret");
+            }
         }
-        char_count += line.chars().count() + 1;
+        assert_eq!(text, "secret");
     }
-    char_count
 }
 
-fn line_from_number(s: &str, desired_line_number: usize) -> &str {
-    for (line_nr, line) in s.split('\n').enumerate() {
-        if line_nr == desired_line_number {
-            return line;
-        }
+#[test]
+fn test_hint_text() {
+    let mut ctx = Context::new();
+    let paints_hint = |commands: &[(Rect, PaintCmd)]| {
+        commands
+            .iter()
+            .any(|(_, cmd)| matches!(cmd, PaintCmd::Text { galley, .. } if galley.text == "Search"))
+    };
+    for &(text, shows_hint) in &[("", true), ("a", false)] {
+        let mut text = text.to_owned();
+        let text_edit = TextEdit::new(&mut text).hint_text("Search");
+        let (_, commands) = run_text_edit_frame(&mut ctx, text_edit, vec![]);
+        assert_eq!(paints_hint(&commands), shows_hint);
     }
-    s
+}
+
+#[test]
+fn test_char_limit() {
+    let mut ctx = Context::new();
+    let mut text = "abc".to_owned();
+    let mut type_text = |text: &mut String, typed: &str| {
+        let text_edit = TextEdit::new(text).char_limit(5);
+        let _ = run_text_edit_frame(&mut ctx, text_edit, vec![Event::Text(typed.to_owned())]);
+    };
+
+    // Pasted text is cut off at a char boundary:
+    type_text(&mut text, "dé€f");
+    assert_eq!(text, "abcdé");
+    // And no more fits:
+    type_text(&mut text, "x");
+    assert_eq!(text, "abcdé");
+}
+
+#[test]
+fn test_filter() {
+    let mut ctx = Context::new();
+    let mut text = String::new();
+    let events = vec![
+        Event::Text("1a2".to_owned()),
+        Event::CompositionStart,
+        Event::CompositionUpdate("3b".to_owned()),
+        Event::CompositionEnd("3b".to_owned()),
+    ];
+    let text_edit = TextEdit::new(&mut text).filter(|c| c.is_ascii_digit());
+    let _ = run_text_edit_frame(&mut ctx, text_edit, events);
+    assert_eq!(text, "123");
+}
+
+#[test]
+fn test_word_movement() {
+    let ctrl = Modifiers {
+        ctrl: true,
+        ..Default::default()
+    };
+    let mut text = "let foo_bar = 42;".to_owned();
+    let mut cursor = 0;
+    on_key_press(&mut cursor, &mut text, Key::Right, ctrl);
+    assert_eq!(cursor, 3);
+    on_key_press(&mut cursor, &mut text, Key::Right, ctrl);
+    assert_eq!(cursor, 11);
+    on_key_press(&mut cursor, &mut text, Key::Left, ctrl);
+    assert_eq!(cursor, 4);
+    on_key_press(&mut cursor, &mut text, Key::Delete, ctrl);
+    assert_eq!(text, "let  = 42;");
+    on_key_press(&mut cursor, &mut text, Key::Backspace, ctrl);
+    assert_eq!(text, " = 42;");
+    assert_eq!(cursor, 0);
+}
+
+#[test]
+fn test_find_ignoring_case() {
+    assert_eq!(find_ignoring_case("Open Recent", "rec"), Some(5..8));
+    assert_eq!(find_ignoring_case("ÅÄÖ", "äö"), Some(2..6));
+    assert_eq!(find_ignoring_case("save", "open"), None);
 }
@@ -0,0 +1,430 @@
+//! Laying out (large) texts for `TextEdit` one paragraph at a time.
+
+use std::{
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::Arc,
+};
+
+use ahash::AHashMap;
+
+use crate::{paint::*, *};
+
+/// Laid out paragraphs, keyed by a hash of their text, wrap width and font.
+/// Only the paragraphs used by the latest layout are kept.
+#[derive(Clone, Default)]
+pub(crate) struct ParagraphCache {
+    galleys: AHashMap<u64, Arc<font::Galley>>,
+}
+
+impl std::fmt::Debug for ParagraphCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ParagraphCache({} paragraphs)", self.galleys.len())
+    }
+}
+
+/// What text in `text_style` is laid out with, for keying cached layouts.
+/// The fonts change with `pixels_per_point`, and with the zoom inside a `Scene`.
+pub(crate) fn font_key(fonts: &Fonts, text_style: TextStyle) -> impl Hash {
+    let definitions = fonts.definitions();
+    let (family, size) = definitions.fonts[&text_style];
+    (
+        definitions.pixels_per_point.to_bits(),
+        family,
+        size.to_bits(),
+    )
+}
+
+/// A piece of text ending with a `\n`, or the last piece of the text.
+struct Paragraph {
+    /// Byte range in the laid out text, including the ending `\n`.
+    byte_range: Range<usize>,
+
+    /// Top of the paragraph, relative to the top of the text.
+    y: f32,
+
+    height: f32,
+
+    /// `None` if the paragraph has not been laid out, in which case `height` is just an estimate.
+    galley: Option<Arc<font::Galley>>,
+}
+
+/// A text split into paragraphs, where only the ones we need have been laid out.
+/// All cursors are byte offsets into the text it was laid out from.
+pub(crate) struct TextLayout {
+    paragraphs: Vec<Paragraph>,
+    pub size: Vec2,
+}
+
+impl TextLayout {
+    /// Use an already laid out galley of the whole `text`.
+    pub fn from_galley(text: &str, galley: font::Galley) -> Self {
+        Self {
+            size: galley.size,
+            paragraphs: vec![Paragraph {
+                byte_range: 0..text.len(),
+                y: 0.0,
+                height: galley.size.y,
+                galley: Some(Arc::new(galley)),
+            }],
+        }
+    }
+
+    /// Lay out the text like `Font::layout_multiline`, but one paragraph at a time.
+    ///
+    /// Paragraphs that are in the cache are reused.
+    /// Of the rest, we only lay out those within `visible_y` (relative to the top of the text)
+    /// and the one containing the `cursor`. The others get an estimated height.
+    pub fn from_paragraphs(
+        fonts: &Fonts,
+        text_style: TextStyle,
+        text: &str,
+        wrap_width: f32,
+        visible_y: Range<f32>,
+        cursor: Option<usize>,
+        cache: &mut ParagraphCache,
+    ) -> Self {
+        let font = &fonts[text_style];
+        let line_spacing = font.line_spacing();
+        let char_width = font.layout_single_line_fragment("n")[1];
+
+        let mut old_galleys = std::mem::take(&mut cache.galleys);
+        let mut paragraphs = vec![];
+        let mut size = Vec2::zero();
+
+        let mut start = 0;
+        while start < text.len() {
+            let end = text[start..]
+                .find('\n')
+                .map_or(text.len(), |newline| start + newline + 1);
+            let paragraph_text = &text[start..end];
+
+            let key = {
+                let mut hasher = ahash::AHasher::default();
+                paragraph_text.hash(&mut hasher);
+                wrap_width.to_bits().hash(&mut hasher);
+                font_key(fonts, text_style).hash(&mut hasher);
+                hasher.finish()
+            };
+
+            let mut galley = cache
+                .galleys
+                .get(&key)
+                .cloned()
+                .or_else(|| old_galleys.remove(&key));
+            let mut height = galley.as_ref().map_or(0.0, |galley| galley.size.y);
+            let mut width = galley.as_ref().map_or(0.0, |galley| galley.size.x);
+
+            if galley.is_none() {
+                let has_cursor = matches!(
+                    cursor,
+                    Some(cursor) if start <= cursor && (cursor < end || end == text.len())
+                );
+                let estimated_width = paragraph_text.chars().count() as f32 * char_width;
+                let estimated_rows = (estimated_width / wrap_width).ceil().max(1.0);
+                height = (estimated_rows - 1.0) * line_spacing + font.height();
+                width = estimated_width.min(wrap_width);
+
+                let y_range = size.y..size.y + height;
+                let is_visible = y_range.start <= visible_y.end && visible_y.start <= y_range.end;
+                if is_visible || has_cursor {
+                    let lines = font.layout_paragraph_max_width(paragraph_text, wrap_width);
+                    let new_galley = font::Galley {
+                        text: paragraph_text.to_owned(),
+                        size: vec2(
+                            lines.iter().map(|line| line.max_x()).fold(0.0, f32::max),
+                            lines.last().unwrap().y_max,
+                        ),
+                        lines,
+                        sections: vec![],
+                    };
+                    height = new_galley.size.y;
+                    width = new_galley.size.x;
+                    galley = Some(Arc::new(new_galley));
+                }
+            }
+
+            if let Some(galley) = &galley {
+                cache.galleys.insert(key, galley.clone());
+            }
+
+            paragraphs.push(Paragraph {
+                byte_range: start..end,
+                y: size.y,
+                height,
+                galley,
+            });
+            size.x = size.x.max(width);
+            size.y += height + line_spacing * 0.4; // extra spacing between paragraphs, like `layout_multiline`
+
+            start = end;
+        }
+
+        if text.is_empty() || text.ends_with('\n') {
+            // Add an empty last paragraph for the cursor to go to:
+            let galley = font::Galley {
+                text: String::new(),
+                lines: vec![font::Line {
+                    x_offsets: vec![0.0],
+                    y_min: 0.0,
+                    y_max: line_spacing,
+                    ends_with_newline: false,
+                }],
+                size: vec2(0.0, line_spacing),
+                sections: vec![],
+            };
+            paragraphs.push(Paragraph {
+                byte_range: text.len()..text.len(),
+                y: size.y,
+                height: line_spacing,
+                galley: Some(Arc::new(galley)),
+            });
+            size.y += line_spacing;
+        } else {
+            size.y -= line_spacing * 0.4;
+        }
+
+        Self { paragraphs, size }
+    }
+
+    /// Index of the paragraph containing the cursor.
+    fn paragraph_idx(&self, cursor: usize) -> usize {
+        let idx = self
+            .paragraphs
+            .partition_point(|paragraph| paragraph.byte_range.end <= cursor);
+        idx.min(self.paragraphs.len() - 1)
+    }
+
+    fn num_rows(&self, paragraph_idx: usize) -> usize {
+        self.paragraphs[paragraph_idx]
+            .galley
+            .as_ref()
+            .map_or(1, |galley| galley.lines.len())
+    }
+
+    /// Where to paint the cursor, relative to the top left of the text.
+    pub fn cursor_pos(&self, text: &str, cursor: usize) -> Vec2 {
+        let paragraph = &self.paragraphs[self.paragraph_idx(cursor)];
+        let offset = vec2(0.0, paragraph.y);
+        match &paragraph.galley {
+            Some(galley) => {
+                let char_idx = text[paragraph.byte_range.start..cursor].chars().count();
+                offset + galley.char_start_pos(char_idx)
+            }
+            None => offset,
+        }
+    }
+
+    /// The cursor closest to the given position, relative to the top left of the text.
+    pub fn cursor_at(&self, text: &str, pos: Vec2) -> usize {
+        let idx = self
+            .paragraphs
+            .partition_point(|paragraph| paragraph.y <= pos.y)
+            .saturating_sub(1);
+        self.cursor_in_paragraph(text, idx, pos - vec2(0.0, self.paragraphs[idx].y))
+    }
+
+    fn cursor_in_paragraph(&self, text: &str, paragraph_idx: usize, pos: Vec2) -> usize {
+        let paragraph = &self.paragraphs[paragraph_idx];
+        match &paragraph.galley {
+            Some(galley) => {
+                let char_idx = galley.char_at(pos).char_idx;
+                let paragraph_text = &text[paragraph.byte_range.clone()];
+                paragraph.byte_range.start + byte_index(paragraph_text, char_idx)
+            }
+            None => paragraph.byte_range.start,
+        }
+    }
+
+    /// Move the cursor this many rows down (or up, if negative), staying as close as possible to `x`.
+    /// Moving past the first or last row puts the cursor at the start or end of the text.
+    pub fn move_rows(&self, text: &str, cursor: usize, x: f32, num_rows: isize) -> usize {
+        let mut paragraph_idx = self.paragraph_idx(cursor);
+        let paragraph = &self.paragraphs[paragraph_idx];
+        let mut row = match &paragraph.galley {
+            Some(galley) => {
+                // The row `cursor_pos` puts the cursor on:
+                let mut char_idx = text[paragraph.byte_range.start..cursor].chars().count();
+                let mut row = 0;
+                while row + 1 < galley.lines.len() && galley.lines[row].char_count() <= char_idx {
+                    char_idx -= galley.lines[row].char_count();
+                    row += 1;
+                }
+                row
+            }
+            None => 0,
+        };
+
+        for _ in 0..num_rows.abs() {
+            if num_rows > 0 {
+                if row + 1 < self.num_rows(paragraph_idx) {
+                    row += 1;
+                } else if paragraph_idx + 1 < self.paragraphs.len() {
+                    paragraph_idx += 1;
+                    row = 0;
+                } else {
+                    return text.len();
+                }
+            } else if row > 0 {
+                row -= 1;
+            } else if paragraph_idx > 0 {
+                paragraph_idx -= 1;
+                row = self.num_rows(paragraph_idx) - 1;
+            } else {
+                return 0;
+            }
+        }
+
+        let y = match &self.paragraphs[paragraph_idx].galley {
+            Some(galley) => {
+                let line = &galley.lines[row];
+                (line.y_min + line.y_max) / 2.0
+            }
+            None => 0.0,
+        };
+        self.cursor_in_paragraph(text, paragraph_idx, vec2(x, y))
+    }
+
+    /// Paint a line under the characters in the given byte range.
+    pub fn underline(
+        &self,
+        painter: &Painter,
+        pos: Pos2,
+        text: &str,
+        byte_range: Range<usize>,
+        color: Srgba,
+    ) {
+        self.for_each_line_range(pos, text, byte_range, |line_pos, line, x_range| {
+            let y = painter.round_to_pixel(line_pos.y + line.y_max);
+            let x = |x: f32| line_pos.x + x;
+            painter.line_segment(
+                [pos2(x(x_range.start), y), pos2(x(x_range.end), y)],
+                (1.0, color),
+            );
+        });
+    }
+
+    /// Paint a background behind the characters in the given byte range, e.g. the selection.
+    pub fn highlight(
+        &self,
+        painter: &Painter,
+        pos: Pos2,
+        text: &str,
+        byte_range: Range<usize>,
+        color: Srgba,
+    ) {
+        self.for_each_line_range(pos, text, byte_range, |line_pos, line, x_range| {
+            let rect = Rect::from_min_max(
+                pos2(line_pos.x + x_range.start, line_pos.y + line.y_min),
+                pos2(line_pos.x + x_range.end, line_pos.y + line.y_max),
+            );
+            painter.rect_filled(rect, 0.0, color);
+        });
+    }
+
+    /// Call `f` with the position of each laid out galley, and the x range on each of its lines
+    /// covered by the characters in the given byte range.
+    fn for_each_line_range(
+        &self,
+        pos: Pos2,
+        text: &str,
+        byte_range: Range<usize>,
+        mut f: impl FnMut(Pos2, &font::Line, Range<f32>),
+    ) {
+        for paragraph in &self.paragraphs {
+            let start = byte_range.start.max(paragraph.byte_range.start);
+            let end = byte_range.end.min(paragraph.byte_range.end);
+            if let (Some(galley), true) = (&paragraph.galley, start < end) {
+                let paragraph_start = paragraph.byte_range.start;
+                let start_char = text[paragraph_start..start].chars().count();
+                let end_char = start_char + text[start..end].chars().count();
+                let pos = pos + vec2(0.0, paragraph.y);
+
+                let mut line_start = 0;
+                for line in &galley.lines {
+                    let line_end = line_start + line.char_count();
+                    let start = start_char.max(line_start);
+                    let end = end_char.min(line_end);
+                    if start < end {
+                        let x_range =
+                            line.x_offsets[start - line_start]..line.x_offsets[end - line_start];
+                        f(pos, line, x_range);
+                    }
+                    line_start = line_end;
+                }
+            }
+        }
+    }
+
+    /// Paint the paragraphs that are within the clip rectangle of the painter.
+    pub fn paint(self, painter: &Painter, pos: Pos2, text_style: TextStyle, color: Srgba) {
+        let clip_rect = painter.clip_rect();
+        for paragraph in self.paragraphs {
+            let top = pos.y + paragraph.y;
+            let is_visible = top <= clip_rect.max.y && clip_rect.min.y <= top + paragraph.height;
+            if let (Some(galley), true) = (paragraph.galley, is_visible) {
+                painter.galley(pos2(pos.x, top), galley, text_style, color);
+            }
+        }
+    }
+}
+
+/// Byte offset of the character with the given index, or the length of the text if past the end.
+pub(crate) fn byte_index(text: &str, char_idx: usize) -> usize {
+    text.char_indices()
+        .nth(char_idx)
+        .map_or(text.len(), |(byte_idx, _)| byte_idx)
+}
+
+#[test]
+fn test_paragraph_layout() {
+    let ui = Ui::__test();
+    let text = "Hello world!\n\nThis paragraph is long enough to wrap onto several rows.\nÅäö\n";
+    let galley = ui.fonts()[TextStyle::Body].layout_multiline(text.to_owned(), 100.0);
+    let mut cache = ParagraphCache::default();
+    let layout = TextLayout::from_paragraphs(
+        ui.fonts(),
+        TextStyle::Body,
+        text,
+        100.0,
+        0.0..1000.0,
+        None,
+        &mut cache,
+    );
+    assert!((layout.size - galley.size).length() < 0.01);
+    for (char_idx, (byte_idx, _)) in text.char_indices().enumerate() {
+        let pos = layout.cursor_pos(text, byte_idx);
+        assert!((pos - galley.char_start_pos(char_idx)).length() < 0.01);
+        assert_eq!(layout.cursor_at(text, pos + vec2(0.5, 0.5)), byte_idx);
+    }
+
+    let second_row_start = 19; // "This " fits on the first row
+    assert_eq!(layout.move_rows(text, 14, 0.0, 1), second_row_start);
+    assert_eq!(layout.move_rows(text, second_row_start, 0.0, -1), 14);
+    assert_eq!(layout.move_rows(text, 0, 0.0, 1000), text.len());
+}
+
+#[test]
+fn test_paragraph_cache_follows_fonts() {
+    let fonts = Fonts::from_definitions(FontDefinitions::with_pixels_per_point(1.0));
+    let zoomed_fonts = fonts.scaled(2.0).unwrap();
+    let text = "Hello world!";
+    let mut cache = ParagraphCache::default();
+    let mut layout = |fonts: &Fonts| {
+        let layout = TextLayout::from_paragraphs(
+            fonts,
+            TextStyle::Body,
+            text,
+            100.0,
+            0.0..1000.0,
+            None,
+            &mut cache,
+        );
+        layout.paragraphs[0].galley.clone().unwrap()
+    };
+
+    let galley = layout(&fonts);
+    assert!(Arc::ptr_eq(&layout(&fonts), &galley));
+    // Laid out again with the fonts of a zoomed `Scene`:
+    assert!(!Arc::ptr_eq(&layout(&zoomed_fonts), &galley));
+}
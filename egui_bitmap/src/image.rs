@@ -0,0 +1,93 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use egui::Srgba;
+
+/// An image in memory, e.g. a screenshot of Egui.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row, from the top left.
+    /// sRGBA with premultiplied alpha, just like what you'd read back from the GPU.
+    pub pixels: Vec<Srgba>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: Srgba) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Srgba {
+        assert!(x < self.width && y < self.height);
+        self.pixels[y * self.width + x]
+    }
+
+    /// The largest difference in any channel of any pixel, and how many pixels differ by more than `tolerance`.
+    ///
+    /// Panics if the images have different sizes.
+    pub fn diff(&self, other: &Image, tolerance: u8) -> (u8, usize) {
+        assert_eq!(
+            self.size(),
+            other.size(),
+            "Can't compare images of different sizes"
+        );
+        let mut max_diff = 0;
+        let mut num_different = 0;
+        for (a, b) in self.pixels.iter().zip(&other.pixels) {
+            let diff = a
+                .to_array()
+                .iter()
+                .zip(&b.to_array())
+                .map(|(&a, &b)| a.max(b) - a.min(b))
+                .max()
+                .unwrap_or_default();
+            max_diff = max_diff.max(diff);
+            if diff > tolerance {
+                num_different += 1;
+            }
+        }
+        (max_diff, num_different)
+    }
+
+    /// The pixels are stored as they are, i.e. with premultiplied alpha.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        let bytes: Vec<u8> = self.pixels.iter().flat_map(|p| p.to_array()).collect();
+        writer.write_image_data(&bytes)?;
+        Ok(())
+    }
+
+    /// Load a PNG saved with `save_png`.
+    pub fn load_png(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let decoder = png::Decoder::new(File::open(path)?);
+        let (info, mut reader) = decoder.read_info()?;
+        if info.color_type != png::ColorType::RGBA || info.bit_depth != png::BitDepth::Eight {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Expected an 8-bit RGBA image",
+            ));
+        }
+        let mut bytes = vec![0; info.buffer_size()];
+        reader.next_frame(&mut bytes)?;
+        Ok(Self {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels: bytes
+                .chunks_exact(4)
+                .map(|p| Srgba::new(p[0], p[1], p[2], p[3]))
+                .collect(),
+        })
+    }
+}
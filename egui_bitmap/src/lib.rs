@@ -0,0 +1,23 @@
+//! A slow reference rasterizer for Egui.
+//!
+//! Paints the `PaintJobs` from `egui::Context::tesselate` into an `Image` on the CPU,
+//! the same way `egui_glium` does on the GPU.
+//! Use it for headless rendering, and for comparing screenshots in tests on machines without a GPU.
+//!
+//! ``` ignore
+//! let mut painter = egui_bitmap::Painter::new();
+//! let (output, paint_commands) = egui_ctx.end_frame();
+//! let paint_jobs = egui_ctx.tesselate(paint_commands);
+//! let image = painter.paint_jobs((800, 600), egui_ctx.pixels_per_point(), paint_jobs, &egui_ctx.texture());
+//! image.save_png("screenshot.png")?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![deny(warnings)]
+#![warn(clippy::all)]
+
+mod image;
+mod painter;
+
+pub use image::Image;
+pub use painter::Painter;
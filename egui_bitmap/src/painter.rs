@@ -0,0 +1,265 @@
+use egui::{
+    math::{clamp, pos2, Pos2, Rect},
+    paint::{PaintJobs, Triangles, Vertex},
+    Rgba, Srgba, TextureId,
+};
+
+use crate::Image;
+
+/// A texture with its texels converted to linear space,
+/// like the GPU does when sampling an sRGB texture.
+struct LinearTexture {
+    width: usize,
+    height: usize,
+    texels: Vec<Rgba>,
+}
+
+impl LinearTexture {
+    fn new(width: usize, height: usize, pixels: impl Iterator<Item = Srgba>) -> Self {
+        let texels: Vec<Rgba> = pixels.map(Rgba::from).collect();
+        assert_eq!(texels.len(), width * height);
+        Self {
+            width,
+            height,
+            texels,
+        }
+    }
+
+    /// Bilinear filtering, clamped to the edges.
+    fn sample(&self, uv: Pos2) -> Rgba {
+        let x = uv.x * self.width as f32 - 0.5;
+        let y = uv.y * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let texel = |x: f32, y: f32| {
+            let x = clamp(x, 0.0..=(self.width - 1) as f32) as usize;
+            let y = clamp(y, 0.0..=(self.height - 1) as f32) as usize;
+            self.texels[y * self.width + x]
+        };
+        let top = texel(x0, y0) * (1.0 - tx) + texel(x0 + 1.0, y0) * tx;
+        let bottom = texel(x0, y0 + 1.0) * (1.0 - tx) + texel(x0 + 1.0, y0 + 1.0) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+}
+
+/// Paints Egui on the CPU. Slow, but needs no GPU.
+///
+/// Mimics `egui_glium::Painter`:
+/// vertex colors and textures are converted from sRGB to linear space,
+/// blended with premultiplied alpha in linear space, and converted back to sRGB.
+#[derive(Default)]
+pub struct Painter {
+    user_textures: Vec<LinearTexture>,
+}
+
+impl Painter {
+    pub fn new() -> Painter {
+        Default::default()
+    }
+
+    pub fn new_user_texture(&mut self, size: (usize, usize), pixels: &[Srgba]) -> TextureId {
+        assert_eq!(size.0 * size.1, pixels.len());
+        let id = TextureId::User(self.user_textures.len() as u64);
+        self.user_textures
+            .push(LinearTexture::new(size.0, size.1, pixels.iter().copied()));
+        id
+    }
+
+    /// Paint a frame onto a transparent image of the given size in physical pixels.
+    pub fn paint_jobs(
+        &self,
+        size_in_pixels: (usize, usize),
+        pixels_per_point: f32,
+        jobs: PaintJobs,
+        egui_texture: &egui::Texture,
+    ) -> Image {
+        let egui_texture = LinearTexture::new(
+            egui_texture.width,
+            egui_texture.height,
+            egui_texture.pixels.iter().map(|&a| Srgba::white_alpha(a)),
+        );
+
+        let mut frame_buffer = FrameBuffer {
+            width: size_in_pixels.0,
+            height: size_in_pixels.1,
+            pixels: vec![Rgba::default(); size_in_pixels.0 * size_in_pixels.1],
+        };
+        for (clip_rect, triangles) in &jobs {
+            let texture = match triangles.texture_id {
+                TextureId::Egui => &egui_texture,
+                TextureId::User(id) => {
+                    let id = id as usize;
+                    assert!(id < self.user_textures.len());
+                    &self.user_textures[id]
+                }
+            };
+            frame_buffer.paint_job(pixels_per_point, *clip_rect, triangles, texture);
+        }
+
+        Image {
+            width: frame_buffer.width,
+            height: frame_buffer.height,
+            pixels: frame_buffer.pixels.into_iter().map(Srgba::from).collect(),
+        }
+    }
+}
+
+/// Linear, premultiplied colors, like an sRGB framebuffer on the GPU.
+struct FrameBuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgba>,
+}
+
+impl FrameBuffer {
+    fn paint_job(
+        &mut self,
+        pixels_per_point: f32,
+        clip_rect: Rect,
+        triangles: &Triangles,
+        texture: &LinearTexture,
+    ) {
+        debug_assert!(triangles.is_valid());
+
+        // Round the clip rect to physical pixels, like the scissor test in `egui_glium`:
+        let (width, height) = (self.width as f32, self.height as f32);
+        let clip_min_x = clamp(pixels_per_point * clip_rect.min.x, 0.0..=width);
+        let clip_min_y = clamp(pixels_per_point * clip_rect.min.y, 0.0..=height);
+        let clip_max_x = clamp(pixels_per_point * clip_rect.max.x, clip_min_x..=width);
+        let clip_max_y = clamp(pixels_per_point * clip_rect.max.y, clip_min_y..=height);
+        let clip = [
+            clip_min_x.round() as usize,
+            clip_min_y.round() as usize,
+            clip_max_x.round() as usize,
+            clip_max_y.round() as usize,
+        ];
+
+        for triangle in triangles.indices.chunks_exact(3) {
+            let vertex = |i: u32| {
+                let v = &triangles.vertices[i as usize];
+                Vertex {
+                    pos: pos2(pixels_per_point * v.pos.x, pixels_per_point * v.pos.y),
+                    ..*v
+                }
+            };
+            self.paint_triangle(
+                [
+                    vertex(triangle[0]),
+                    vertex(triangle[1]),
+                    vertex(triangle[2]),
+                ],
+                clip,
+                texture,
+            );
+        }
+    }
+
+    /// Fill the pixels whose centers are within the triangle.
+    ///
+    /// Pixel centers exactly on an edge are only filled if it is a top or left edge,
+    /// so that triangles sharing an edge don't paint the same pixel twice.
+    fn paint_triangle(
+        &mut self,
+        [a, mut b, mut c]: [Vertex; 3],
+        [clip_min_x, clip_min_y, clip_max_x, clip_max_y]: [usize; 4],
+        texture: &LinearTexture,
+    ) {
+        let mut area = edge(a.pos, b.pos, c.pos);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        if area < 0.0 {
+            std::mem::swap(&mut b, &mut c);
+            area = -area;
+        }
+        let colors = [
+            Rgba::from(a.color),
+            Rgba::from(b.color),
+            Rgba::from(c.color),
+        ];
+
+        let min_x = a.pos.x.min(b.pos.x).min(c.pos.x).floor().max(0.0) as usize;
+        let min_y = a.pos.y.min(b.pos.y).min(c.pos.y).floor().max(0.0) as usize;
+        let max_x = a.pos.x.max(b.pos.x).max(c.pos.x).ceil().max(0.0) as usize;
+        let max_y = a.pos.y.max(b.pos.y).max(c.pos.y).ceil().max(0.0) as usize;
+
+        for y in min_y.max(clip_min_y)..max_y.min(clip_max_y) {
+            for x in min_x.max(clip_min_x)..max_x.min(clip_max_x) {
+                let p = pos2(x as f32 + 0.5, y as f32 + 0.5);
+                let w = [
+                    edge(b.pos, c.pos, p),
+                    edge(c.pos, a.pos, p),
+                    edge(a.pos, b.pos, p),
+                ];
+                let inside =
+                    |w: f32, from: Pos2, to: Pos2| w > 0.0 || (w == 0.0 && is_top_left(from, to));
+                if !inside(w[0], b.pos, c.pos)
+                    || !inside(w[1], c.pos, a.pos)
+                    || !inside(w[2], a.pos, b.pos)
+                {
+                    continue;
+                }
+
+                let [w0, w1, w2] = [w[0] / area, w[1] / area, w[2] / area];
+                let color = colors[0] * w0 + colors[1] * w1 + colors[2] * w2;
+                let uv = pos2(
+                    a.uv.x * w0 + b.uv.x * w1 + c.uv.x * w2,
+                    a.uv.y * w0 + b.uv.y * w1 + c.uv.y * w2,
+                );
+                let src = color * texture.sample(uv);
+
+                // Premultiplied alpha, like `egui_glium`:
+                let dst = &mut self.pixels[y * self.width + x];
+                *dst = Rgba::new(
+                    src.r() + dst.r() * (1.0 - src.a()),
+                    src.g() + dst.g() * (1.0 - src.a()),
+                    src.b() + dst.b() * (1.0 - src.a()),
+                    src.a() * (1.0 - dst.a()) + dst.a(),
+                );
+            }
+        }
+    }
+}
+
+/// Positive if `p` is to the right of the line from `a` to `b`, with y pointing down.
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// For a triangle with positive `edge` area, i.e. clockwise on screen.
+fn is_top_left(from: Pos2, to: Pos2) -> bool {
+    let is_top = from.y == to.y && to.x > from.x;
+    let is_left = to.y < from.y;
+    is_top || is_left
+}
+
+#[test]
+fn test_paint_triangles() {
+    let mut triangles = Triangles::default();
+    let rect = Rect::from_min_max(pos2(1.0, 1.0), pos2(3.0, 3.0));
+    let red = Srgba::new(255, 0, 0, 255);
+    triangles.add_colored_rect(rect, red);
+    triangles.add_colored_rect(rect.translate(egui::vec2(4.0, 0.0)), red);
+    let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(6.0, 8.0));
+
+    let texture = egui::Texture {
+        version: 0,
+        width: 1,
+        height: 1,
+        pixels: vec![255],
+    };
+    let image = Painter::new().paint_jobs((8, 4), 1.0, vec![(clip_rect, triangles)], &texture);
+
+    for y in 0..4 {
+        for x in 0..8 {
+            let is_inside_rect =
+                (1..3).contains(&y) && ((1..3).contains(&x) || (5..6).contains(&x));
+            let expected = if is_inside_rect {
+                red
+            } else {
+                Srgba::default()
+            };
+            assert_eq!(image.get(x, y), expected, "x: {}, y: {}", x, y);
+        }
+    }
+}
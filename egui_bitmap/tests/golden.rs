@@ -0,0 +1,99 @@
+//! Pixel regression tests: paint Egui with `egui_bitmap` and compare with the images in `tests/golden/`.
+//!
+//! After an intentional change to how things look, update the images with:
+//! `UPDATE_GOLDEN=1 cargo test -p egui_bitmap`
+
+use std::path::PathBuf;
+
+use egui::{paint::TesselationOptions, vec2, RawInput};
+use egui_bitmap::{Image, Painter};
+
+/// How much a channel of a pixel may differ, e.g. because of floating point differences between machines.
+const TOLERANCE: u8 = 8;
+
+/// How many pixels may differ by more than `TOLERANCE`.
+const MAX_DIFFERENT_PIXELS: usize = 100;
+
+/// The demo windows as they look on the first frames, at a fixed time and with no input.
+fn paint_demo_windows(pixels_per_point: f32) -> Image {
+    let screen_size = vec2(800.0, 600.0);
+    let raw_input = RawInput {
+        screen_size,
+        pixels_per_point: Some(pixels_per_point),
+        time: 0.0,
+        ..Default::default()
+    };
+
+    let mut ctx = egui::Context::new();
+    let mut demo_windows = egui::demos::DemoWindows::default();
+    let mut paint_commands = Default::default();
+    // Windows find their size over the first few frames:
+    for _ in 0..3 {
+        ctx.begin_frame(raw_input.clone());
+        demo_windows.ui(&ctx, &Default::default(), &mut None);
+        paint_commands = ctx.end_frame().1;
+    }
+
+    // Always anti-aliased, so we get the same result in debug and release builds:
+    let options = TesselationOptions {
+        aa_size: 1.0 / pixels_per_point,
+        anti_alias: true,
+        ..Default::default()
+    };
+    let paint_jobs =
+        egui::paint::tessellator::tessellate_paint_commands(paint_commands, options, ctx.fonts());
+
+    let size_in_pixels = (
+        (pixels_per_point * screen_size.x).round() as usize,
+        (pixels_per_point * screen_size.y).round() as usize,
+    );
+    Painter::new().paint_jobs(size_in_pixels, pixels_per_point, paint_jobs, &ctx.texture())
+}
+
+fn assert_matches_golden(name: &str, image: &Image) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    let path = path.with_extension("png");
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        image.save_png(&path).unwrap();
+        return;
+    }
+
+    let golden = Image::load_png(&path).unwrap_or_else(|err| {
+        panic!(
+            "Failed to load {}: {}. Run with UPDATE_GOLDEN=1 to create it.",
+            path.display(),
+            err
+        )
+    });
+    assert_eq!(image.size(), golden.size(), "{} changed size", name);
+
+    let (max_diff, num_different) = image.diff(&golden, TOLERANCE);
+    if num_different > MAX_DIFFERENT_PIXELS {
+        let failed_path = path.with_extension("failed.png");
+        image.save_png(&failed_path).unwrap();
+        panic!(
+            "{} pixels differ from {} by more than {} (at most {}). See {}",
+            num_different,
+            path.display(),
+            TOLERANCE,
+            max_diff,
+            failed_path.display()
+        );
+    }
+}
+
+// The demo says when it is a debug build, so the images are of debug builds:
+#[cfg_attr(not(debug_assertions), ignore)]
+#[test]
+fn demo_windows() {
+    assert_matches_golden("demo_windows", &paint_demo_windows(1.0));
+}
+
+#[cfg_attr(not(debug_assertions), ignore)]
+#[test]
+fn demo_windows_high_dpi() {
+    assert_matches_golden("demo_windows_high_dpi", &paint_demo_windows(2.0));
+}
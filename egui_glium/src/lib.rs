@@ -177,6 +177,17 @@ pub fn handle_output(
         }
     }
 
+    if let Some(text_cursor) = output.text_cursor_rect {
+        // Place the IME candidate window below the text cursor:
+        display
+            .gl_window()
+            .window()
+            .set_ime_position(glutin::dpi::LogicalPosition::new(
+                text_cursor.left() as f64,
+                text_cursor.bottom() as f64,
+            ));
+    }
+
     display
         .gl_window()
         .window()
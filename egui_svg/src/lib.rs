@@ -0,0 +1,472 @@
+//! Export a frame of Egui as an SVG document, for crisp vector screenshots.
+//!
+//! ``` ignore
+//! let (output, paint_commands) = egui_ctx.end_frame();
+//! let svg = egui_svg::svg_from_paint_commands(&paint_commands, egui_ctx.input().screen_size, egui_ctx.fonts());
+//! std::fs::write("screenshot.svg", svg)?;
+//! let paint_jobs = egui_ctx.tesselate(paint_commands); // Paint as usual
+//! ```
+
+#![forbid(unsafe_code)]
+#![deny(warnings)]
+#![warn(clippy::all)]
+
+mod text;
+
+use std::fmt::Write;
+
+use egui::{
+    math::{pos2, Pos2, Rect, Vec2},
+    paint::{CornerRadius, Fill, Fonts, PaintCmd, Stroke, StrokePattern, Triangles},
+    Rgba, Srgba,
+};
+
+/// Turn the paint commands of a frame into an SVG document of the given size (in points).
+///
+/// Each shape becomes an SVG element, clipped to its clip rectangle.
+/// Text becomes the outlines of its glyphs, so the document looks the same without the fonts installed.
+/// Triangle meshes become one polygon per triangle, in the average color of its corners.
+pub fn svg_from_paint_commands(
+    paint_commands: &[(Rect, PaintCmd)],
+    screen_size: Vec2,
+    fonts: &Fonts,
+) -> String {
+    let mut svg = Svg {
+        defs: String::new(),
+        body: String::new(),
+        num_ids: 0,
+        glyphs: text::Glyphs::new(fonts),
+    };
+
+    let screen_rect = Rect::from_min_size(Pos2::default(), screen_size);
+    let mut current_clip_rect = None;
+    for (clip_rect, cmd) in paint_commands {
+        if current_clip_rect != Some(*clip_rect) {
+            if current_clip_rect.is_some() {
+                svg.body += "</g>\n";
+            }
+            let id = svg.new_id();
+            // Clip rectangles are often infinite:
+            let rect = clip_rect.intersect(screen_rect);
+            let rect = Rect::from_min_size(rect.min, rect.size().max(Vec2::default()));
+            writeln!(
+                svg.defs,
+                r#"<clipPath id="{}"><rect{}/></clipPath>"#,
+                id,
+                rect_attributes(rect)
+            )
+            .unwrap();
+            writeln!(svg.body, r#"<g clip-path="url(#{})">"#, id).unwrap();
+            current_clip_rect = Some(*clip_rect);
+        }
+        svg.paint_cmd(cmd);
+    }
+    if current_clip_rect.is_some() {
+        svg.body += "</g>\n";
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n<defs>\n{defs}</defs>\n{body}</svg>\n",
+        w = num(screen_size.x),
+        h = num(screen_size.y),
+        defs = svg.defs,
+        body = svg.body,
+    )
+}
+
+struct Svg {
+    /// Clip paths and gradients
+    defs: String,
+    /// The shapes
+    body: String,
+    num_ids: usize,
+    glyphs: text::Glyphs,
+}
+
+impl Svg {
+    fn new_id(&mut self) -> String {
+        self.num_ids += 1;
+        format!("egui{}", self.num_ids)
+    }
+
+    fn paint_cmd(&mut self, cmd: &PaintCmd) {
+        let body = &mut self.body;
+        match cmd {
+            PaintCmd::Noop => {}
+            PaintCmd::Circle {
+                center,
+                radius,
+                fill,
+                stroke,
+            } => {
+                writeln!(
+                    body,
+                    r#"<circle cx="{}" cy="{}" r="{}"{}{}/>"#,
+                    num(center.x),
+                    num(center.y),
+                    num(*radius),
+                    color_attributes("fill", *fill),
+                    stroke_attributes(*stroke)
+                )
+                .unwrap();
+            }
+            PaintCmd::LineSegment { points, stroke } => {
+                writeln!(
+                    body,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+                    num(points[0].x),
+                    num(points[0].y),
+                    num(points[1].x),
+                    num(points[1].y),
+                    stroke_attributes(*stroke)
+                )
+                .unwrap();
+            }
+            PaintCmd::Path {
+                points,
+                closed,
+                fill,
+                stroke,
+            } => {
+                let d = polygon_path_data(points, *closed);
+                path_element(body, &d, *fill, *stroke, "");
+            }
+            PaintCmd::PolygonWithHoles {
+                points,
+                holes,
+                fill,
+                stroke,
+            } => {
+                let mut d = polygon_path_data(points, true);
+                for hole in holes {
+                    d += &polygon_path_data(hole, true);
+                }
+                path_element(body, &d, *fill, *stroke, r#" fill-rule="evenodd""#);
+            }
+            PaintCmd::Rect {
+                rect,
+                corner_radius,
+                fill,
+                stroke,
+            } => {
+                let fill = match *fill {
+                    Fill::Color(color) => color_attributes("fill", color),
+                    gradient => {
+                        let id = self.new_id();
+                        gradient_def(&mut self.defs, &id, gradient, *rect);
+                        format!(r#" fill="url(#{})""#, id)
+                    }
+                };
+                rect_element(&mut self.body, *rect, *corner_radius, &fill, *stroke);
+            }
+            PaintCmd::QuadraticBezier {
+                points: [a, b, c],
+                closed,
+                fill,
+                stroke,
+            } => {
+                let mut d = format!(
+                    "M{} {}Q{} {} {} {}",
+                    num(a.x),
+                    num(a.y),
+                    num(b.x),
+                    num(b.y),
+                    num(c.x),
+                    num(c.y)
+                );
+                if *closed {
+                    d += "Z";
+                }
+                path_element(body, &d, *fill, *stroke, "");
+            }
+            PaintCmd::CubicBezier {
+                points: [a, b, c, d],
+                closed,
+                fill,
+                stroke,
+            } => {
+                let mut data = format!(
+                    "M{} {}C{} {} {} {} {} {}",
+                    num(a.x),
+                    num(a.y),
+                    num(b.x),
+                    num(b.y),
+                    num(c.x),
+                    num(c.y),
+                    num(d.x),
+                    num(d.y)
+                );
+                if *closed {
+                    data += "Z";
+                }
+                path_element(body, &data, *fill, *stroke, "");
+            }
+            PaintCmd::Text {
+                pos,
+                galley,
+                text_style,
+                color,
+                scale,
+            } => {
+                self.glyphs
+                    .paint_text(body, *pos, galley, *text_style, *color, *scale);
+            }
+            PaintCmd::Triangles(triangles) => triangles_element(body, triangles),
+        }
+    }
+}
+
+/// Round to hundredths of a point, to keep the document small.
+fn num(x: f32) -> f32 {
+    (x * 100.0).round() / 100.0
+}
+
+/// SVG wants colors without premultiplied alpha, and the opacity on its own.
+fn color_attributes(name: &str, color: Srgba) -> String {
+    let rgba = Rgba::from(color);
+    let a = rgba.a();
+    if a <= 0.0 {
+        return format!(r#" {}="none""#, name);
+    }
+    let unmultiplied = Srgba::from(Rgba::new(rgba.r() / a, rgba.g() / a, rgba.b() / a, 1.0));
+    let mut attributes = format!(
+        r#" {}="rgb({},{},{})""#,
+        name,
+        unmultiplied.r(),
+        unmultiplied.g(),
+        unmultiplied.b()
+    );
+    if a < 1.0 {
+        write!(attributes, r#" {}-opacity="{}""#, name, num(a)).unwrap();
+    }
+    attributes
+}
+
+fn stop_element(offset: f32, color: Srgba) -> String {
+    let attributes =
+        color_attributes("stop-color", color).replace("stop-color-opacity", "stop-opacity");
+    format!(r#"<stop offset="{}"{}/>"#, offset, attributes)
+}
+
+fn stroke_attributes(stroke: Stroke) -> String {
+    if stroke.width <= 0.0 || stroke.color.a() == 0 {
+        return String::new();
+    }
+    let mut attributes = color_attributes("stroke", stroke.color);
+    write!(attributes, r#" stroke-width="{}""#, num(stroke.width)).unwrap();
+    match stroke.pattern {
+        StrokePattern::Solid => {}
+        StrokePattern::Dashed { dash, gap } => {
+            write!(
+                attributes,
+                r#" stroke-dasharray="{} {}""#,
+                num(dash),
+                num(gap)
+            )
+            .unwrap();
+        }
+        StrokePattern::Dotted { spacing } => {
+            // Dots of zero length get round caps as wide as the stroke:
+            write!(
+                attributes,
+                r#" stroke-dasharray="0 {}" stroke-linecap="round""#,
+                num(spacing)
+            )
+            .unwrap();
+        }
+    }
+    attributes
+}
+
+fn rect_attributes(rect: Rect) -> String {
+    format!(
+        r#" x="{}" y="{}" width="{}" height="{}""#,
+        num(rect.min.x),
+        num(rect.min.y),
+        num(rect.width()),
+        num(rect.height())
+    )
+}
+
+fn polygon_path_data(points: &[Pos2], closed: bool) -> String {
+    let mut d = String::new();
+    for (i, p) in points.iter().enumerate() {
+        let command = if i == 0 { 'M' } else { 'L' };
+        write!(d, "{}{} {}", command, num(p.x), num(p.y)).unwrap();
+    }
+    if closed && !points.is_empty() {
+        d += "Z";
+    }
+    d
+}
+
+fn path_element(out: &mut String, d: &str, fill: Srgba, stroke: Stroke, extra: &str) {
+    writeln!(
+        out,
+        r#"<path d="{}"{}{}{}/>"#,
+        d,
+        color_attributes("fill", fill),
+        stroke_attributes(stroke),
+        extra
+    )
+    .unwrap();
+}
+
+fn rect_element(
+    out: &mut String,
+    rect: Rect,
+    corner_radius: CornerRadius,
+    fill: &str,
+    stroke: Stroke,
+) {
+    let cr = corner_radius.at_most(rect.width().min(rect.height()) * 0.5);
+    let stroke = stroke_attributes(stroke);
+    if cr.nw == cr.ne && cr.nw == cr.sw && cr.nw == cr.se {
+        let rx = if cr.nw > 0.0 {
+            format!(r#" rx="{}""#, num(cr.nw))
+        } else {
+            String::new()
+        };
+        writeln!(
+            out,
+            r#"<rect{}{}{}{}/>"#,
+            rect_attributes(rect),
+            rx,
+            fill,
+            stroke
+        )
+        .unwrap();
+    } else {
+        let (min, max) = (rect.min, rect.max);
+        let arc =
+            |r: f32, to: Pos2| format!("A{} {} 0 0 1 {} {}", num(r), num(r), num(to.x), num(to.y));
+        let d = format!(
+            "M{} {}H{}{}V{}{}H{}{}V{}{}Z",
+            num(min.x + cr.nw),
+            num(min.y),
+            num(max.x - cr.ne),
+            arc(cr.ne, pos2(max.x, min.y + cr.ne)),
+            num(max.y - cr.se),
+            arc(cr.se, pos2(max.x - cr.se, max.y)),
+            num(min.x + cr.sw),
+            arc(cr.sw, pos2(min.x, max.y - cr.sw)),
+            num(min.y + cr.nw),
+            arc(cr.nw, pos2(min.x + cr.nw, min.y)),
+        );
+        writeln!(out, r#"<path d="{}"{}{}/>"#, d, fill, stroke).unwrap();
+    }
+}
+
+/// Gradients interpolate in linear space, like Egui does.
+fn gradient_def(defs: &mut String, id: &str, fill: Fill, rect: Rect) {
+    match fill {
+        Fill::Color(_) => {}
+        Fill::LinearGradient {
+            direction,
+            from,
+            to,
+        } => {
+            // From one corner to the opposite one, like `Fill::color_at`:
+            let direction = direction.normalized();
+            let half_length = (rect.width() * direction.x).abs() / 2.0
+                + (rect.height() * direction.y).abs() / 2.0;
+            let start = rect.center() - half_length * direction;
+            let end = rect.center() + half_length * direction;
+            writeln!(
+                defs,
+                r#"<linearGradient id="{}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}" color-interpolation="linearRGB">{}{}</linearGradient>"#,
+                id,
+                num(start.x),
+                num(start.y),
+                num(end.x),
+                num(end.y),
+                stop_element(0.0, from),
+                stop_element(1.0, to)
+            )
+            .unwrap();
+        }
+        Fill::RadialGradient { inner, outer } => {
+            // Reaches `outer` in the corners:
+            writeln!(
+                defs,
+                r#"<radialGradient id="{}" cx="0.5" cy="0.5" r="{}" color-interpolation="linearRGB">{}{}</radialGradient>"#,
+                id,
+                num(std::f32::consts::FRAC_1_SQRT_2),
+                stop_element(0.0, inner),
+                stop_element(1.0, outer)
+            )
+            .unwrap();
+        }
+    }
+}
+
+fn triangles_element(out: &mut String, triangles: &Triangles) {
+    if triangles.indices.is_empty() {
+        return;
+    }
+    *out += "<g>\n";
+    for triangle in triangles.indices.chunks_exact(3) {
+        let vertices = [
+            triangles.vertices[triangle[0] as usize],
+            triangles.vertices[triangle[1] as usize],
+            triangles.vertices[triangle[2] as usize],
+        ];
+        let color = vertices
+            .iter()
+            .map(|v| Rgba::from(v.color))
+            .fold(Rgba::default(), |sum, color| sum + color)
+            * (1.0 / 3.0);
+        writeln!(
+            out,
+            r#"<polygon points="{},{} {},{} {},{}"{}/>"#,
+            num(vertices[0].pos.x),
+            num(vertices[0].pos.y),
+            num(vertices[1].pos.x),
+            num(vertices[1].pos.y),
+            num(vertices[2].pos.x),
+            num(vertices[2].pos.y),
+            color_attributes("fill", Srgba::from(color))
+        )
+        .unwrap();
+    }
+    *out += "</g>\n";
+}
+
+#[test]
+fn test_svg_from_paint_commands() {
+    let fonts = Fonts::from_definitions(egui::FontDefinitions::with_pixels_per_point(1.0));
+    let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(50.0, 50.0));
+    let galley = fonts[egui::TextStyle::Body].layout_single_line("Hi".to_owned());
+    let commands = vec![
+        (
+            clip_rect,
+            PaintCmd::Rect {
+                rect: Rect::from_min_max(pos2(1.0, 2.0), pos2(11.0, 12.0)),
+                corner_radius: 2.0.into(),
+                fill: Srgba::new(255, 0, 0, 255).into(),
+                stroke: Stroke::new(1.0, Srgba::new(0, 0, 0, 128)).dashed(2.0, 1.0),
+            },
+        ),
+        (
+            Rect::everything(),
+            PaintCmd::Text {
+                pos: pos2(10.0, 20.0),
+                galley: galley.into(),
+                text_style: egui::TextStyle::Body,
+                color: Srgba::new(0, 0, 255, 255),
+                scale: 1.0,
+            },
+        ),
+    ];
+    let svg = svg_from_paint_commands(&commands, egui::vec2(100.0, 80.0), &fonts);
+
+    assert!(svg.starts_with("<svg "));
+    assert!(svg
+        .contains(r#"<clipPath id="egui1"><rect x="0" y="0" width="50" height="50"/></clipPath>"#));
+    assert!(svg.contains(
+        r#"<clipPath id="egui2"><rect x="0" y="0" width="100" height="80"/></clipPath>"#
+    ));
+    assert!(svg.contains(r#"<rect x="1" y="2" width="10" height="10" rx="2" fill="rgb(255,0,0)" stroke="rgb(0,0,0)" stroke-opacity="0.5" stroke-width="1" stroke-dasharray="2 1"/>"#));
+    assert!(svg.contains(r#"fill="rgb(0,0,255)""#));
+    assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+}
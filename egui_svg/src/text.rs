@@ -0,0 +1,193 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use egui::{
+    math::{pos2, vec2, Pos2, Rect},
+    paint::{
+        font::{Galley, BASELINE_HEIGHT},
+        tessellator::TEXT_OFFSET,
+        FontDefinitions, FontFamily, Fonts, TextStyle,
+    },
+    Srgba,
+};
+use rusttype::{point, Scale};
+
+use crate::{color_attributes, num};
+
+/// Same as in `egui::paint::font`.
+const REPLACEMENT_CHAR: char = '?';
+
+/// The fonts Egui lays out text with, for turning glyphs into outlines.
+pub(crate) struct Glyphs {
+    definitions: FontDefinitions,
+    fonts: BTreeMap<FontFamily, rusttype::Font<'static>>,
+}
+
+impl Glyphs {
+    pub fn new(fonts: &Fonts) -> Self {
+        let definitions = fonts.definitions().clone();
+        let fonts = definitions
+            .ttf_data
+            .iter()
+            .map(|(family, data)| {
+                let font = rusttype::Font::try_from_bytes(data).expect("Error constructing Font");
+                (*family, font)
+            })
+            .collect();
+        Self { definitions, fonts }
+    }
+
+    /// Paint the outlines of the glyphs, one path per run of the same color,
+    /// on top of the backgrounds and underlines of the text sections.
+    pub fn paint_text(
+        &self,
+        out: &mut String,
+        pos: Pos2,
+        galley: &Galley,
+        text_style: TextStyle,
+        color: Srgba,
+        scale: f32,
+    ) {
+        let mut line_start_char = 0;
+        for line in &galley.lines {
+            let line_end_char = line_start_char + line.char_count();
+            for section in &galley.sections {
+                let start = section.char_range.start.max(line_start_char);
+                let end = section.char_range.end.min(line_end_char);
+                if start >= end {
+                    continue;
+                }
+                let min_x = pos.x + scale * line.x_offsets[start - line_start_char];
+                let max_x = pos.x + scale * line.x_offsets[end - line_start_char];
+                if section.background.a() > 0 {
+                    let rect = Rect::from_min_max(
+                        pos2(min_x, pos.y + scale * line.y_min),
+                        pos2(max_x, pos.y + scale * line.y_max),
+                    );
+                    rect_element(out, rect, section.background);
+                }
+                if section.underline {
+                    let y = pos.y + scale * line.y_max;
+                    let rect = Rect::from_min_max(pos2(min_x, y - 1.0), pos2(max_x, y));
+                    rect_element(out, rect, section.color);
+                }
+            }
+            line_start_char = line_end_char;
+        }
+
+        let mut path = PathData::default();
+        let mut path_color = color;
+        let mut section_idx = 0;
+        let mut chars = galley.text.chars().enumerate();
+        for line in &galley.lines {
+            for x_offset in line.x_offsets.iter().take(line.x_offsets.len() - 1) {
+                let (char_idx, c) = chars.next().unwrap();
+
+                let (text_style, color) = if galley.sections.is_empty() {
+                    (text_style, color)
+                } else {
+                    while galley.sections[section_idx].char_range.end <= char_idx {
+                        section_idx += 1;
+                    }
+                    let section = &galley.sections[section_idx];
+                    (section.text_style, section.color)
+                };
+                if color != path_color {
+                    path.paint(out, path_color);
+                    path_color = color;
+                }
+                if c == '\n' || color.a() == 0 {
+                    continue;
+                }
+
+                let (family, size) = self.definitions.fonts[&text_style];
+                let font = &self.fonts[&family];
+                let mut glyph = font.glyph(c);
+                if glyph.id().0 == 0 {
+                    glyph = font.glyph(REPLACEMENT_CHAR);
+                }
+                // Where the tessellator puts the glyph origin:
+                let baseline =
+                    pos + scale * (vec2(*x_offset, line.y_max - BASELINE_HEIGHT) + TEXT_OFFSET);
+                path.baseline = point(baseline.x, baseline.y);
+                glyph
+                    .scaled(Scale::uniform(scale * size))
+                    .build_outline(&mut path);
+            }
+        }
+        path.paint(out, path_color);
+    }
+}
+
+fn rect_element(out: &mut String, rect: Rect, color: Srgba) {
+    writeln!(
+        out,
+        r#"<rect{}{}/>"#,
+        crate::rect_attributes(rect),
+        color_attributes("fill", color)
+    )
+    .unwrap();
+}
+
+/// Glyph outlines, moved from the glyph origin to `baseline`.
+#[derive(Default)]
+struct PathData {
+    d: String,
+    baseline: rusttype::Point<f32>,
+}
+
+impl PathData {
+    fn paint(&mut self, out: &mut String, color: Srgba) {
+        if !self.d.is_empty() {
+            writeln!(
+                out,
+                r#"<path d="{}"{}/>"#,
+                self.d,
+                color_attributes("fill", color)
+            )
+            .unwrap();
+            self.d.clear();
+        }
+    }
+
+    fn point(&mut self, x: f32, y: f32) {
+        write!(
+            self.d,
+            "{} {}",
+            num(self.baseline.x + x),
+            num(self.baseline.y + y)
+        )
+        .unwrap();
+    }
+}
+
+impl rusttype::OutlineBuilder for PathData {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.d += "M";
+        self.point(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.d += "L";
+        self.point(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.d += "Q";
+        self.point(x1, y1);
+        self.d += " ";
+        self.point(x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.d += "C";
+        self.point(x1, y1);
+        self.d += " ";
+        self.point(x2, y2);
+        self.d += " ";
+        self.point(x, y);
+    }
+
+    fn close(&mut self) {
+        self.d += "Z";
+    }
+}
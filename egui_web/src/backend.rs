@@ -172,6 +172,7 @@ impl AppRunner {
         let app_output = integration_context.output;
         let (egui_output, paint_jobs) = self.web_backend.end_frame()?;
         handle_output(&egui_output);
+        update_text_agent(self.canvas_id(), egui_output.text_cursor_rect);
 
         {
             let egui::app::AppOutput {
@@ -199,6 +200,7 @@ pub fn start(app_runner: AppRunner) -> Result<AppRunnerRef, JsValue> {
     let runner_ref = AppRunnerRef(Arc::new(Mutex::new(app_runner)));
     install_canvas_events(&runner_ref)?;
     install_document_events(&runner_ref)?;
+    install_text_agent(&runner_ref)?;
     paint_and_schedule(runner_ref.clone())?;
     Ok(runner_ref)
 }
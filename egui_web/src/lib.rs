@@ -148,6 +148,37 @@ pub fn handle_output(output: &egui::Output) {
     }
 }
 
+/// The id of a hidden `<input>` that has keyboard focus while a `TextEdit` has it.
+/// Browsers only send IME composition events to a focused editable element,
+/// and on mobile focusing it brings up the virtual keyboard.
+const TEXT_AGENT_ID: &str = "egui_text_agent";
+
+/// Focus the text agent and move it to the text cursor, so the IME candidate window shows up there.
+/// Blur it when no `TextEdit` has keyboard focus.
+pub fn update_text_agent(canvas_id: &str, text_cursor_rect: Option<egui::Rect>) -> Option<()> {
+    use wasm_bindgen::JsCast;
+    let document = web_sys::window()?.document()?;
+    let input = document
+        .get_element_by_id(TEXT_AGENT_ID)?
+        .dyn_into::<web_sys::HtmlInputElement>()
+        .ok()?;
+    match text_cursor_rect {
+        Some(rect) => {
+            let canvas_rect = canvas_element(canvas_id)?.get_bounding_client_rect();
+            let style = input.style();
+            let left = canvas_rect.left() as f32 + rect.left();
+            let top = canvas_rect.top() as f32 + rect.top();
+            style.set_property("left", &format!("{}px", left)).ok()?;
+            style.set_property("top", &format!("{}px", top)).ok()?;
+            style
+                .set_property("height", &format!("{}px", rect.height()))
+                .ok()?;
+            input.focus().ok()
+        }
+        None => input.blur().ok(),
+    }
+}
+
 pub fn set_cursor_icon(cursor: egui::CursorIcon) -> Option<()> {
     let document = web_sys::window()?.document()?;
     document
@@ -315,6 +346,68 @@ fn install_document_events(runner_ref: &AppRunnerRef) -> Result<(), JsValue> {
     Ok(())
 }
 
+/// Add the hidden `<input>` that receives IME composition events, see `update_text_agent`.
+fn install_text_agent(runner_ref: &AppRunnerRef) -> Result<(), JsValue> {
+    use wasm_bindgen::JsCast;
+    let document = web_sys::window().unwrap().document().unwrap();
+    let body = document.body().expect("document should have a body");
+    let input = document
+        .create_element("input")?
+        .dyn_into::<web_sys::HtmlInputElement>()?;
+    input.set_id(TEXT_AGENT_ID);
+    input.set_type("text");
+    {
+        // Invisible, and not in the way of the mouse:
+        let style = input.style();
+        style.set_property("position", "fixed")?;
+        style.set_property("width", "1px")?;
+        style.set_property("opacity", "0")?;
+        style.set_property("border", "none")?;
+        style.set_property("padding", "0")?;
+        style.set_property("outline", "none")?;
+        style.set_property("pointer-events", "none")?;
+    }
+
+    {
+        // IME composition, e.g. for CJK input:
+        let runner_ref = runner_ref.clone();
+        let input_clone = input.clone();
+        let closure = Closure::wrap(Box::new(move |event: web_sys::CompositionEvent| {
+            let mut runner_lock = runner_ref.0.lock();
+            let data = event.data().unwrap_or_default();
+            let event = match event.type_().as_str() {
+                "compositionstart" => egui::Event::CompositionStart,
+                "compositionupdate" => egui::Event::CompositionUpdate(data),
+                _ => {
+                    input_clone.set_value("");
+                    egui::Event::CompositionEnd(data)
+                }
+            };
+            runner_lock.web_input.events.push(event);
+            runner_lock.needs_repaint = true;
+        }) as Box<dyn FnMut(_)>);
+        for event_name in &["compositionstart", "compositionupdate", "compositionend"] {
+            input.add_event_listener_with_callback(event_name, closure.as_ref().unchecked_ref())?;
+        }
+        closure.forget();
+    }
+
+    {
+        // Typed text reaches egui through "keydown", so don't let it pile up in the input:
+        let input_clone = input.clone();
+        let closure = Closure::wrap(Box::new(move |event: web_sys::InputEvent| {
+            if !event.is_composing() {
+                input_clone.set_value("");
+            }
+        }) as Box<dyn FnMut(_)>);
+        input.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    body.append_child(&input)?;
+    Ok(())
+}
+
 fn install_canvas_events(runner_ref: &AppRunnerRef) -> Result<(), JsValue> {
     use wasm_bindgen::JsCast;
     let canvas = canvas_element(runner_ref.0.lock().canvas_id()).unwrap();